@@ -0,0 +1,275 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::Vector3f;
+use crate::matrix::Matrix4f;
+
+/// Encodes a vector as little-endian bytes
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::vec3f_to_le_bytes;
+///
+/// let bytes = vec3f_to_le_bytes([1.0, -2.0, 0.5]);
+/// assert_eq!(bytes, [0x00, 0x00, 0x80, 0x3f,
+///                    0x00, 0x00, 0x00, 0xc0,
+///                    0x00, 0x00, 0x00, 0x3f]);
+/// ```
+pub fn vec3f_to_le_bytes(v: Vector3f) -> [u8; 12] {
+    let mut bytes = [0; 12];
+    write_floats(&v, &mut bytes, f32::to_le_bytes);
+    bytes
+}
+
+/// Decodes a vector from little-endian bytes
+///
+/// The bit patterns of the components are preserved, including NaN payloads.
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::{vec3f_from_le_bytes, vec3f_to_le_bytes};
+///
+/// let bytes = [0x00, 0x00, 0x80, 0x3f,
+///              0x00, 0x00, 0x00, 0xc0,
+///              0x00, 0x00, 0x00, 0x3f];
+/// assert_eq!(vec3f_from_le_bytes(bytes), [1.0, -2.0, 0.5]);
+///
+/// // Special values
+/// let v = [f32::from_bits(0x7fc0_1234), f32::INFINITY, f32::NEG_INFINITY];
+/// let decoded = vec3f_from_le_bytes(vec3f_to_le_bytes(v));
+/// assert_eq!(decoded[0].to_bits(), 0x7fc0_1234);
+/// assert_eq!(decoded[1], f32::INFINITY);
+/// assert_eq!(decoded[2], f32::NEG_INFINITY);
+/// ```
+pub fn vec3f_from_le_bytes(bytes: [u8; 12]) -> Vector3f {
+    let mut v = [0.0; 3];
+    read_floats(&bytes, &mut v, f32::from_le_bytes);
+    v
+}
+
+/// Encodes a vector as big-endian bytes
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::{vec3f_to_be_bytes, vec3f_to_le_bytes};
+///
+/// let bytes = vec3f_to_be_bytes([1.0, -2.0, 0.5]);
+/// assert_eq!(bytes, [0x3f, 0x80, 0x00, 0x00,
+///                    0xc0, 0x00, 0x00, 0x00,
+///                    0x3f, 0x00, 0x00, 0x00]);
+///
+/// // Each component has its bytes reversed
+/// let le = vec3f_to_le_bytes([3.25, -7.5, 1e-3]);
+/// let be = vec3f_to_be_bytes([3.25, -7.5, 1e-3]);
+/// for i in 0..3 {
+///     let mut reversed = [0; 4];
+///     reversed.copy_from_slice(&le[i * 4..i * 4 + 4]);
+///     reversed.reverse();
+///     assert_eq!(reversed, be[i * 4..i * 4 + 4]);
+/// }
+/// ```
+pub fn vec3f_to_be_bytes(v: Vector3f) -> [u8; 12] {
+    let mut bytes = [0; 12];
+    write_floats(&v, &mut bytes, f32::to_be_bytes);
+    bytes
+}
+
+/// Decodes a vector from big-endian bytes
+///
+/// The bit patterns of the components are preserved, including NaN payloads.
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::{vec3f_from_be_bytes, vec3f_to_be_bytes};
+///
+/// let bytes = [0x3f, 0x80, 0x00, 0x00,
+///              0xc0, 0x00, 0x00, 0x00,
+///              0x3f, 0x00, 0x00, 0x00];
+/// assert_eq!(vec3f_from_be_bytes(bytes), [1.0, -2.0, 0.5]);
+///
+/// let v = [f32::from_bits(0xffa0_0001), f32::NEG_INFINITY, -0.0];
+/// let decoded = vec3f_from_be_bytes(vec3f_to_be_bytes(v));
+/// assert_eq!(decoded[0].to_bits(), 0xffa0_0001);
+/// assert_eq!(decoded[1], f32::NEG_INFINITY);
+/// assert_eq!(decoded[2].to_bits(), (-0.0f32).to_bits());
+/// ```
+pub fn vec3f_from_be_bytes(bytes: [u8; 12]) -> Vector3f {
+    let mut v = [0.0; 3];
+    read_floats(&bytes, &mut v, f32::from_be_bytes);
+    v
+}
+
+/// Encodes a matrix as little-endian bytes
+///
+/// The elements are written in the order of the matrix, i.e. row by row.
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::{mat4f_from_le_bytes, mat4f_to_le_bytes};
+/// use stones::matrix::mat4_identity;
+///
+/// let bytes = mat4f_to_le_bytes(mat4_identity());
+/// assert_eq!(bytes[0..4], [0x00, 0x00, 0x80, 0x3f]);
+/// assert_eq!(bytes[4..20], [0; 16]);
+/// assert_eq!(bytes[20..24], [0x00, 0x00, 0x80, 0x3f]);
+///
+/// let m = [1.0, 2.0, 3.0, 4.0,
+///          5.0, 6.0, 7.0, 8.0,
+///          9.0, 10.0, 11.0, 12.0,
+///          f32::NAN, f32::INFINITY, -0.0, f32::MIN_POSITIVE];
+/// let decoded = mat4f_from_le_bytes(mat4f_to_le_bytes(m));
+/// for i in 0..16 {
+///     assert_eq!(decoded[i].to_bits(), m[i].to_bits());
+/// }
+/// ```
+pub fn mat4f_to_le_bytes(m: Matrix4f) -> [u8; 64] {
+    let mut bytes = [0; 64];
+    write_floats(&m, &mut bytes, f32::to_le_bytes);
+    bytes
+}
+
+/// Decodes a matrix from little-endian bytes
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::mat4f_from_le_bytes;
+///
+/// let mut bytes = [0; 64];
+/// bytes[12..16].copy_from_slice(&[0x00, 0x00, 0x20, 0x41]);
+/// let m = mat4f_from_le_bytes(bytes);
+/// assert_eq!(m[3], 10.0);
+/// assert_eq!(m.iter().filter(|&&e| e == 0.0).count(), 15);
+/// ```
+pub fn mat4f_from_le_bytes(bytes: [u8; 64]) -> Matrix4f {
+    let mut m = [0.0; 16];
+    read_floats(&bytes, &mut m, f32::from_le_bytes);
+    m
+}
+
+/// Encodes a matrix as big-endian bytes
+///
+/// The elements are written in the order of the matrix, i.e. row by row.
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::{mat4f_from_be_bytes, mat4f_to_be_bytes, mat4f_to_le_bytes};
+///
+/// let m = [1.0, 2.0, 3.0, 4.0,
+///          5.0, 6.0, 7.0, 8.0,
+///          9.0, 10.0, 11.0, 12.0,
+///          13.0, 14.0, 15.0, 16.0];
+/// let be = mat4f_to_be_bytes(m);
+/// assert_eq!(be[12..16], [0x40, 0x80, 0x00, 0x00]);
+/// assert_eq!(mat4f_from_be_bytes(be), m);
+///
+/// // Each element has its bytes reversed
+/// let le = mat4f_to_le_bytes(m);
+/// for (le, be) in le.chunks(4).zip(be.chunks(4)) {
+///     assert!(le.iter().eq(be.iter().rev()));
+/// }
+/// ```
+pub fn mat4f_to_be_bytes(m: Matrix4f) -> [u8; 64] {
+    let mut bytes = [0; 64];
+    write_floats(&m, &mut bytes, f32::to_be_bytes);
+    bytes
+}
+
+/// Decodes a matrix from big-endian bytes
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::mat4f_from_be_bytes;
+///
+/// let mut bytes = [0; 64];
+/// bytes[60..64].copy_from_slice(&[0x41, 0x20, 0x00, 0x00]);
+/// let m = mat4f_from_be_bytes(bytes);
+/// assert_eq!(m[15], 10.0);
+/// assert_eq!(m.iter().filter(|&&e| e == 0.0).count(), 15);
+/// ```
+pub fn mat4f_from_be_bytes(bytes: [u8; 64]) -> Matrix4f {
+    let mut m = [0.0; 16];
+    read_floats(&bytes, &mut m, f32::from_be_bytes);
+    m
+}
+
+/// Appends the little-endian encoding of a slice of vectors to a buffer
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::{vec3f_to_le_bytes, write_vec3f_slice_le};
+///
+/// let mut buffer = vec![0xff];
+/// write_vec3f_slice_le(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]], &mut buffer);
+/// assert_eq!(buffer.len(), 25);
+/// assert_eq!(buffer[0], 0xff);
+/// assert_eq!(buffer[1..13], vec3f_to_le_bytes([1.0, 2.0, 3.0]));
+/// assert_eq!(buffer[13..25], vec3f_to_le_bytes([4.0, 5.0, 6.0]));
+/// ```
+pub fn write_vec3f_slice_le(vectors: &[Vector3f], buffer: &mut Vec<u8>) {
+    buffer.reserve(vectors.len() * 12);
+    for &v in vectors {
+        buffer.extend_from_slice(&vec3f_to_le_bytes(v));
+    }
+}
+
+/// Appends the big-endian encoding of a slice of vectors to a buffer
+///
+/// # Examples
+///
+/// ```
+/// use stones::bytes::{vec3f_to_be_bytes, write_vec3f_slice_be};
+///
+/// let mut buffer = Vec::new();
+/// write_vec3f_slice_be(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]], &mut buffer);
+/// assert_eq!(buffer.len(), 24);
+/// assert_eq!(buffer[0..12], vec3f_to_be_bytes([1.0, 2.0, 3.0]));
+/// assert_eq!(buffer[12..24], vec3f_to_be_bytes([4.0, 5.0, 6.0]));
+/// ```
+pub fn write_vec3f_slice_be(vectors: &[Vector3f], buffer: &mut Vec<u8>) {
+    buffer.reserve(vectors.len() * 12);
+    for &v in vectors {
+        buffer.extend_from_slice(&vec3f_to_be_bytes(v));
+    }
+}
+
+fn write_floats(values: &[f32], bytes: &mut [u8], encode: fn(f32) -> [u8; 4]) {
+    for (value, chunk) in values.iter().zip(bytes.chunks_mut(4)) {
+        chunk.copy_from_slice(&encode(*value));
+    }
+}
+
+fn read_floats(bytes: &[u8], values: &mut [f32], decode: fn([u8; 4]) -> f32) {
+    for (value, chunk) in values.iter_mut().zip(bytes.chunks(4)) {
+        *value = decode([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+}
@@ -0,0 +1,288 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::Vector3;
+use crate::matrix::Matrix4;
+use crate::quaternion::Quaternion;
+use crate::number_traits::Float;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Componentwise differences between two 4x4 matrices
+///
+/// The Display implementation prints the absolute differences and flags the components
+/// whose absolute difference exceeds the threshold, or is NaN.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4Diff<T> {
+    /// Absolute differences |a - b|
+    pub absolute: Matrix4<T>,
+    /// Relative differences |a - b| / max(|a|, |b|), zero where both components are zero
+    pub relative: Matrix4<T>,
+    /// Absolute difference above which a component is flagged
+    pub threshold: T,
+}
+
+/// Componentwise differences between two 3D vectors
+///
+/// See `Mat4Diff` for the meaning of the fields.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vec3Diff<T> {
+    pub absolute: Vector3<T>,
+    pub relative: Vector3<T>,
+    pub threshold: T,
+}
+
+/// Componentwise differences between two quaternions
+///
+/// See `Mat4Diff` for the meaning of the fields. The components are compared as they are:
+/// q and -q represent the same rotation but differ.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuatDiff<T> {
+    pub absolute: Quaternion<T>,
+    pub relative: Quaternion<T>,
+    pub threshold: T,
+}
+
+/// Computes the componentwise differences between two 4x4 matrices
+///
+/// The threshold of the returned diff is zero so every differing component is flagged, it can
+/// be changed to ignore small differences.
+///
+/// # Examples
+///
+/// ```
+/// use stones::diff::{Mat4Diff, mat4_diff};
+/// use stones::matrix::mat4_identity;
+///
+/// // Identical matrices
+/// let diff = mat4_diff(mat4_identity::<f64>(), mat4_identity());
+/// assert_eq!(diff.absolute, [0.0; 16]);
+/// assert_eq!(diff.relative, [0.0; 16]);
+///
+/// // A single perturbed component
+/// let mut perturbed = mat4_identity::<f64>();
+/// perturbed[6] = 0.5;
+/// perturbed[0] = 1.0 + 1e-9;
+/// let diff = Mat4Diff { threshold: 1e-6, ..mat4_diff(mat4_identity(), perturbed) };
+/// for i in 0..16 {
+///     assert_eq!(diff.absolute[i] > 1e-6, i == 6);
+/// }
+///
+/// // The relative difference of a zero baseline is 1 rather than a division by zero
+/// assert_eq!(diff.relative[6], 1.0);
+///
+/// let report = diff.to_string();
+/// assert!(report.contains("0.5*"));
+/// assert!(report.contains("[6] row 1, column 2: absolute 0.5, relative 1"));
+/// assert!(!report.contains("[0]"));
+/// ```
+pub fn mat4_diff<T>(a: Matrix4<T>, b: Matrix4<T>) -> Mat4Diff<T>
+    where T: Float
+{
+    let mut absolute = [T::zero(); 16];
+    let mut relative = [T::zero(); 16];
+    component_diffs(&a, &b, &mut absolute, &mut relative);
+    Mat4Diff { absolute, relative, threshold: T::zero() }
+}
+
+/// Computes the componentwise differences between two 3D vectors
+///
+/// # Examples
+///
+/// ```
+/// use stones::diff::{Vec3Diff, vec3_diff};
+///
+/// let diff = vec3_diff([1.0, 0.0, 4.0], [1.0, 0.0, 3.0]);
+/// assert_eq!(diff.absolute, [0.0, 0.0, 1.0]);
+/// assert_eq!(diff.relative, [0.0, 0.0, 0.25]);
+///
+/// let report = Vec3Diff { threshold: 0.5, ..diff }.to_string();
+/// assert!(report.contains("[2]: absolute 1, relative 0.25"));
+/// assert!(!report.contains("[0]"));
+/// ```
+pub fn vec3_diff<T>(a: Vector3<T>, b: Vector3<T>) -> Vec3Diff<T>
+    where T: Float
+{
+    let mut absolute = [T::zero(); 3];
+    let mut relative = [T::zero(); 3];
+    component_diffs(&a, &b, &mut absolute, &mut relative);
+    Vec3Diff { absolute, relative, threshold: T::zero() }
+}
+
+/// Computes the componentwise differences between two quaternions
+///
+/// # Examples
+///
+/// ```
+/// use stones::diff::quat_diff;
+///
+/// let diff = quat_diff([0.0, 0.0, 0.0, 1.0], [0.0, 0.0, 0.0, -1.0]);
+/// assert_eq!(diff.absolute, [0.0, 0.0, 0.0, 2.0]);
+/// assert_eq!(diff.relative, [0.0, 0.0, 0.0, 2.0]);
+/// assert!(diff.to_string().contains("[3]: absolute 2, relative 2"));
+/// ```
+pub fn quat_diff<T>(a: Quaternion<T>, b: Quaternion<T>) -> QuatDiff<T>
+    where T: Float
+{
+    let mut absolute = [T::zero(); 4];
+    let mut relative = [T::zero(); 4];
+    component_diffs(&a, &b, &mut absolute, &mut relative);
+    QuatDiff { absolute, relative, threshold: T::zero() }
+}
+
+/// Returns the largest absolute difference between the components of two 4x4 matrices
+///
+/// The result is NaN if a component of either matrix is NaN, like the components flagged by
+/// the `Display` of `mat4_diff`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::diff::mat4_max_abs_diff;
+/// use stones::matrix::{mat4_identity, mat4_scaling};
+///
+/// assert_eq!(mat4_max_abs_diff(mat4_identity::<f32>(), mat4_identity()), 0.0);
+/// assert_eq!(mat4_max_abs_diff(mat4_identity(), mat4_scaling([1.0f32, -2.0, 1.5])), 3.0);
+///
+/// let mut with_nan = mat4_scaling([1.0f32, -2.0, 1.5]);
+/// with_nan[3] = f32::NAN;
+/// assert!(mat4_max_abs_diff(mat4_identity(), with_nan).is_nan());
+/// assert!(mat4_max_abs_diff(with_nan, mat4_identity()).is_nan());
+/// ```
+pub fn mat4_max_abs_diff<T>(a: Matrix4<T>, b: Matrix4<T>) -> T
+    where T: Float
+{
+    max_abs_diff(&a, &b)
+}
+
+/// Returns the largest absolute difference between the components of two 3D vectors
+///
+/// # Examples
+///
+/// ```
+/// use stones::diff::vec3_max_abs_diff;
+///
+/// assert_eq!(vec3_max_abs_diff([1.0, 2.0, 3.0], [1.5, 2.0, 1.0]), 2.0);
+/// ```
+pub fn vec3_max_abs_diff<T>(a: Vector3<T>, b: Vector3<T>) -> T
+    where T: Float
+{
+    max_abs_diff(&a, &b)
+}
+
+/// Returns the largest absolute difference between the components of two quaternions
+///
+/// # Examples
+///
+/// ```
+/// use stones::diff::quat_max_abs_diff;
+///
+/// assert_eq!(quat_max_abs_diff([0.0, 0.5, 0.0, 0.5], [0.0, 0.25, 0.0, 1.0]), 0.5);
+/// ```
+pub fn quat_max_abs_diff<T>(a: Quaternion<T>, b: Quaternion<T>) -> T
+    where T: Float
+{
+    max_abs_diff(&a, &b)
+}
+
+impl<T> fmt::Display for Mat4Diff<T>
+    where T: Float + fmt::Display
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_diff(f, &self.absolute, &self.relative, self.threshold, 4)
+    }
+}
+
+impl<T> fmt::Display for Vec3Diff<T>
+    where T: Float + fmt::Display
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_diff(f, &self.absolute, &self.relative, self.threshold, 3)
+    }
+}
+
+impl<T> fmt::Display for QuatDiff<T>
+    where T: Float + fmt::Display
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_diff(f, &self.absolute, &self.relative, self.threshold, 4)
+    }
+}
+
+fn component_diffs<T>(a: &[T], b: &[T], absolute: &mut [T], relative: &mut [T])
+    where T: Float
+{
+    for i in 0..a.len() {
+        let (a_abs, b_abs) = (a[i].abs(), b[i].abs());
+        let scale = if a_abs > b_abs { a_abs } else { b_abs };
+        absolute[i] = (a[i] - b[i]).abs();
+        relative[i] = if scale == T::zero() { T::zero() } else { absolute[i] / scale };
+    }
+}
+
+fn max_abs_diff<T>(a: &[T], b: &[T]) -> T
+    where T: Float
+{
+    a.iter().zip(b).fold(T::zero(), |max, (&a, &b)| {
+        let difference = (a - b).abs();
+        let is_nan = difference.partial_cmp(&difference).is_none();
+        if difference > max || is_nan { difference } else { max }
+    })
+}
+
+/// Tests whether a difference exceeds the threshold, NaN differences always do
+fn exceeds<T>(difference: T, threshold: T) -> bool
+    where T: PartialOrd
+{
+    !matches!(difference.partial_cmp(&threshold), Some(Ordering::Less) | Some(Ordering::Equal))
+}
+
+/// Writes the absolute differences as rows of `columns` components, followed by the details
+/// of each flagged component
+fn write_diff<T>(f: &mut fmt::Formatter, absolute: &[T], relative: &[T], threshold: T, columns: usize) -> fmt::Result
+    where T: Copy + PartialOrd + fmt::Display
+{
+    writeln!(f, "absolute differences, * marks the ones above {}:", threshold)?;
+    for row in absolute.chunks(columns) {
+        let components: Vec<String> = row.iter()
+            .map(|&d| if exceeds(d, threshold) { format!("{}*", d) } else { format!("{}", d) })
+            .collect();
+        writeln!(f, "    {}", components.join(" "))?;
+    }
+
+    for (i, (&a, &r)) in absolute.iter().zip(relative).enumerate() {
+        if !exceeds(a, threshold) {
+            continue;
+        }
+
+        if columns < absolute.len() {
+            write!(f, "[{}] row {}, column {}", i, i / columns, i % columns)?;
+        } else {
+            write!(f, "[{}]", i)?;
+        }
+        writeln!(f, ": absolute {}, relative {}", a, r)?;
+    }
+
+    Ok(())
+}
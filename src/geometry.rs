@@ -0,0 +1,2082 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::{Vector2, Vector2i, Vector3, vec3_add, vec3_cross, vec3_dot, vec3_mul, vec3_sub};
+use crate::matrix::{Matrix3, Matrix4, mat3_eigen_symmetric, mat3_mul, mat3_transpose};
+use crate::number_traits::{Float, Zero};
+use std::collections::HashMap;
+use std::ops::{Add, Sub, Mul};
+
+/// Plane defined by the points x satisfying dot(normal, x) + distance = 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane<T> {
+    pub normal: Vector3<T>,
+    pub distance: T,
+}
+
+/// Half-line starting at origin, the points of the ray are origin + t * direction for t >= 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray<T> {
+    pub origin: Vector3<T>,
+    pub direction: Vector3<T>,
+}
+
+/// Axis-aligned bounding box defined by its minimum and maximum corners
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb<T> {
+    pub min: Vector3<T>,
+    pub max: Vector3<T>,
+}
+
+/// View frustum defined by six planes whose normals point inside
+///
+/// The planes are ordered left, right, bottom, top, near and far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum<T> {
+    pub planes: [Plane<T>; 6],
+}
+
+/// 2D line defined by the homogeneous coefficients [a, b, c] of the points satisfying
+/// a * x + b * y + c = 0
+pub type Line2<T> = [T; 3];
+
+/// Computes the centroid and the covariance matrix of a set of points
+///
+/// The covariance is the population covariance, i.e. normalized by the number of points.
+/// An empty set of points yields a zero centroid and a zero covariance matrix.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::points_covariance;
+///
+/// let points = [[1.0, 0.0, 2.0], [3.0, 0.0, 2.0], [1.0, 4.0, 2.0], [3.0, 4.0, 2.0]];
+/// let (centroid, covariance) = points_covariance(&points);
+/// assert_eq!(centroid, [2.0, 2.0, 2.0]);
+/// assert_eq!(covariance, [1.0, 0.0, 0.0,
+///                         0.0, 4.0, 0.0,
+///                         0.0, 0.0, 0.0]);
+/// ```
+pub fn points_covariance<T>(points: &[Vector3<T>]) -> (Vector3<T>, Matrix3<T>)
+    where T: Float
+{
+    if points.is_empty() {
+        return ([T::zero(); 3], [T::zero(); 9]);
+    }
+
+    let count = T::from_f64(points.len() as f64);
+    let sum = points.iter().fold([T::zero(); 3], |acc, p| [acc[0] + p[0], acc[1] + p[1], acc[2] + p[2]]);
+    let centroid = [sum[0] / count, sum[1] / count, sum[2] / count];
+
+    let mut covariance = [T::zero(); 9];
+    for p in points {
+        let d = [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]];
+        for row in 0..3 {
+            for column in 0..3 {
+                covariance[row * 3 + column] = covariance[row * 3 + column] + d[row] * d[column];
+            }
+        }
+    }
+
+    for c in covariance.iter_mut() {
+        *c = *c / count;
+    }
+
+    (centroid, covariance)
+}
+
+/// Computes the principal axes of a set of points
+///
+/// Returns the centroid, the principal axes as the columns of a rotation matrix, i.e. with a
+/// positive determinant, and the variance along each of them, sorted in descending order.
+/// Returns None if there are less than 2 points.
+///
+/// # Examples
+///
+/// Axis-aligned box
+/// ```
+/// use stones::geometry::points_principal_axes;
+///
+/// let mut points = Vec::new();
+/// for &x in &[-3.0, 3.0] {
+///     for &y in &[-1.0, 1.0] {
+///         for &z in &[-2.0, 2.0] {
+///             points.push([x + 1.0, y, z]);
+///         }
+///     }
+/// }
+///
+/// let (centroid, axes, variances) = points_principal_axes(&points).unwrap();
+/// assert_eq!(centroid, [1.0, 0.0, 0.0]);
+/// assert!((variances[0] - 9.0f64).abs() < 1e-12);
+/// assert!((variances[1] - 4.0f64).abs() < 1e-12);
+/// assert!((variances[2] - 1.0f64).abs() < 1e-12);
+///
+/// // Columns are ±X, ±Z and ±Y
+/// assert!((axes[0].abs() - 1.0).abs() < 1e-12);
+/// assert!((axes[7].abs() - 1.0).abs() < 1e-12);
+/// assert!((axes[5].abs() - 1.0).abs() < 1e-12);
+/// ```
+///
+/// Rotated box
+/// ```
+/// use stones::geometry::points_principal_axes;
+///
+/// let (sin, cos) = 0.5f64.sin_cos();
+/// let rotation = [cos, -sin, 0.0,
+///                 sin, cos, 0.0,
+///                 0.0, 0.0, 1.0];
+///
+/// let mut points = Vec::new();
+/// for &x in &[-3.0, 3.0] {
+///     for &y in &[-2.0, 2.0] {
+///         for &z in &[-1.0, 1.0] {
+///             points.push([rotation[0] * x + rotation[1] * y,
+///                          rotation[3] * x + rotation[4] * y,
+///                          z]);
+///         }
+///     }
+/// }
+///
+/// let (_, axes, _) = points_principal_axes(&points).unwrap();
+/// for column in 0..3 {
+///     let dot = (0..3).fold(0.0, |acc, row| acc + axes[row * 3 + column] * rotation[row * 3 + column]);
+///     assert!((dot.abs() - 1.0).abs() < 1e-9);
+/// }
+/// ```
+///
+/// Not enough points
+/// ```
+/// use stones::geometry::points_principal_axes;
+///
+/// assert_eq!(points_principal_axes(&[[1.0, 2.0, 3.0]]), None);
+/// ```
+pub fn points_principal_axes<T>(points: &[Vector3<T>]) -> Option<(Vector3<T>, Matrix3<T>, Vector3<T>)>
+    where T: Float
+{
+    if points.len() < 2 {
+        return None;
+    }
+
+    let (centroid, covariance) = points_covariance(points);
+    let (variances, mut axes) = mat3_eigen_symmetric(covariance);
+
+    let x = [axes[0], axes[3], axes[6]];
+    let y = [axes[1], axes[4], axes[7]];
+    let z = [axes[2], axes[5], axes[8]];
+    if vec3_dot(vec3_cross(x, y), z) < T::zero() {
+        axes[2] = -axes[2];
+        axes[5] = -axes[5];
+        axes[8] = -axes[8];
+    }
+
+    Some((centroid, axes, variances))
+}
+
+/// Computes the plane best fitting a set of points in the least-squares sense
+///
+/// The normal of the returned plane is the unit axis along which the points vary the least,
+/// its orientation is unspecified. Returns None if there are less than 3 points or if the
+/// points are collinear or coincident, as the plane isn't unique in those cases.
+///
+/// # Examples
+///
+/// Coplanar points
+/// ```
+/// use stones::geometry::fit_plane;
+///
+/// // Points of the plane z = 2
+/// let points: [[f64; 3]; 4] = [[0.0, 0.0, 2.0], [1.0, 0.0, 2.0], [0.0, 3.0, 2.0], [-2.0, 1.0, 2.0]];
+/// let plane = fit_plane(&points).unwrap();
+/// for p in &points {
+///     let d = plane.normal[0] * p[0] + plane.normal[1] * p[1] + plane.normal[2] * p[2] + plane.distance;
+///     assert!(d.abs() < 1e-12);
+/// }
+/// assert!((plane.normal[2].abs() - 1.0).abs() < 1e-12);
+/// ```
+///
+/// Noisy points
+/// ```
+/// use stones::geometry::fit_plane;
+///
+/// // Points around the plane x + y + z = 1
+/// let mut points = Vec::new();
+/// for i in 0..10 {
+///     for j in 0..10 {
+///         let (u, v) = (i as f64, j as f64);
+///         let noise = 0.01 * ((i * 7 + j * 13) % 5) as f64 - 0.02;
+///         points.push([u, v, 1.0 - u - v + noise]);
+///     }
+/// }
+///
+/// let plane = fit_plane(&points).unwrap();
+/// let expected = 1.0 / 3.0f64.sqrt();
+/// for &n in &plane.normal {
+///     assert!((n.abs() - expected).abs() < 1e-3);
+/// }
+/// ```
+///
+/// Degenerate inputs
+/// ```
+/// use stones::geometry::fit_plane;
+///
+/// assert_eq!(fit_plane(&[[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]]), None);
+/// assert_eq!(fit_plane(&[[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, 2.0, 2.0]]), None);
+/// ```
+pub fn fit_plane<T>(points: &[Vector3<T>]) -> Option<Plane<T>>
+    where T: Float
+{
+    if points.len() < 3 {
+        return None;
+    }
+
+    let (centroid, axes, variances) = points_principal_axes(points)?;
+    if variances[1] <= variances[0] * T::epsilon() {
+        return None;
+    }
+
+    let normal = [axes[2], axes[5], axes[8]];
+    Some(Plane {
+        normal,
+        distance: -vec3_dot(normal, centroid),
+    })
+}
+
+/// Computes the line best fitting a set of points in the least-squares sense
+///
+/// Returns a point of the line, the centroid of the points, and its unit direction, whose
+/// orientation is unspecified. Returns None if there are less than 2 points or if all the
+/// points are coincident.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::fit_line;
+///
+/// let points = [[1.0, 1.0, 1.0], [2.0, 3.0, 1.0], [3.0, 5.0, 1.0]];
+/// let (point, direction) = fit_line(&points).unwrap();
+/// assert_eq!(point, [2.0, 3.0, 1.0]);
+///
+/// let expected = [1.0 / 5.0f64.sqrt(), 2.0 / 5.0f64.sqrt(), 0.0];
+/// let dot = direction[0] * expected[0] + direction[1] * expected[1] + direction[2] * expected[2];
+/// assert!((dot.abs() - 1.0).abs() < 1e-12);
+///
+/// assert_eq!(fit_line(&[[1.0, 1.0, 1.0]]), None);
+/// assert_eq!(fit_line(&[[1.0, 1.0, 1.0], [1.0, 1.0, 1.0]]), None);
+/// ```
+pub fn fit_line<T>(points: &[Vector3<T>]) -> Option<(Vector3<T>, Vector3<T>)>
+    where T: Float
+{
+    let (centroid, axes, variances) = points_principal_axes(points)?;
+    if variances[0] <= T::zero() {
+        return None;
+    }
+
+    Some((centroid, [axes[0], axes[3], axes[6]]))
+}
+
+/// Computes the 2D line best fitting a set of points in the least-squares sense
+///
+/// Returns a point of the line, the centroid of the points, and its unit direction, whose
+/// orientation is unspecified. Returns None if there are less than 2 points or if all the
+/// points are coincident.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::fit_line_2d;
+///
+/// // Points of the vertical line x = 2
+/// let points: [[f64; 2]; 3] = [[2.0, -1.0], [2.0, 0.0], [2.0, 4.0]];
+/// let (point, direction) = fit_line_2d(&points).unwrap();
+/// assert_eq!(point, [2.0, 1.0]);
+/// assert!(direction[0].abs() < 1e-12);
+/// assert!((direction[1].abs() - 1.0).abs() < 1e-12);
+///
+/// assert_eq!(fit_line_2d(&[[1.0, 1.0]]), None);
+/// ```
+pub fn fit_line_2d<T>(points: &[Vector2<T>]) -> Option<(Vector2<T>, Vector2<T>)>
+    where T: Float
+{
+    if points.len() < 2 {
+        return None;
+    }
+
+    let count = T::from_f64(points.len() as f64);
+    let sum = points.iter().fold([T::zero(); 2], |acc, p| [acc[0] + p[0], acc[1] + p[1]]);
+    let centroid = [sum[0] / count, sum[1] / count];
+
+    let (mut xx, mut xy, mut yy) = (T::zero(), T::zero(), T::zero());
+    for p in points {
+        let d = [p[0] - centroid[0], p[1] - centroid[1]];
+        xx = xx + d[0] * d[0];
+        xy = xy + d[0] * d[1];
+        yy = yy + d[1] * d[1];
+    }
+
+    if xx + yy <= T::zero() {
+        return None;
+    }
+
+    let half_angle = (xy + xy).atan2(xx - yy) / T::from_f64(2.0);
+    Some((centroid, [half_angle.cos(), half_angle.sin()]))
+}
+
+/// Computes the rigid transformation best aligning a set of points onto another
+///
+/// Returns the rotation R and the translation t minimizing the sum of the squared distances
+/// between R * from[i] + t and to[i] using the Kabsch algorithm. The rotation is always
+/// proper, i.e. its determinant is +1, even if a reflection would align the points better.
+/// Returns None if the sets have different lengths, contain less than 3 points or if the
+/// points are collinear.
+///
+/// # Examples
+///
+/// Exact alignment
+/// ```
+/// use stones::geometry::kabsch_alignment;
+/// use stones::matrix::mat3_mul;
+/// use stones::vector::{vec3_cross, vec3_dot};
+///
+/// let (sin_a, cos_a) = 0.7f64.sin_cos();
+/// let (sin_b, cos_b) = (-1.9f64).sin_cos();
+/// let rotation = mat3_mul([cos_a, -sin_a, 0.0,
+///                          sin_a, cos_a, 0.0,
+///                          0.0, 0.0, 1.0],
+///                         [1.0, 0.0, 0.0,
+///                          0.0, cos_b, -sin_b,
+///                          0.0, sin_b, cos_b]);
+/// let translation = [4.0, -2.0, 0.5];
+///
+/// let from = [[1.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 3.0], [1.0, -1.0, 2.0], [-2.0, 1.0, 1.0]];
+/// let to: Vec<[f64; 3]> = from.iter().map(|p| {
+///     let mut q = translation;
+///     for row in 0..3 {
+///         for column in 0..3 {
+///             q[row] += rotation[row * 3 + column] * p[column];
+///         }
+///     }
+///     q
+/// }).collect();
+///
+/// let (r, t) = kabsch_alignment(&from, &to).unwrap();
+/// for i in 0..9 {
+///     assert!((r[i] - rotation[i]).abs() < 1e-12);
+/// }
+/// for i in 0..3 {
+///     assert!((t[i] - translation[i]).abs() < 1e-12);
+/// }
+///
+/// let determinant = vec3_dot(vec3_cross([r[0], r[3], r[6]], [r[1], r[4], r[7]]), [r[2], r[5], r[8]]);
+/// assert!((determinant - 1.0).abs() < 1e-12);
+/// ```
+///
+/// Noisy correspondences
+/// ```
+/// use stones::geometry::kabsch_alignment;
+///
+/// let from = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+/// // Rotation of 90° around Z followed by a translation of [1, 2, 3], with noise
+/// let to: [[f64; 3]; 4] = [[1.001, 2.0, 3.0], [1.0, 2.999, 3.0], [0.0, 2.0, 3.002], [0.998, 2.0, 4.0]];
+///
+/// let (r, t) = kabsch_alignment(&from, &to).unwrap();
+/// let expected = [0.0, -1.0, 0.0,
+///                 1.0, 0.0, 0.0,
+///                 0.0, 0.0, 1.0];
+/// for i in 0..9 {
+///     assert!((r[i] - expected[i]).abs() < 1e-2);
+/// }
+/// assert!((t[0] - 1.0).abs() < 1e-2);
+/// assert!((t[1] - 2.0).abs() < 1e-2);
+/// assert!((t[2] - 3.0).abs() < 1e-2);
+/// ```
+///
+/// Mirrored points
+/// ```
+/// use stones::geometry::kabsch_alignment;
+/// use stones::vector::{vec3_cross, vec3_dot};
+///
+/// let from = [[1.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 3.0], [1.0, 1.0, 1.0]];
+/// let to: Vec<[f64; 3]> = from.iter().map(|p| [p[0], p[1], -p[2]]).collect();
+///
+/// let (r, _) = kabsch_alignment(&from, &to).unwrap();
+/// let determinant = vec3_dot(vec3_cross([r[0], r[3], r[6]], [r[1], r[4], r[7]]), [r[2], r[5], r[8]]);
+/// assert!((determinant - 1.0).abs() < 1e-12);
+/// ```
+///
+/// Degenerate inputs
+/// ```
+/// use stones::geometry::kabsch_alignment;
+///
+/// let collinear = [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, 2.0, 2.0]];
+/// assert_eq!(kabsch_alignment(&collinear, &collinear), None);
+///
+/// let triangle = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+/// assert_eq!(kabsch_alignment(&triangle, &triangle[..2]), None);
+/// assert_eq!(kabsch_alignment(&triangle[..2], &triangle[..2]), None);
+/// ```
+pub fn kabsch_alignment<T>(from: &[Vector3<T>], to: &[Vector3<T>]) -> Option<(Matrix3<T>, Vector3<T>)>
+    where T: Float
+{
+    if from.len() != to.len() || from.len() < 3 {
+        return None;
+    }
+
+    let (from_centroid, _) = points_covariance(from);
+    let (to_centroid, _) = points_covariance(to);
+
+    // Cross-covariance matrix H = sum((to[i] - to_centroid) * (from[i] - from_centroid)^T)
+    let mut h = [T::zero(); 9];
+    for (p, q) in from.iter().zip(to.iter()) {
+        let p = vec3_sub(*p, from_centroid);
+        let q = vec3_sub(*q, to_centroid);
+        for row in 0..3 {
+            for column in 0..3 {
+                h[row * 3 + column] = h[row * 3 + column] + q[row] * p[column];
+            }
+        }
+    }
+
+    // H = U * S * V^T, where V and S^2 are given by the eigendecomposition of H^T * H
+    let (values, v) = mat3_eigen_symmetric(mat3_mul(mat3_transpose(h), h));
+    if values[1] <= values[0] * T::epsilon() {
+        return None;
+    }
+
+    let hv = mat3_mul(h, v);
+    let u0 = vec3_mul([hv[0], hv[3], hv[6]], T::one() / values[0].sqrt());
+    let u1 = [hv[1], hv[4], hv[7]];
+    let u1 = vec3_sub(u1, vec3_mul(u0, vec3_dot(u0, u1)));
+    let u1 = vec3_mul(u1, T::one() / vec3_dot(u1, u1).sqrt());
+
+    // Making both U and V proper rotations yields the optimal proper rotation U * V^T
+    let u = [u0, u1, vec3_cross(u0, u1)];
+    let v0 = [v[0], v[3], v[6]];
+    let v1 = [v[1], v[4], v[7]];
+    let v = [v0, v1, vec3_cross(v0, v1)];
+
+    let mut rotation = [T::zero(); 9];
+    for row in 0..3 {
+        for column in 0..3 {
+            rotation[row * 3 + column] = (0..3).fold(T::zero(), |acc, k| acc + u[k][row] * v[k][column]);
+        }
+    }
+
+    let mut translation = to_centroid;
+    for (row, t) in translation.iter_mut().enumerate() {
+        *t = *t - (0..3).fold(T::zero(), |acc, k| acc + rotation[row * 3 + k] * from_centroid[k]);
+    }
+
+    Some((rotation, translation))
+}
+
+/// Computes the intersection of a ray and a capped cylinder
+///
+/// The cylinder extends from `base` over `height` along `axis`, which doesn't need to be
+/// normalized. Returns the smallest non-negative t for which the ray hits the surface of
+/// the cylinder, so if the ray starts inside the cylinder, this is where it exits.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{Ray, ray_cylinder_intersection};
+///
+/// let intersect = |origin, direction| {
+///     ray_cylinder_intersection(Ray { origin, direction }, [0.0, 0.0, 0.0], [0.0, 0.0, 3.0], 1.0, 2.0)
+/// };
+///
+/// // Curved surface
+/// assert_eq!(intersect([-5.0, 0.0, 1.0], [1.0, 0.0, 0.0]), Some(4.0));
+/// assert_eq!(intersect([-5.0, 0.0, 1.0], [2.0, 0.0, 0.0]), Some(2.0));
+/// assert_eq!(intersect([-5.0, 0.0, 3.0], [1.0, 0.0, 0.0]), None);
+///
+/// // Caps
+/// assert_eq!(intersect([0.5, 0.0, 5.0], [0.0, 0.0, -1.0]), Some(3.0));
+/// assert_eq!(intersect([0.0, 0.0, -1.0], [0.0, 0.0, 1.0]), Some(1.0));
+///
+/// // Parallel to the axis, outside of the cylinder
+/// assert_eq!(intersect([2.0, 0.0, 5.0], [0.0, 0.0, -1.0]), None);
+///
+/// // Starting inside
+/// assert_eq!(intersect([0.0, 0.0, 1.0], [1.0, 0.0, 0.0]), Some(1.0));
+/// assert_eq!(intersect([0.0, 0.0, 1.5], [0.0, 0.0, 1.0]), Some(0.5));
+///
+/// // Grazing the curved surface
+/// assert_eq!(intersect([-5.0, 1.0, 1.0], [1.0, 0.0, 0.0]), Some(5.0));
+///
+/// // Pointing away
+/// assert_eq!(intersect([-5.0, 0.0, 1.0], [-1.0, 0.0, 0.0]), None);
+/// ```
+pub fn ray_cylinder_intersection<T>(ray: Ray<T>, base: Vector3<T>, axis: Vector3<T>, radius: T, height: T) -> Option<T>
+    where T: Float
+{
+    let axis = vec3_mul(axis, T::one() / vec3_dot(axis, axis).sqrt());
+    let offset = vec3_sub(ray.origin, base);
+    let (offset_along, direction_along) = (vec3_dot(offset, axis), vec3_dot(ray.direction, axis));
+    let offset_across = vec3_sub(offset, vec3_mul(axis, offset_along));
+    let direction_across = vec3_sub(ray.direction, vec3_mul(axis, direction_along));
+    let radius_squared = radius * radius;
+
+    let mut nearest = None;
+
+    let side = solve_quadratic(
+        vec3_dot(direction_across, direction_across),
+        vec3_dot(offset_across, direction_across),
+        vec3_dot(offset_across, offset_across) - radius_squared
+    );
+    if let Some((t0, t1)) = side {
+        for &t in &[t0, t1] {
+            let h = offset_along + t * direction_along;
+            if h >= T::zero() && h <= height {
+                nearest = nearest_hit(nearest, t);
+            }
+        }
+    }
+
+    if direction_along != T::zero() {
+        for &cap in &[T::zero(), height] {
+            let t = (cap - offset_along) / direction_along;
+            let p = vec3_add(offset_across, vec3_mul(direction_across, t));
+            if vec3_dot(p, p) <= radius_squared {
+                nearest = nearest_hit(nearest, t);
+            }
+        }
+    }
+
+    nearest
+}
+
+/// Computes the intersection of a ray and a capsule
+///
+/// The capsule is the set of points within `radius` of the segment [a, b]. Returns the
+/// smallest non-negative t for which the ray hits the surface of the capsule, so if the ray
+/// starts inside the capsule, this is where it exits.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{Ray, ray_capsule_intersection};
+///
+/// let intersect = |origin, direction| {
+///     ray_capsule_intersection(Ray { origin, direction }, [0.0, 0.0, 0.0], [0.0, 0.0, 2.0], 1.0)
+/// };
+///
+/// // Curved surface
+/// assert_eq!(intersect([-5.0, 0.0, 1.0], [1.0, 0.0, 0.0]), Some(4.0));
+///
+/// // Hemispherical ends
+/// assert_eq!(intersect([0.0, 0.0, 5.0], [0.0, 0.0, -1.0]), Some(2.0));
+/// assert_eq!(intersect([0.0, 0.0, -5.0], [0.0, 0.0, 1.0]), Some(4.0));
+///
+/// // Parallel to the axis, outside of the capsule
+/// assert_eq!(intersect([2.0, 0.0, 5.0], [0.0, 0.0, -1.0]), None);
+///
+/// // Starting inside
+/// assert_eq!(intersect([0.0, 0.0, 1.0], [0.0, 0.0, 1.0]), Some(2.0));
+/// assert_eq!(intersect([0.0, 0.0, 1.0], [0.0, -1.0, 0.0]), Some(1.0));
+///
+/// // Grazing the curved surface and an end
+/// assert_eq!(intersect([-5.0, 1.0, 1.0], [1.0, 0.0, 0.0]), Some(5.0));
+/// assert_eq!(intersect([-5.0, 0.0, 3.0], [1.0, 0.0, 0.0]), Some(5.0));
+/// assert_eq!(intersect([-5.0, 0.0, 3.5], [1.0, 0.0, 0.0]), None);
+/// ```
+pub fn ray_capsule_intersection<T>(ray: Ray<T>, a: Vector3<T>, b: Vector3<T>, radius: T) -> Option<T>
+    where T: Float
+{
+    let segment = vec3_sub(b, a);
+    let length_squared = vec3_dot(segment, segment);
+    let radius_squared = radius * radius;
+
+    let mut nearest = None;
+
+    // Hemispherical ends, only the parts beyond the segment belong to the surface
+    for &(center, sign) in &[(a, -T::one()), (b, T::one())] {
+        let offset = vec3_sub(ray.origin, center);
+        let hits = solve_quadratic(
+            vec3_dot(ray.direction, ray.direction),
+            vec3_dot(offset, ray.direction),
+            vec3_dot(offset, offset) - radius_squared
+        );
+        if let Some((t0, t1)) = hits {
+            for &t in &[t0, t1] {
+                let p = vec3_add(offset, vec3_mul(ray.direction, t));
+                if length_squared == T::zero() || sign * vec3_dot(p, segment) >= T::zero() {
+                    nearest = nearest_hit(nearest, t);
+                }
+            }
+        }
+    }
+
+    if length_squared == T::zero() {
+        return nearest;
+    }
+
+    let axis = vec3_mul(segment, T::one() / length_squared.sqrt());
+    let offset = vec3_sub(ray.origin, a);
+    let (offset_along, direction_along) = (vec3_dot(offset, axis), vec3_dot(ray.direction, axis));
+    let offset_across = vec3_sub(offset, vec3_mul(axis, offset_along));
+    let direction_across = vec3_sub(ray.direction, vec3_mul(axis, direction_along));
+
+    let side = solve_quadratic(
+        vec3_dot(direction_across, direction_across),
+        vec3_dot(offset_across, direction_across),
+        vec3_dot(offset_across, offset_across) - radius_squared
+    );
+    if let Some((t0, t1)) = side {
+        for &t in &[t0, t1] {
+            let h = offset_along + t * direction_along;
+            if h >= T::zero() && h * h <= length_squared {
+                nearest = nearest_hit(nearest, t);
+            }
+        }
+    }
+
+    nearest
+}
+
+/// Solves a * t^2 + 2 * half_b * t + c = 0, returning the roots in ascending order
+fn solve_quadratic<T>(a: T, half_b: T, c: T) -> Option<(T, T)>
+    where T: Float
+{
+    if a == T::zero() {
+        return None;
+    }
+
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < T::zero() {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    Some(((-half_b - root) / a, (-half_b + root) / a))
+}
+
+fn nearest_hit<T>(nearest: Option<T>, t: T) -> Option<T>
+    where T: Float
+{
+    match nearest {
+        _ if t < T::zero() => nearest,
+        Some(n) if n <= t => nearest,
+        _ => Some(t)
+    }
+}
+
+/// Bounding volume hierarchy over axis-aligned bounding boxes
+///
+/// The hierarchy is built by recursively splitting the boxes at the median of their
+/// centers along the axis on which they are the most spread out.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{Aabb, Bvh, Ray};
+///
+/// let mut seed: u64 = 3;
+/// let mut uniform = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 40) as f32 / (1u64 << 24) as f32
+/// };
+///
+/// let aabbs: Vec<Aabb<f32>> = (0..500).map(|_| {
+///     let min = [uniform() * 100.0, uniform() * 100.0, uniform() * 100.0];
+///     let size = [uniform() * 8.0, uniform() * 8.0, uniform() * 8.0];
+///     Aabb { min, max: [min[0] + size[0], min[1] + size[1], min[2] + size[2]] }
+/// }).collect();
+/// let bvh = Bvh::build(&aabbs);
+///
+/// // Brute force slab test
+/// let hit = |ray: &Ray<f32>, aabb: &Aabb<f32>| {
+///     let (mut near, mut far) = (0.0f32, f32::INFINITY);
+///     for i in 0..3 {
+///         let t0 = (aabb.min[i] - ray.origin[i]) / ray.direction[i];
+///         let t1 = (aabb.max[i] - ray.origin[i]) / ray.direction[i];
+///         near = near.max(t0.min(t1));
+///         far = far.min(t0.max(t1));
+///     }
+///     near <= far
+/// };
+///
+/// for _ in 0..100 {
+///     let ray = Ray {
+///         origin: [uniform() * 100.0, uniform() * 100.0, -10.0],
+///         direction: [uniform() - 0.5, uniform() - 0.5, 1.0],
+///     };
+///
+///     let mut candidates: Vec<usize> = bvh.intersect_ray(&ray).collect();
+///     candidates.sort();
+///     let expected: Vec<usize> = (0..aabbs.len()).filter(|&i| hit(&ray, &aabbs[i])).collect();
+///     assert_eq!(candidates, expected);
+///
+///     let min = [uniform() * 100.0, uniform() * 100.0, uniform() * 100.0];
+///     let query = Aabb { min, max: [min[0] + 10.0, min[1] + 10.0, min[2] + 10.0] };
+///
+///     let mut overlapping: Vec<usize> = bvh.query_aabb(&query).collect();
+///     overlapping.sort();
+///     let expected: Vec<usize> = (0..aabbs.len()).filter(|&i| {
+///         (0..3).all(|k| aabbs[i].min[k] <= query.max[k] && query.min[k] <= aabbs[i].max[k])
+///     }).collect();
+///     assert_eq!(overlapping, expected);
+/// }
+/// ```
+///
+/// Empty hierarchy and single box
+/// ```
+/// use stones::geometry::{Aabb, Bvh, Ray};
+///
+/// let ray = Ray { origin: [0.5, 0.5, -1.0], direction: [0.0, 0.0, 1.0] };
+/// let aabb = Aabb { min: [0.0, 0.0, 0.0], max: [1.0, 1.0, 1.0] };
+///
+/// let empty = Bvh::build(&[]);
+/// assert_eq!(empty.intersect_ray(&ray).count(), 0);
+/// assert_eq!(empty.query_aabb(&aabb).count(), 0);
+///
+/// let single = Bvh::build(&[aabb]);
+/// assert_eq!(single.intersect_ray(&ray).collect::<Vec<_>>(), [0]);
+/// assert_eq!(single.query_aabb(&aabb).collect::<Vec<_>>(), [0]);
+///
+/// let away = Ray { origin: [0.5, 0.5, -1.0], direction: [0.0, 0.0, -1.0] };
+/// assert_eq!(single.intersect_ray(&away).count(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bvh<T> {
+    nodes: Vec<BvhNode<T>>,
+    indices: Vec<usize>,
+    aabbs: Vec<Aabb<T>>,
+}
+
+/// Node of a bounding volume hierarchy, a leaf if count isn't zero
+///
+/// The boxes of a leaf are indices[first..first + count], the children of an internal node
+/// are nodes[first] and nodes[first + 1].
+#[derive(Debug, Clone)]
+struct BvhNode<T> {
+    bounds: Aabb<T>,
+    first: usize,
+    count: usize,
+}
+
+impl<T> Bvh<T>
+    where T: Float
+{
+    const MAX_LEAF_SIZE: usize = 4;
+
+    /// Builds a bounding volume hierarchy over the given boxes
+    pub fn build(aabbs: &[Aabb<T>]) -> Bvh<T> {
+        let mut bvh = Bvh {
+            nodes: Vec::new(),
+            indices: (0..aabbs.len()).collect(),
+            aabbs: aabbs.to_vec(),
+        };
+
+        if !aabbs.is_empty() {
+            bvh.nodes.push(BvhNode { bounds: aabbs[0], first: 0, count: 0 });
+            bvh.subdivide(0, 0, aabbs.len());
+        }
+
+        bvh
+    }
+
+    /// Returns the indices of the boxes intersected by a ray, in no particular order
+    pub fn intersect_ray<'a>(&'a self, ray: &'a Ray<T>) -> impl Iterator<Item = usize> + 'a {
+        self.traverse(move |aabb| ray_hits_aabb(ray, aabb))
+    }
+
+    /// Returns the indices of the boxes overlapping a box, in no particular order
+    pub fn query_aabb<'a>(&'a self, aabb: &'a Aabb<T>) -> impl Iterator<Item = usize> + 'a {
+        self.traverse(move |other| aabbs_overlap(aabb, other))
+    }
+
+    fn traverse<'a, F>(&'a self, test: F) -> BvhTraversal<'a, T, F>
+        where F: Fn(&Aabb<T>) -> bool
+    {
+        BvhTraversal {
+            bvh: self,
+            stack: if self.nodes.is_empty() { Vec::new() } else { vec![0] },
+            leaf: [].iter(),
+            test,
+        }
+    }
+
+    fn subdivide(&mut self, node: usize, first: usize, count: usize) {
+        let indices = &mut self.indices[first..first + count];
+        let aabbs = &self.aabbs;
+
+        let mut bounds = aabbs[indices[0]];
+        let mut centers = Aabb { min: aabb_center(&bounds), max: aabb_center(&bounds) };
+        for &i in indices.iter() {
+            bounds = aabb_union(&bounds, &aabbs[i]);
+            let center = aabb_center(&aabbs[i]);
+            centers = aabb_union(&centers, &Aabb { min: center, max: center });
+        }
+
+        let extent = vec3_sub(centers.max, centers.min);
+        let axis = if extent[0] >= extent[1] && extent[0] >= extent[2] {
+            0
+        } else if extent[1] >= extent[2] {
+            1
+        } else {
+            2
+        };
+
+        if count <= Self::MAX_LEAF_SIZE || extent[axis] <= T::zero() {
+            self.nodes[node] = BvhNode { bounds, first, count };
+            return;
+        }
+
+        let middle = count / 2;
+        indices.select_nth_unstable_by(middle, |&a, &b| {
+            let (a, b) = (aabbs[a].min[axis] + aabbs[a].max[axis], aabbs[b].min[axis] + aabbs[b].max[axis]);
+            a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let children = self.nodes.len();
+        self.nodes.push(BvhNode { bounds, first, count: 0 });
+        self.nodes.push(BvhNode { bounds, first, count: 0 });
+        self.nodes[node] = BvhNode { bounds, first: children, count: 0 };
+
+        self.subdivide(children, first, middle);
+        self.subdivide(children + 1, first + middle, count - middle);
+    }
+}
+
+struct BvhTraversal<'a, T, F> {
+    bvh: &'a Bvh<T>,
+    stack: Vec<usize>,
+    leaf: std::slice::Iter<'a, usize>,
+    test: F,
+}
+
+impl<'a, T, F> Iterator for BvhTraversal<'a, T, F>
+    where F: Fn(&Aabb<T>) -> bool
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if let Some(&i) = self.leaf.next() {
+                if (self.test)(&self.bvh.aabbs[i]) {
+                    return Some(i);
+                }
+                continue;
+            }
+
+            let node = &self.bvh.nodes[self.stack.pop()?];
+            if !(self.test)(&node.bounds) {
+                continue;
+            }
+
+            if node.count > 0 {
+                self.leaf = self.bvh.indices[node.first..node.first + node.count].iter();
+            } else {
+                self.stack.push(node.first + 1);
+                self.stack.push(node.first);
+            }
+        }
+    }
+}
+
+fn aabb_center<T>(aabb: &Aabb<T>) -> Vector3<T>
+    where T: Float
+{
+    vec3_mul(vec3_add(aabb.min, aabb.max), T::from_f64(0.5))
+}
+
+fn aabb_union<T>(a: &Aabb<T>, b: &Aabb<T>) -> Aabb<T>
+    where T: Float
+{
+    let min = |x: T, y: T| if y < x { y } else { x };
+    let max = |x: T, y: T| if y > x { y } else { x };
+    Aabb {
+        min: [min(a.min[0], b.min[0]), min(a.min[1], b.min[1]), min(a.min[2], b.min[2])],
+        max: [max(a.max[0], b.max[0]), max(a.max[1], b.max[1]), max(a.max[2], b.max[2])],
+    }
+}
+
+fn aabbs_overlap<T>(a: &Aabb<T>, b: &Aabb<T>) -> bool
+    where T: Float
+{
+    (0..3).all(|i| a.min[i] <= b.max[i] && b.min[i] <= a.max[i])
+}
+
+/// Slab test between a ray and a box
+fn ray_hits_aabb<T>(ray: &Ray<T>, aabb: &Aabb<T>) -> bool
+    where T: Float
+{
+    let (mut near, mut far) = (T::zero(), None);
+    for i in 0..3 {
+        if ray.direction[i] == T::zero() {
+            if ray.origin[i] < aabb.min[i] || ray.origin[i] > aabb.max[i] {
+                return false;
+            }
+            continue;
+        }
+
+        let t0 = (aabb.min[i] - ray.origin[i]) / ray.direction[i];
+        let t1 = (aabb.max[i] - ray.origin[i]) / ray.direction[i];
+        let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+        if t0 > near {
+            near = t0;
+        }
+        far = match far {
+            Some(f) if f <= t1 => Some(f),
+            _ => Some(t1)
+        };
+    }
+
+    match far {
+        Some(far) => near <= far,
+        None => true
+    }
+}
+
+/// Uniform grid over points for neighbor queries
+///
+/// The points are bucketed in cubic cells of a given size, queries only look at the occupied
+/// cells overlapping the queried region. Their cost grows with the size of the region relative
+/// to the cell size, but never exceeds the number of occupied cells.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{Aabb, SpatialGrid};
+///
+/// let mut seed: u64 = 11;
+/// let mut uniform = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 40) as f32 / (1u64 << 24) as f32
+/// };
+///
+/// let mut points: Vec<[f32; 3]> = (0..1000)
+///     .map(|_| [uniform() * 40.0 - 20.0, uniform() * 40.0 - 20.0, uniform() * 40.0 - 20.0])
+///     .collect();
+/// let mut grid = SpatialGrid::build(&points, 2.5);
+///
+/// for pass in 0..2 {
+///     for _ in 0..50 {
+///         let center = [uniform() * 40.0 - 20.0, uniform() * 40.0 - 20.0, uniform() * 40.0 - 20.0];
+///         let radius = uniform() * 6.0;
+///
+///         let mut found: Vec<usize> = grid.query_radius(center, radius).collect();
+///         found.sort();
+///         let expected: Vec<usize> = (0..points.len()).filter(|&i| {
+///             let d = [points[i][0] - center[0], points[i][1] - center[1], points[i][2] - center[2]];
+///             d[0] * d[0] + d[1] * d[1] + d[2] * d[2] <= radius * radius
+///         }).collect();
+///         assert_eq!(found, expected);
+///
+///         let query = Aabb { min: center, max: [center[0] + radius, center[1] + 2.0, center[2] + 3.0] };
+///         let mut found: Vec<usize> = grid.query_aabb(&query).collect();
+///         found.sort();
+///         let expected: Vec<usize> = (0..points.len()).filter(|&i| {
+///             (0..3).all(|k| query.min[k] <= points[i][k] && points[i][k] <= query.max[k])
+///         }).collect();
+///         assert_eq!(found, expected);
+///     }
+///
+///     // Move the points and rebuild the grid
+///     if pass == 0 {
+///         for p in points.iter_mut() {
+///             p[0] = -p[0] * 0.5;
+///             p[2] += 3.0;
+///         }
+///         grid.rebuild(&points);
+///     }
+/// }
+/// ```
+///
+/// Cell boundaries
+/// ```
+/// use stones::geometry::SpatialGrid;
+///
+/// let points = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [2.0, 1.0, -1.0]];
+/// let grid = SpatialGrid::build(&points, 1.0);
+///
+/// let mut found: Vec<usize> = grid.query_radius([0.0, 0.0, 0.0], 1.0).collect();
+/// found.sort();
+/// assert_eq!(found, [0, 1, 2]);
+///
+/// let found: Vec<usize> = grid.query_radius([1.0, 1.0, -1.0], 1.0).collect();
+/// assert_eq!(found, [3]);
+/// ```
+///
+/// Queries much larger than the cells
+/// ```
+/// use stones::geometry::{Aabb, SpatialGrid};
+///
+/// let mut grid = SpatialGrid::build(&[[0.0, 0.0, 0.0], [5.0, -3.0, 2.0]], 1e-3);
+///
+/// let mut found: Vec<usize> = grid.query_radius([0.0, 0.0, 0.0], 1e30).collect();
+/// found.sort();
+/// assert_eq!(found, [0, 1]);
+///
+/// grid.rebuild(&[[1e6, 1e6, 1e6]]);
+/// let everything = Aabb { min: [-1e30, -1e30, -1e30], max: [1e30, 1e30, 1e30] };
+/// assert_eq!(grid.query_aabb(&everything).collect::<Vec<_>>(), [0]);
+///
+/// grid.rebuild(&[]);
+/// assert_eq!(grid.query_aabb(&everything).count(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpatialGrid<T> {
+    cell_size: T,
+    cells: HashMap<[i64; 3], Vec<usize>>,
+    occupied: Option<Aabb<i64>>,
+    points: Vec<Vector3<T>>,
+}
+
+impl<T> SpatialGrid<T>
+    where T: Float
+{
+    /// Builds a grid over the given points with cells of the given size
+    pub fn build(points: &[Vector3<T>], cell_size: T) -> SpatialGrid<T> {
+        debug_assert!(cell_size > T::zero(), "the cell size must be positive");
+
+        let mut grid = SpatialGrid {
+            cell_size,
+            cells: HashMap::new(),
+            occupied: None,
+            points: Vec::new(),
+        };
+        grid.rebuild(points);
+        grid
+    }
+
+    /// Replaces the points of the grid, typically after they moved
+    pub fn rebuild(&mut self, points: &[Vector3<T>]) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+
+        self.points.clear();
+        self.points.extend_from_slice(points);
+        self.occupied = None;
+        for (i, &p) in points.iter().enumerate() {
+            let cell = self.cell(p);
+            self.cells.entry(cell).or_default().push(i);
+            self.occupied = Some(match self.occupied {
+                Some(occupied) => Aabb {
+                    min: [occupied.min[0].min(cell[0]), occupied.min[1].min(cell[1]), occupied.min[2].min(cell[2])],
+                    max: [occupied.max[0].max(cell[0]), occupied.max[1].max(cell[1]), occupied.max[2].max(cell[2])],
+                },
+                None => Aabb { min: cell, max: cell },
+            });
+        }
+
+        // Drop the cells the points left, keeping the allocations of the others
+        self.cells.retain(|_, indices| !indices.is_empty());
+    }
+
+    /// Returns the indices of the points within a distance of a center, in no particular order
+    pub fn query_radius(&self, center: Vector3<T>, radius: T) -> impl Iterator<Item = usize> + '_ {
+        let bounds = Aabb {
+            min: [center[0] - radius, center[1] - radius, center[2] - radius],
+            max: [center[0] + radius, center[1] + radius, center[2] + radius],
+        };
+        let radius_squared = radius * radius;
+        self.query_cells(&bounds).filter(move |&i| {
+            let d = vec3_sub(self.points[i], center);
+            vec3_dot(d, d) <= radius_squared
+        })
+    }
+
+    /// Returns the indices of the points inside a box, in no particular order
+    pub fn query_aabb(&self, aabb: &Aabb<T>) -> impl Iterator<Item = usize> + '_ {
+        let aabb = *aabb;
+        self.query_cells(&aabb).filter(move |&i| {
+            let p = self.points[i];
+            (0..3).all(|k| aabb.min[k] <= p[k] && p[k] <= aabb.max[k])
+        })
+    }
+
+    fn query_cells(&self, bounds: &Aabb<T>) -> impl Iterator<Item = usize> + '_ {
+        let (mut min, mut max) = (self.cell(bounds.min), self.cell(bounds.max));
+        let mut cell_count = 0u128;
+        if let Some(occupied) = self.occupied {
+            for k in 0..3 {
+                min[k] = min[k].max(occupied.min[k]);
+                max[k] = max[k].min(occupied.max[k]);
+            }
+            cell_count = (0..3).fold(1u128, |count, k| {
+                let extent = (max[k] as i128 - min[k] as i128 + 1).max(0) as u128;
+                count.saturating_mul(extent)
+            });
+        }
+
+        // Walk the cells of the range, or the occupied cells if there are fewer of them
+        let walk_range = self.occupied.is_some() && cell_count <= self.cells.len() as u128;
+        let range_cells = if walk_range {
+            let range = (min[0]..=max[0])
+                .flat_map(move |x| (min[1]..=max[1]).flat_map(move |y| (min[2]..=max[2]).map(move |z| [x, y, z])))
+                .filter_map(move |cell| self.cells.get(&cell));
+            Some(range)
+        } else {
+            None
+        };
+        let occupied_cells = if walk_range {
+            None
+        } else {
+            let occupied = self.cells.iter()
+                .filter(move |(cell, _)| (0..3).all(|k| min[k] <= cell[k] && cell[k] <= max[k]))
+                .map(|(_, indices)| indices);
+            Some(occupied)
+        };
+
+        range_cells.into_iter().flatten()
+            .chain(occupied_cells.into_iter().flatten())
+            .flat_map(|indices| indices.iter().copied())
+    }
+
+    fn cell(&self, p: Vector3<T>) -> [i64; 3] {
+        [
+            (p[0] / self.cell_size).floor().to_f64() as i64,
+            (p[1] / self.cell_size).floor().to_f64() as i64,
+            (p[2] / self.cell_size).floor().to_f64() as i64
+        ]
+    }
+}
+
+/// Extracts the view frustum of a view-projection matrix
+///
+/// The matrix is expected to map the frustum to the [-1, 1] clip volume, as OpenGL
+/// projections do. The planes are normalized and their normals point inside the frustum.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::frustum_from_mat4;
+///
+/// // Perspective projection with a 90° field of view, near = 1 and far = 3
+/// let m = [1.0, 0.0, 0.0, 0.0,
+///          0.0, 1.0, 0.0, 0.0,
+///          0.0, 0.0, -2.0, -3.0,
+///          0.0, 0.0, -1.0, 0.0];
+/// let frustum = frustum_from_mat4(m);
+///
+/// let near = frustum.planes[4];
+/// assert_eq!(near.normal, [0.0, 0.0, -1.0]);
+/// assert_eq!(near.distance, -1.0);
+///
+/// let far = frustum.planes[5];
+/// assert_eq!(far.normal, [0.0, 0.0, 1.0]);
+/// assert_eq!(far.distance, 3.0);
+/// ```
+pub fn frustum_from_mat4<T>(m: Matrix4<T>) -> Frustum<T>
+    where T: Float
+{
+    let row = |i: usize| [m[i * 4], m[i * 4 + 1], m[i * 4 + 2], m[i * 4 + 3]];
+    let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+    let plane = |sign: T, r: [T; 4]| {
+        let p = [w[0] + sign * r[0], w[1] + sign * r[1], w[2] + sign * r[2], w[3] + sign * r[3]];
+        let length = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+        Plane {
+            normal: [p[0] / length, p[1] / length, p[2] / length],
+            distance: p[3] / length,
+        }
+    };
+
+    let one = T::one();
+    Frustum {
+        planes: [plane(one, x), plane(-one, x), plane(one, y), plane(-one, y), plane(one, z), plane(-one, z)],
+    }
+}
+
+/// Clips a segment against an axis-aligned bounding box
+///
+/// Returns the part of the segment [a, b] inside the box, boundaries included, or None if
+/// the segment doesn't intersect the box.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{Aabb, clip_segment_aabb};
+///
+/// let aabb = Aabb { min: [0.0, 0.0, 0.0], max: [2.0, 2.0, 2.0] };
+///
+/// // Inside
+/// assert_eq!(clip_segment_aabb([0.5, 0.5, 0.5], [1.5, 1.0, 1.0], &aabb), Some(([0.5, 0.5, 0.5], [1.5, 1.0, 1.0])));
+///
+/// // Outside
+/// assert_eq!(clip_segment_aabb([3.0, 0.5, 0.5], [4.0, 1.0, 1.0], &aabb), None);
+/// assert_eq!(clip_segment_aabb([-1.0, 1.0, 3.0], [3.0, 1.0, 3.0], &aabb), None);
+///
+/// // Entering and exiting through faces
+/// assert_eq!(clip_segment_aabb([-1.0, 1.0, 1.0], [1.0, 1.0, 1.0], &aabb), Some(([0.0, 1.0, 1.0], [1.0, 1.0, 1.0])));
+/// assert_eq!(clip_segment_aabb([-1.0, 1.0, 1.0], [3.0, 1.0, 1.0], &aabb), Some(([0.0, 1.0, 1.0], [2.0, 1.0, 1.0])));
+///
+/// // Through opposite corners
+/// assert_eq!(clip_segment_aabb([-1.0, -1.0, -1.0], [3.0, 3.0, 3.0], &aabb), Some(([0.0, 0.0, 0.0], [2.0, 2.0, 2.0])));
+///
+/// // Touching a corner
+/// assert_eq!(clip_segment_aabb([3.0, 1.0, 2.0], [1.0, 3.0, 2.0], &aabb), Some(([2.0, 2.0, 2.0], [2.0, 2.0, 2.0])));
+///
+/// // Lying on a face
+/// assert_eq!(clip_segment_aabb([-1.0, 2.0, 1.0], [1.0, 2.0, 1.0], &aabb), Some(([0.0, 2.0, 1.0], [1.0, 2.0, 1.0])));
+///
+/// // Zero-length segments
+/// assert_eq!(clip_segment_aabb([1.0, 1.0, 1.0], [1.0, 1.0, 1.0], &aabb), Some(([1.0, 1.0, 1.0], [1.0, 1.0, 1.0])));
+/// assert_eq!(clip_segment_aabb([3.0, 1.0, 1.0], [3.0, 1.0, 1.0], &aabb), None);
+/// ```
+pub fn clip_segment_aabb<T>(a: Vector3<T>, b: Vector3<T>, aabb: &Aabb<T>) -> Option<(Vector3<T>, Vector3<T>)>
+    where T: Float
+{
+    let d = vec3_sub(b, a);
+    let (mut t0, mut t1) = (T::zero(), T::one());
+    for i in 0..3 {
+        if d[i] == T::zero() {
+            if a[i] < aabb.min[i] || a[i] > aabb.max[i] {
+                return None;
+            }
+            continue;
+        }
+
+        let near = (aabb.min[i] - a[i]) / d[i];
+        let far = (aabb.max[i] - a[i]) / d[i];
+        let (near, far) = if near < far { (near, far) } else { (far, near) };
+        if near > t0 {
+            t0 = near;
+        }
+        if far < t1 {
+            t1 = far;
+        }
+        if t0 > t1 {
+            return None;
+        }
+    }
+
+    Some((segment_point(a, b, d, t0), segment_point(a, b, d, t1)))
+}
+
+/// Clips a segment against a view frustum
+///
+/// Returns the part of the segment [a, b] inside the frustum, boundaries included, or None
+/// if the segment doesn't intersect the frustum.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{clip_segment_frustum, frustum_from_mat4};
+///
+/// // Perspective projection with a 90° field of view, near = 1 and far = 3
+/// let frustum = frustum_from_mat4([1.0, 0.0, 0.0, 0.0,
+///                                  0.0, 1.0, 0.0, 0.0,
+///                                  0.0, 0.0, -2.0, -3.0,
+///                                  0.0, 0.0, -1.0, 0.0]);
+///
+/// // Inside
+/// assert_eq!(clip_segment_frustum([0.0, 0.0, -1.5], [0.5, 0.5, -2.0], &frustum), Some(([0.0, 0.0, -1.5], [0.5, 0.5, -2.0])));
+///
+/// // Starting behind the camera
+/// assert_eq!(clip_segment_frustum([0.0, 0.0, 1.0], [0.0, 0.0, -2.0], &frustum), Some(([0.0, 0.0, -1.0], [0.0, 0.0, -2.0])));
+///
+/// // Going through the near and far planes
+/// assert_eq!(clip_segment_frustum([0.0, 0.0, 0.0], [0.0, 0.0, -4.0], &frustum), Some(([0.0, 0.0, -1.0], [0.0, 0.0, -3.0])));
+///
+/// // Exiting through a side
+/// assert_eq!(clip_segment_frustum([0.0, 0.0, -2.0], [4.0, 0.0, -2.0], &frustum), Some(([0.0, 0.0, -2.0], [2.0, 0.0, -2.0])));
+///
+/// // Outside
+/// assert_eq!(clip_segment_frustum([0.0, 0.0, 1.0], [0.0, 0.0, 2.0], &frustum), None);
+/// assert_eq!(clip_segment_frustum([3.0, 0.0, -2.0], [3.0, 1.0, -2.0], &frustum), None);
+///
+/// // Zero-length segments
+/// assert_eq!(clip_segment_frustum([0.0, 0.0, -2.0], [0.0, 0.0, -2.0], &frustum), Some(([0.0, 0.0, -2.0], [0.0, 0.0, -2.0])));
+/// assert_eq!(clip_segment_frustum([0.0, 0.0, 2.0], [0.0, 0.0, 2.0], &frustum), None);
+/// ```
+pub fn clip_segment_frustum<T>(a: Vector3<T>, b: Vector3<T>, frustum: &Frustum<T>) -> Option<(Vector3<T>, Vector3<T>)>
+    where T: Float
+{
+    let (mut t0, mut t1) = (T::zero(), T::one());
+    for plane in &frustum.planes {
+        let distance_a = vec3_dot(plane.normal, a) + plane.distance;
+        let distance_b = vec3_dot(plane.normal, b) + plane.distance;
+        if distance_a < T::zero() && distance_b < T::zero() {
+            return None;
+        }
+
+        if distance_a < T::zero() {
+            let t = distance_a / (distance_a - distance_b);
+            if t > t0 {
+                t0 = t;
+            }
+        } else if distance_b < T::zero() {
+            let t = distance_a / (distance_a - distance_b);
+            if t < t1 {
+                t1 = t;
+            }
+        }
+
+        if t0 > t1 {
+            return None;
+        }
+    }
+
+    let d = vec3_sub(b, a);
+    Some((segment_point(a, b, d, t0), segment_point(a, b, d, t1)))
+}
+
+/// Returns a + t * d, returning the endpoints exactly for t = 0 and t = 1
+fn segment_point<T>(a: Vector3<T>, b: Vector3<T>, d: Vector3<T>, t: T) -> Vector3<T>
+    where T: Float
+{
+    if t == T::zero() {
+        a
+    } else if t == T::one() {
+        b
+    } else {
+        vec3_add(a, vec3_mul(d, t))
+    }
+}
+
+/// Clips a convex polygon against a plane, keeping the part in the positive half-space
+///
+/// Vertices lying on the plane are kept and new vertices are only created on edges strictly
+/// crossing the plane, so edges lying on the plane don't produce duplicate points. The
+/// result is empty if the polygon is entirely in the negative half-space.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{Plane, clip_polygon_plane};
+///
+/// let plane = Plane { normal: [1.0, 0.0, 0.0], distance: -1.0 };
+///
+/// // Inside
+/// let triangle = [[1.0, 0.0, 0.0], [3.0, 0.0, 0.0], [2.0, 1.0, 0.0]];
+/// assert_eq!(clip_polygon_plane(&triangle, &plane), triangle.to_vec());
+///
+/// // Outside
+/// let triangle = [[-1.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+/// assert!(clip_polygon_plane(&triangle, &plane).is_empty());
+///
+/// // Crossing the plane
+/// let triangle = [[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [4.0, 4.0, 0.0]];
+/// assert_eq!(clip_polygon_plane(&triangle, &plane),
+///            vec![[1.0, 1.0, 0.0], [1.0, 0.0, 0.0], [4.0, 0.0, 0.0], [4.0, 4.0, 0.0]]);
+///
+/// // Edge lying on the plane
+/// let square = [[1.0, 0.0, 0.0], [3.0, 0.0, 0.0], [3.0, 2.0, 0.0], [1.0, 2.0, 0.0]];
+/// assert_eq!(clip_polygon_plane(&square, &plane), square.to_vec());
+///
+/// // Vertex lying on the plane
+/// let triangle = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [2.0, 1.0, 0.0]];
+/// assert_eq!(clip_polygon_plane(&triangle, &plane), vec![[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [2.0, 1.0, 0.0]]);
+/// ```
+pub fn clip_polygon_plane<T>(vertices: &[Vector3<T>], plane: &Plane<T>) -> Vec<Vector3<T>>
+    where T: Float
+{
+    let mut clipped = Vec::with_capacity(vertices.len() + 1);
+    let mut previous = match vertices.last() {
+        Some(&vertex) => vertex,
+        None => return clipped,
+    };
+    let mut previous_distance = vec3_dot(plane.normal, previous) + plane.distance;
+
+    for &current in vertices {
+        let current_distance = vec3_dot(plane.normal, current) + plane.distance;
+        let crossing = (previous_distance < T::zero() && current_distance > T::zero())
+            || (previous_distance > T::zero() && current_distance < T::zero());
+        if crossing {
+            let t = previous_distance / (previous_distance - current_distance);
+            clipped.push(vec3_add(previous, vec3_mul(vec3_sub(current, previous), t)));
+        }
+        if current_distance >= T::zero() {
+            clipped.push(current);
+        }
+
+        previous = current;
+        previous_distance = current_distance;
+    }
+
+    clipped
+}
+
+/// Clips a convex polygon against the six planes of a view frustum
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{clip_polygon_frustum, frustum_from_mat4};
+///
+/// // Perspective projection with a 90° field of view, near = 1 and far = 3
+/// let frustum = frustum_from_mat4([1.0, 0.0, 0.0, 0.0,
+///                                  0.0, 1.0, 0.0, 0.0,
+///                                  0.0, 0.0, -2.0, -3.0,
+///                                  0.0, 0.0, -1.0, 0.0]);
+///
+/// // Inside
+/// let triangle = [[0.0, 0.0, -2.0], [1.0, 0.0, -2.0], [0.0, 1.0, -2.0]];
+/// assert_eq!(clip_polygon_frustum(&triangle, &frustum), triangle.to_vec());
+///
+/// // Behind the camera
+/// let triangle = [[0.0, 0.0, 2.0], [1.0, 0.0, 2.0], [0.0, 1.0, 2.0]];
+/// assert!(clip_polygon_frustum(&triangle, &frustum).is_empty());
+///
+/// // Larger than the frustum: clipped to its cross-section at z = -2
+/// let square: [[f64; 3]; 4] = [[-4.0, -4.0, -2.0], [4.0, -4.0, -2.0], [4.0, 4.0, -2.0], [-4.0, 4.0, -2.0]];
+/// let clipped = clip_polygon_frustum(&square, &frustum);
+/// assert_eq!(clipped.len(), 4);
+/// for vertex in clipped {
+///     assert!((vertex[0].abs() - 2.0).abs() < 1e-9);
+///     assert!((vertex[1].abs() - 2.0).abs() < 1e-9);
+///     assert_eq!(vertex[2], -2.0);
+/// }
+/// ```
+pub fn clip_polygon_frustum<T>(vertices: &[Vector3<T>], frustum: &Frustum<T>) -> Vec<Vector3<T>>
+    where T: Float
+{
+    let mut clipped = vertices.to_vec();
+    for plane in &frustum.planes {
+        if clipped.is_empty() {
+            break;
+        }
+        clipped = clip_polygon_plane(&clipped, plane);
+    }
+
+    clipped
+}
+
+/// Computes twice the signed area of the triangle abc
+///
+/// The result is positive if the points are in counter-clockwise order, negative if they are
+/// in clockwise order and zero if they are collinear.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::orient2d;
+///
+/// assert_eq!(orient2d([0.0, 0.0], [1.0, 0.0], [0.0, 1.0]), 1.0);
+/// assert_eq!(orient2d([0.0, 0.0], [0.0, 1.0], [1.0, 0.0]), -1.0);
+/// assert_eq!(orient2d([0.0, 0.0], [1.0, 1.0], [2.0, 2.0]), 0.0);
+/// ```
+pub fn orient2d<T>(a: Vector2<T>, b: Vector2<T>, c: Vector2<T>) -> T
+    where T: Float
+{
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Tests the position of a point relative to the circumcircle of a triangle
+///
+/// For a counter-clockwise triangle abc, the result is positive if d is inside the
+/// circumcircle, negative if it is outside and zero if the four points are cocircular. The
+/// sign is reversed for a clockwise triangle.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::in_circumcircle;
+///
+/// let (a, b, c) = ([1.0, 0.0], [0.0, 1.0], [-1.0, 0.0]);
+/// assert!(in_circumcircle(a, b, c, [0.0, 0.0]) > 0.0);
+/// assert!(in_circumcircle(a, b, c, [2.0, 0.0]) < 0.0);
+/// assert_eq!(in_circumcircle(a, b, c, [0.0, -1.0]), 0.0);
+/// ```
+pub fn in_circumcircle<T>(a: Vector2<T>, b: Vector2<T>, c: Vector2<T>, d: Vector2<T>) -> T
+    where T: Float
+{
+    let (adx, ady) = (a[0] - d[0], a[1] - d[1]);
+    let (bdx, bdy) = (b[0] - d[0], b[1] - d[1]);
+    let (cdx, cdy) = (c[0] - d[0], c[1] - d[1]);
+
+    let ad = adx * adx + ady * ady;
+    let bd = bdx * bdx + bdy * bdy;
+    let cd = cdx * cdx + cdy * cdy;
+
+    adx * (bdy * cd - bd * cdy) - ady * (bdx * cd - bd * cdx) + ad * (bdx * cdy - bdy * cdx)
+}
+
+/// Computes the Delaunay triangulation of a set of points
+///
+/// Uses the Bowyer–Watson algorithm and returns triangles as indices into points, all in
+/// counter-clockwise order. Duplicate points are ignored: only the first occurrence of a
+/// point is referenced by the triangles. The result is empty if there are less than 3
+/// distinct points or if they are all collinear.
+///
+/// # Examples
+///
+/// Triangle
+/// ```
+/// use stones::geometry::delaunay_triangulate_2d;
+///
+/// let points: [[f64; 2]; 3] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+/// assert_eq!(delaunay_triangulate_2d(&points), vec![[0, 1, 2]]);
+/// ```
+///
+/// Empty circumcircles
+/// ```
+/// use stones::geometry::{delaunay_triangulate_2d, in_circumcircle, orient2d};
+///
+/// let points: [[f64; 2]; 5] = [[0.0, 0.0], [2.0, 0.0], [2.2, 1.0], [0.1, 1.8], [1.0, 0.7]];
+/// let triangles = delaunay_triangulate_2d(&points);
+/// assert_eq!(triangles.len(), 4);
+/// for t in &triangles {
+///     let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+///     assert!(orient2d(a, b, c) > 0.0);
+///     for p in &points {
+///         assert!(in_circumcircle(a, b, c, *p) <= 0.0);
+///     }
+/// }
+///
+/// // The four corners of a square are cocircular, both diagonals are valid
+/// let square: [[f64; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+/// let triangles = delaunay_triangulate_2d(&square);
+/// assert_eq!(triangles.len(), 2);
+/// for t in &triangles {
+///     let (a, b, c) = (square[t[0]], square[t[1]], square[t[2]]);
+///     assert!(orient2d(a, b, c) > 0.0);
+///     for p in &square {
+///         assert!(in_circumcircle(a, b, c, *p) <= 0.0);
+///     }
+/// }
+/// ```
+///
+/// Regular grid
+/// ```
+/// use stones::geometry::{delaunay_triangulate_2d, orient2d};
+///
+/// let mut points: Vec<[f64; 2]> = Vec::new();
+/// for y in 0..5 {
+///     for x in 0..5 {
+///         points.push([x as f64, y as f64]);
+///     }
+/// }
+///
+/// // The triangles cover the 4x4 grid exactly once
+/// let triangles = delaunay_triangulate_2d(&points);
+/// assert_eq!(triangles.len(), 32);
+/// let area: f64 = triangles.iter().map(|t| orient2d(points[t[0]], points[t[1]], points[t[2]]) / 2.0).sum();
+/// assert_eq!(area, 16.0);
+/// for t in &triangles {
+///     assert_eq!(orient2d(points[t[0]], points[t[1]], points[t[2]]), 1.0);
+/// }
+/// ```
+///
+/// Degenerate inputs
+/// ```
+/// use stones::geometry::delaunay_triangulate_2d;
+///
+/// // Duplicate points are ignored
+/// let points: [[f64; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+/// assert_eq!(delaunay_triangulate_2d(&points), vec![[0, 1, 3]]);
+///
+/// // Collinear points
+/// let points: [[f64; 2]; 3] = [[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]];
+/// assert!(delaunay_triangulate_2d(&points).is_empty());
+/// ```
+pub fn delaunay_triangulate_2d<T>(points: &[Vector2<T>]) -> Vec<[usize; 3]>
+    where T: Float
+{
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in points {
+        for i in 0..2 {
+            if p[i] < min[i] {
+                min[i] = p[i];
+            }
+            if p[i] > max[i] {
+                max[i] = p[i];
+            }
+        }
+    }
+
+    let two = T::from_f64(2.0);
+    let center = [(min[0] + max[0]) / two, (min[1] + max[1]) / two];
+    let extent = if max[0] - min[0] > max[1] - min[1] { max[0] - min[0] } else { max[1] - min[1] };
+    let extent = if extent > T::zero() { extent } else { T::one() };
+    let far = extent * T::from_f64(64.0);
+
+    // The super-triangle enclosing all the points uses the indices following the points
+    let mut vertices = points.to_vec();
+    vertices.push([center[0] - far, center[1] - extent]);
+    vertices.push([center[0] + far, center[1] - extent]);
+    vertices.push([center[0], center[1] + far]);
+    let mut triangles = vec![[points.len(), points.len() + 1, points.len() + 2]];
+
+    for (index, &p) in points.iter().enumerate() {
+        let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) = triangles.into_iter().partition(|t| {
+            in_circumcircle(vertices[t[0]], vertices[t[1]], vertices[t[2]], p) > T::zero()
+        });
+        triangles = good;
+
+        let duplicate = bad.is_empty() || bad.iter().any(|t| t.iter().any(|&v| vertices[v] == p));
+        if duplicate {
+            triangles.extend(bad);
+            continue;
+        }
+
+        // The edges of the cavity are the edges belonging to a single bad triangle
+        let edges: Vec<(usize, usize)> = bad.iter()
+            .flat_map(|t| vec![(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+            .collect();
+        for &(a, b) in &edges {
+            if !edges.contains(&(b, a)) {
+                triangles.push([a, b, index]);
+            }
+        }
+    }
+
+    triangles.into_iter()
+        .filter(|t| t.iter().all(|&v| v < points.len()))
+        .filter(|t| orient2d(points[t[0]], points[t[1]], points[t[2]]) > T::zero())
+        .collect()
+}
+
+/// Computes the Plücker coordinates of the line going through p and q
+///
+/// Returns the direction q - p and the moment p × q of the line.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::line_plucker;
+///
+/// assert_eq!(line_plucker([0, 1, 0], [1, 1, 0]), ([1, 0, 0], [0, 0, -1]));
+/// ```
+pub fn line_plucker<T>(p: Vector3<T>, q: Vector3<T>) -> (Vector3<T>, Vector3<T>)
+    where T: Copy + Sub<Output=T> + Mul<Output=T>
+{
+    (vec3_sub(q, p), vec3_cross(p, q))
+}
+
+/// Computes the permuted inner product of the Plücker coordinates of two lines
+///
+/// The result is zero if the lines intersect or are parallel, i.e. if they are coplanar.
+/// Otherwise, its sign tells on which side a line passes the other: it is positive if b
+/// turns clockwise around a when looking along the direction of a, and negative if it turns
+/// counter-clockwise. Reversing the direction of one of the lines negates the result.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{line_plucker, plucker_side};
+///
+/// let x_axis = line_plucker([0, 0, 0], [1, 0, 0]);
+///
+/// // Passing above the X axis along Y, and back
+/// let above = line_plucker([0, 0, 1], [0, 1, 1]);
+/// let above_reversed = line_plucker([0, 1, 1], [0, 0, 1]);
+/// assert!(plucker_side(x_axis, above) < 0);
+/// assert!(plucker_side(x_axis, above_reversed) > 0);
+/// assert_eq!(plucker_side(x_axis, above), plucker_side(above, x_axis));
+///
+/// // Passing below
+/// let below = line_plucker([0, 0, -1], [0, 1, -1]);
+/// assert!(plucker_side(x_axis, below) > 0);
+///
+/// // Intersecting lines
+/// assert_eq!(plucker_side(x_axis, line_plucker([3, -1, 0], [3, 1, 0])), 0);
+/// assert_eq!(plucker_side(x_axis, line_plucker([0, 0, 0], [0, 0, 1])), 0);
+///
+/// // Parallel lines
+/// assert_eq!(plucker_side(x_axis, line_plucker([0, 1, 1], [2, 1, 1])), 0);
+/// ```
+pub fn plucker_side<T>(a: (Vector3<T>, Vector3<T>), b: (Vector3<T>, Vector3<T>)) -> T
+    where T: Zero + Copy + Add<Output=T> + Mul<Output=T>
+{
+    vec3_dot(a.0, b.1) + vec3_dot(b.0, a.1)
+}
+
+/// Computes the intersection of a ray with a triangle using Plücker coordinates
+///
+/// Returns the ray parameter t of the intersection, or None if there is none. The sides of
+/// the ray relative to the edges are computed from the same Plücker coordinates whatever the
+/// triangle using the edge, and rays going exactly through an edge are attributed to a
+/// single triangle. The test is thus watertight: a ray hitting an edge shared by two
+/// triangles of a consistently wound mesh hits exactly one of them. Rays parallel to the
+/// triangle never hit it.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{Ray, ray_triangle_intersect_plucker};
+///
+/// let (a, b, c) = ([0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]);
+///
+/// let ray = Ray { origin: [0.5, 0.5, 2.0], direction: [0.0, 0.0, -1.0] };
+/// assert_eq!(ray_triangle_intersect_plucker(&ray, a, b, c), Some(2.0));
+///
+/// // Both sides are hit
+/// let ray = Ray { origin: [0.5, 0.5, -1.0], direction: [0.0, 0.0, 2.0] };
+/// assert_eq!(ray_triangle_intersect_plucker(&ray, a, b, c), Some(0.5));
+///
+/// // Missing the triangle, pointing away from it or parallel to it
+/// let ray = Ray { origin: [1.5, 1.5, 2.0], direction: [0.0, 0.0, -1.0] };
+/// assert_eq!(ray_triangle_intersect_plucker(&ray, a, b, c), None);
+/// let ray = Ray { origin: [0.5, 0.5, 2.0], direction: [0.0, 0.0, 1.0] };
+/// assert_eq!(ray_triangle_intersect_plucker(&ray, a, b, c), None);
+/// let ray = Ray { origin: [-1.0, 0.5, 0.0], direction: [1.0, 0.0, 0.0] };
+/// assert_eq!(ray_triangle_intersect_plucker(&ray, a, b, c), None);
+/// ```
+///
+/// Watertightness
+/// ```
+/// use stones::geometry::{Ray, ray_triangle_intersect_plucker};
+///
+/// // Square split along its diagonal into two triangles with the same winding
+/// let (a, b, c, d) = ([0.0, 0.0, 0.0], [1.0, 0.0, 0.3], [1.0, 1.0, 0.0], [0.0, 1.0, -0.2]);
+///
+/// for i in 1..16 {
+///     let s = i as f64 / 16.0;
+///     let on_diagonal = [s, s, 0.0];
+///     for &(dx, dy) in &[(0.0, 0.0), (0.3, -0.1), (-0.7, 0.2), (0.1, 0.9), (1e-9, -1e-9)] {
+///         for &height in &[1.0, -1.0] {
+///             let origin = [on_diagonal[0] + dx, on_diagonal[1] + dy, height];
+///             let direction = [on_diagonal[0] - origin[0], on_diagonal[1] - origin[1], -height];
+///             let ray = Ray { origin, direction };
+///
+///             let hits = [ray_triangle_intersect_plucker(&ray, a, b, c), ray_triangle_intersect_plucker(&ray, a, c, d)];
+///             assert_eq!(hits.iter().filter(|hit| hit.is_some()).count(), 1);
+///         }
+///     }
+/// }
+/// ```
+pub fn ray_triangle_intersect_plucker<T>(ray: &Ray<T>, a: Vector3<T>, b: Vector3<T>, c: Vector3<T>) -> Option<T>
+    where T: Float
+{
+    let ray_line = line_plucker(ray.origin, vec3_add(ray.origin, ray.direction));
+    let sides = [edge_side(ray_line, a, b), edge_side(ray_line, b, c), edge_side(ray_line, c, a)];
+    if sides.iter().any(|&side| side != sides[0]) {
+        return None;
+    }
+
+    let normal = vec3_cross(vec3_sub(b, a), vec3_sub(c, a));
+    let denominator = vec3_dot(normal, ray.direction);
+    if denominator == T::zero() {
+        return None;
+    }
+
+    let t = vec3_dot(normal, vec3_sub(a, ray.origin)) / denominator;
+    if t < T::zero() {
+        None
+    } else {
+        Some(t)
+    }
+}
+
+/// Returns whether a line passes on the positive side of the edge going from u to v
+///
+/// The side is computed from the edge oriented from its lexicographically smaller vertex to
+/// the other one, so that it is exactly reversed for the edge going from v to u, lines
+/// intersecting the edge being on the positive side of this canonical orientation.
+fn edge_side<T>(line: (Vector3<T>, Vector3<T>), u: Vector3<T>, v: Vector3<T>) -> bool
+    where T: Float
+{
+    if u < v {
+        plucker_side(line, line_plucker(u, v)) >= T::zero()
+    } else {
+        plucker_side(line, line_plucker(v, u)) < T::zero()
+    }
+}
+
+/// Returns the orientation of the triangle abc with integer coordinates
+///
+/// Returns 1 if the points are in counter-clockwise order, -1 if they are in clockwise order
+/// and 0 if they are collinear. The computation is exact for all i32 coordinates: the
+/// determinant is computed in 128 bits since it can take up to 66 bits.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::orient_2d_i64;
+///
+/// assert_eq!(orient_2d_i64([0, 0], [4, 0], [0, 3]), 1);
+/// assert_eq!(orient_2d_i64([0, 0], [0, 3], [4, 0]), -1);
+/// assert_eq!(orient_2d_i64([0, 0], [2, 1], [4, 2]), 0);
+///
+/// // No overflow with extreme coordinates
+/// let (min, max) = (i32::MIN, i32::MAX);
+/// assert_eq!(orient_2d_i64([min, min], [max, min], [min, max]), 1);
+/// assert_eq!(orient_2d_i64([min, min], [min, max], [max, min]), -1);
+/// assert_eq!(orient_2d_i64([min, min], [0, 0], [max, max]), 0);
+/// assert_eq!(orient_2d_i64([min, min], [max, max], [max, max - 1]), -1);
+/// assert_eq!(orient_2d_i64([min + 1, min], [max, max - 1], [max - 1, max - 1]), 1);
+/// ```
+pub fn orient_2d_i64(a: Vector2i, b: Vector2i, c: Vector2i) -> i64 {
+    let (abx, aby) = (b[0] as i128 - a[0] as i128, b[1] as i128 - a[1] as i128);
+    let (acx, acy) = (c[0] as i128 - a[0] as i128, c[1] as i128 - a[1] as i128);
+    (abx * acy - aby * acx).signum() as i64
+}
+
+/// Tests whether two closed segments with integer coordinates intersect
+///
+/// The endpoints belong to the segments: segments touching at an endpoint or overlapping
+/// while collinear intersect. Degenerate segments are treated as points. The test is exact.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::segments_intersect_i;
+///
+/// // Crossing
+/// assert!(segments_intersect_i([0, 0], [4, 4], [0, 4], [4, 0]));
+/// assert!(!segments_intersect_i([0, 0], [1, 1], [0, 4], [4, 0]));
+///
+/// // Touching
+/// assert!(segments_intersect_i([0, 0], [2, 2], [2, 2], [4, 0]));
+/// assert!(segments_intersect_i([0, 0], [4, 0], [2, 0], [2, 5]));
+/// assert!(!segments_intersect_i([0, 0], [4, 0], [2, 1], [2, 5]));
+///
+/// // Collinear
+/// assert!(segments_intersect_i([0, 0], [4, 2], [2, 1], [6, 3]));
+/// assert!(segments_intersect_i([0, 0], [4, 2], [4, 2], [6, 3]));
+/// assert!(!segments_intersect_i([0, 0], [4, 2], [6, 3], [8, 4]));
+/// assert!(segments_intersect_i([0, 0], [8, 4], [2, 1], [4, 2]));
+///
+/// // Parallel
+/// assert!(!segments_intersect_i([0, 0], [4, 2], [0, 1], [4, 3]));
+///
+/// // Points
+/// assert!(segments_intersect_i([0, 0], [4, 2], [2, 1], [2, 1]));
+/// assert!(!segments_intersect_i([0, 0], [4, 2], [2, 2], [2, 2]));
+/// assert!(segments_intersect_i([3, 3], [3, 3], [3, 3], [3, 3]));
+///
+/// // Extreme coordinates
+/// let (min, max) = (i32::MIN, i32::MAX);
+/// assert!(segments_intersect_i([min, min], [max, max], [min, max], [max, min]));
+/// assert!(!segments_intersect_i([min, min], [max, max - 1], [min + 1, min], [max, max - 1 - 1]));
+/// ```
+pub fn segments_intersect_i(a0: Vector2i, a1: Vector2i, b0: Vector2i, b1: Vector2i) -> bool {
+    let o1 = orient_2d_i64(a0, a1, b0);
+    let o2 = orient_2d_i64(a0, a1, b1);
+    let o3 = orient_2d_i64(b0, b1, a0);
+    let o4 = orient_2d_i64(b0, b1, a1);
+
+    if o1 * o2 < 0 && o3 * o4 < 0 {
+        return true;
+    }
+
+    (o1 == 0 && in_segment_bounds(a0, a1, b0))
+        || (o2 == 0 && in_segment_bounds(a0, a1, b1))
+        || (o3 == 0 && in_segment_bounds(b0, b1, a0))
+        || (o4 == 0 && in_segment_bounds(b0, b1, a1))
+}
+
+/// Tests whether a point with integer coordinates is inside a triangle
+///
+/// Points strictly inside the triangle are inside. Points on the edges follow the top-left
+/// rule used by rasterizers, with the Y axis pointing up: they are inside if they are on a
+/// top edge, i.e. a horizontal edge above the rest of the triangle, or on a left edge, i.e. a
+/// non-horizontal edge on the left side of the triangle. A point on an edge shared by two
+/// triangles, or on a vertex shared by triangles around it, thus belongs to exactly one of
+/// them. The orientation of the triangle doesn't matter and degenerate triangles contain no
+/// point.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::point_in_triangle_i;
+///
+/// let (a, b, c) = ([0, 0], [4, 0], [0, 4]);
+/// assert!(point_in_triangle_i(a, b, c, [1, 1]));
+/// assert!(point_in_triangle_i(a, c, b, [1, 1]));
+/// assert!(!point_in_triangle_i(a, b, c, [3, 3]));
+///
+/// // The left edge is inside, the bottom and the diagonal edges are not
+/// assert!(point_in_triangle_i(a, b, c, [0, 2]));
+/// assert!(!point_in_triangle_i(a, b, c, [2, 0]));
+/// assert!(!point_in_triangle_i(a, b, c, [2, 2]));
+///
+/// // Degenerate
+/// assert!(!point_in_triangle_i([0, 0], [2, 2], [4, 4], [2, 2]));
+/// ```
+///
+/// Shared edges and vertices
+/// ```
+/// use stones::geometry::point_in_triangle_i;
+///
+/// // Triangulation of a 4x4 grid of cells of size 2, alternating the diagonals
+/// let mut triangles = Vec::new();
+/// for y in 0..4 {
+///     for x in 0..4 {
+///         let (x0, y0, x1, y1) = (x * 2, y * 2, x * 2 + 2, y * 2 + 2);
+///         if (x + y) % 2 == 0 {
+///             triangles.push(([x0, y0], [x1, y0], [x1, y1]));
+///             triangles.push(([x0, y0], [x1, y1], [x0, y1]));
+///         } else {
+///             triangles.push(([x0, y0], [x1, y0], [x0, y1]));
+///             triangles.push(([x1, y0], [x0, y1], [x1, y1]));
+///         }
+///     }
+/// }
+///
+/// // Every point strictly inside the grid, vertices and edges included, belongs to exactly
+/// // one triangle
+/// for y in 1..8 {
+///     for x in 1..8 {
+///         let count = triangles.iter().filter(|(a, b, c)| point_in_triangle_i(*a, *b, *c, [x, y])).count();
+///         assert_eq!(count, 1);
+///     }
+/// }
+/// ```
+pub fn point_in_triangle_i(a: Vector2i, b: Vector2i, c: Vector2i, p: Vector2i) -> bool {
+    let (b, c) = match orient_2d_i64(a, b, c) {
+        0 => return false,
+        orientation if orientation < 0 => (c, b),
+        _ => (b, c),
+    };
+
+    [(a, b), (b, c), (c, a)].iter().all(|&(u, v)| {
+        match orient_2d_i64(u, v, p) {
+            0 => is_top_left_edge(u, v),
+            orientation => orientation > 0,
+        }
+    })
+}
+
+/// Tests whether p, collinear with the segment [a, b], lies within it
+fn in_segment_bounds(a: Vector2i, b: Vector2i, p: Vector2i) -> bool {
+    p[0] >= a[0].min(b[0]) && p[0] <= a[0].max(b[0])
+        && p[1] >= a[1].min(b[1]) && p[1] <= a[1].max(b[1])
+}
+
+/// Tests whether the edge going from u to v of a counter-clockwise triangle is a top or a
+/// left edge
+fn is_top_left_edge(u: Vector2i, v: Vector2i) -> bool {
+    v[1] < u[1] || (v[1] == u[1] && v[0] < u[0])
+}
+
+/// Returns the 2D line going through two points
+///
+/// The normal [a, b] of the line points to the left of the direction going from p to q, so
+/// the points on the left side of the line have a positive signed distance. The line is
+/// degenerate, i.e. all its coefficients are zero, if the points are equal.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::line_through_points;
+///
+/// assert_eq!(line_through_points([0.0, 1.0], [2.0, 1.0]), [0.0, 2.0, -2.0]);
+///
+/// // Vertical lines don't need any special case
+/// assert_eq!(line_through_points([3.0, 0.0], [3.0, 5.0]), [-5.0, 0.0, 15.0]);
+/// ```
+pub fn line_through_points<T>(p: Vector2<T>, q: Vector2<T>) -> Line2<T>
+    where T: Float
+{
+    vec3_cross([p[0], p[1], T::one()], [q[0], q[1], T::one()])
+}
+
+/// Computes the intersection point of two 2D lines
+///
+/// Returns None if the lines are parallel or coincident.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{line_through_points, line_intersection};
+///
+/// // Perpendicular lines
+/// let horizontal = line_through_points([0.0, 1.0], [2.0, 1.0]);
+/// let vertical = line_through_points([3.0, 0.0], [3.0, 5.0]);
+/// assert_eq!(line_intersection(horizontal, vertical), Some([3.0, 1.0]));
+///
+/// // Parallel lines
+/// let other = line_through_points([0.0, 4.0], [-1.0, 4.0]);
+/// assert_eq!(line_intersection(horizontal, other), None);
+/// assert_eq!(line_intersection(horizontal, horizontal), None);
+/// ```
+///
+/// Consistency with the segment intersection
+/// ```
+/// use stones::geometry::{line_through_points, line_intersection, segments_intersect_i};
+///
+/// let (a0, a1, b0, b1) = ([0, 0], [8, 4], [2, 6], [6, -2]);
+/// assert!(segments_intersect_i(a0, a1, b0, b1));
+///
+/// let to_f64 = |v: [i32; 2]| [v[0] as f64, v[1] as f64];
+/// let p = line_intersection(line_through_points(to_f64(a0), to_f64(a1)),
+///                           line_through_points(to_f64(b0), to_f64(b1))).unwrap();
+/// assert_eq!(p, [4.0, 2.0]);
+/// assert!(p[0] >= 0.0 && p[0] <= 8.0 && p[0] >= 2.0 && p[0] <= 6.0);
+/// ```
+pub fn line_intersection<T>(l1: Line2<T>, l2: Line2<T>) -> Option<Vector2<T>>
+    where T: Float
+{
+    let point = vec3_cross(l1, l2);
+    if point[2] == T::zero() {
+        return None;
+    }
+
+    Some([point[0] / point[2], point[1] / point[2]])
+}
+
+/// Computes the signed distance from a point to a 2D line
+///
+/// The distance is positive on the side the normal [a, b] of the line points to, i.e. on the
+/// left side of a line built with `line_through_points`. The normal of the line must not be
+/// zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{line_through_points, line_distance_to_point};
+///
+/// let line = line_through_points([0.0, 1.0], [2.0, 1.0]);
+/// assert_eq!(line_distance_to_point(line, [5.0, 4.0]), 3.0);
+/// assert_eq!(line_distance_to_point(line, [5.0, -1.0]), -2.0);
+/// assert_eq!(line_distance_to_point(line, [-7.0, 1.0]), 0.0);
+///
+/// // Reversing the points flips the sign
+/// let line = line_through_points([2.0, 1.0], [0.0, 1.0]);
+/// assert_eq!(line_distance_to_point(line, [5.0, 4.0]), -3.0);
+///
+/// let vertical = line_through_points([3.0, 0.0], [3.0, 5.0]);
+/// assert_eq!(line_distance_to_point(vertical, [1.0, 100.0]), 2.0);
+/// ```
+pub fn line_distance_to_point<T>(line: Line2<T>, point: Vector2<T>) -> T
+    where T: Float
+{
+    (line[0] * point[0] + line[1] * point[1] + line[2]) / (line[0] * line[0] + line[1] * line[1]).sqrt()
+}
+
+/// Scales the coefficients of a 2D line so its normal [a, b] has a unit length
+///
+/// The coefficient c of the normalized line is then the signed distance from the origin to
+/// the line. Returns None if the normal of the line is zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::geometry::{line_through_points, line_normalize};
+///
+/// let line = line_through_points([0.0, 1.0], [2.0, 1.0]);
+/// assert_eq!(line_normalize(line), Some([0.0, 1.0, -1.0]));
+/// assert_eq!(line_normalize([3.0, 4.0, 10.0]), Some([0.6, 0.8, 2.0]));
+/// assert_eq!(line_normalize([0.0, 0.0, 1.0]), None);
+/// ```
+pub fn line_normalize<T>(line: Line2<T>) -> Option<Line2<T>>
+    where T: Float
+{
+    let length = (line[0] * line[0] + line[1] * line[1]).sqrt();
+    if length == T::zero() {
+        return None;
+    }
+
+    Some([line[0] / length, line[1] / length, line[2] / length])
+}
@@ -24,4 +24,13 @@
 
 pub mod vector;
 pub mod matrix;
-pub mod number_traits;
\ No newline at end of file
+pub mod number_traits;
+pub mod geometry;
+pub mod sampling;
+pub mod quaternion;
+pub mod transform;
+pub mod bytes;
+pub mod physics;
+pub mod sampler;
+pub mod diff;
+pub mod testing;
\ No newline at end of file
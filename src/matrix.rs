@@ -22,21 +22,61 @@
 * SOFTWARE.
 */
 
-use std::ops::{Add, Sub, Mul};
-use crate::vector::Vector4;
-use crate::number_traits::{One, Zero};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use crate::vector::{Vector2, Vector3, Vector3d, Vector4, vec3_cross, vec3_dot, vec3_mul, vec3_outer, vec3_sub};
+use crate::number_traits::{One, Zero, Float};
 
+/// 2x2 matrix stored in row-major order, the component at row r and column c being at
+/// index r * 2 + c
+///
+/// Vectors are column vectors multiplied on the right of the matrix. Use
+/// `mat2_to_col_major` and `mat2_from_col_major` to exchange matrices with column-major
+/// APIs.
 pub type Matrix2<T> = [T; 4];
 pub type Matrix2i = Matrix2<i32>;
 pub type Matrix2f = Matrix2<f32>;
+pub type Matrix2d = Matrix2<f64>;
 
+/// 3x3 matrix stored in row-major order, the component at row r and column c being at
+/// index r * 3 + c
+///
+/// Vectors are column vectors multiplied on the right of the matrix. Use
+/// `mat3_to_col_major` and `mat3_from_col_major` to exchange matrices with column-major
+/// APIs.
 pub type Matrix3<T> = [T; 9];
 pub type Matrix3i = Matrix3<i32>;
 pub type Matrix3f = Matrix3<f32>;
+pub type Matrix3d = Matrix3<f64>;
 
+/// 4x4 matrix stored in row-major order, the component at row r and column c being at
+/// index r * 4 + c
+///
+/// Vectors are column vectors multiplied on the right of the matrix. Use
+/// `mat4_to_col_major` and `mat4_from_col_major` to exchange matrices with column-major
+/// APIs.
 pub type Matrix4<T> = [T; 16];
 pub type Matrix4i = Matrix4<i32>;
 pub type Matrix4f = Matrix4<f32>;
+pub type Matrix4d = Matrix4<f64>;
+
+/// Orientation of the basis of a transform, see `mat4_handedness`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handedness {
+    /// The determinant is positive, the transform preserves the orientation and the winding order
+    RightHanded,
+    /// The determinant is negative, the transform mirrors the space and flips the winding order
+    LeftHanded,
+    /// The determinant is within the tolerance of zero, the transform flattens the space
+    Degenerate,
+}
+
+/// Coordinate axis, see `mat4_axis_swap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
 
 /// Returns the 2x2 identity matrix
 ///
@@ -104,386 +144,5740 @@ pub fn mat4_identity<T>() -> Matrix4<T>
     ]
 }
 
-/// Adds two 2x2 matrices together
+/// Builds a 3x3 matrix from its rows
 ///
-/// # Exmaples
+/// # Examples
 ///
 /// ```
-/// use stones::matrix::mat2_add;
+/// use stones::matrix::{mat3_from_rows, mat3_row};
 ///
-/// let m1 = [2, 4,
-///           5, 9];
-/// let m2 = [1, 9,
-///           1, -2];
-/// assert_eq!(mat2_add(m1, m2), [3, 13,
-///                               6, 7]);
+/// let m = mat3_from_rows([[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+/// assert_eq!(m, [1, 2, 3,
+///                4, 5, 6,
+///                7, 8, 9]);
+/// assert_eq!(mat3_row(m, 1), [4, 5, 6]);
 /// ```
-pub fn mat2_add<T>(lhs: Matrix2<T>, rhs: Matrix2<T>) -> Matrix2<T>
-    where T: Copy + Add<Output=T>
+pub fn mat3_from_rows<T>(rows: [Vector3<T>; 3]) -> Matrix3<T>
+    where T: Copy
 {
     [
-        lhs[0] + rhs[0], lhs[1] + rhs[1],
-        lhs[2] + rhs[2], lhs[3] + rhs[3],
+        rows[0][0], rows[0][1], rows[0][2],
+        rows[1][0], rows[1][1], rows[1][2],
+        rows[2][0], rows[2][1], rows[2][2]
     ]
 }
 
-/// Adds two 3x3 matrices together
+/// Builds a 3x3 matrix from its columns
 ///
-/// # Exmaples
+/// # Examples
 ///
 /// ```
-/// use stones::matrix::mat3_add;
+/// use stones::matrix::{mat3_from_cols, mat3_col, mat3_identity};
 ///
-/// let m1 = [2, 4, 5,
-///           9, 1, 1,
-///           4, 2, 1];
-/// let m2 = [1, 9, 6,
-///           1, -2, 9,
-///           10, 3, 5];
-/// assert_eq!(mat3_add(m1, m2), [3, 13, 11,
-///                               10, -1, 10,
-///                               14, 5, 6]);
+/// assert_eq!(mat3_from_cols([[1, 0, 0], [0, 1, 0], [0, 0, 1]]), mat3_identity());
+///
+/// let m = mat3_from_cols([[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+/// assert_eq!(m, [1, 4, 7,
+///                2, 5, 8,
+///                3, 6, 9]);
+/// assert_eq!(mat3_col(m, 2), [7, 8, 9]);
 /// ```
-pub fn mat3_add<T>(lhs: Matrix3<T>, rhs: Matrix3<T>) -> Matrix3<T>
-    where T: Copy + Add<Output=T>
+pub fn mat3_from_cols<T>(cols: [Vector3<T>; 3]) -> Matrix3<T>
+    where T: Copy
 {
     [
-        lhs[0] + rhs[0], lhs[1] + rhs[1], lhs[2] + rhs[2],
-        lhs[3] + rhs[3], lhs[4] + rhs[4], lhs[5] + rhs[5],
-        lhs[6] + rhs[6], lhs[7] + rhs[7], lhs[8] + rhs[8],
+        cols[0][0], cols[1][0], cols[2][0],
+        cols[0][1], cols[1][1], cols[2][1],
+        cols[0][2], cols[1][2], cols[2][2]
     ]
 }
 
-/// Adds two 4x4 matrices together
+/// Builds a 4x4 matrix from its rows
 ///
-/// # Exmaples
+/// # Examples
 ///
 /// ```
-/// use stones::matrix::mat4_add;
+/// use stones::matrix::{mat4_from_rows, mat4_row};
 ///
-/// let m1 = [2, 4, 5, 1,
-///           9, 1, 1, 3,
-///           4, 2, 1, 2,
-///           1, 0, 2, 2];
-/// let m2 = [1, 9, 6, 5,
-///           1, -2, 9, 9,
-///           10, 3, 5, 4,
-///            4, 3, 1, 0];
-/// assert_eq!(mat4_add(m1, m2), [3, 13, 11, 6,
-///                              10, -1, 10, 12,
-///                              14, 5, 6, 6,
-///                               5, 3, 3, 2]);
+/// let rows = [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 16]];
+/// let m = mat4_from_rows(rows);
+/// assert_eq!(m, [1, 2, 3, 4,
+///                5, 6, 7, 8,
+///                9, 10, 11, 12,
+///                13, 14, 15, 16]);
+/// for i in 0..4 {
+///     assert_eq!(mat4_row(m, i), rows[i]);
+/// }
 /// ```
-pub fn mat4_add<T>(lhs: Matrix4<T>, rhs: Matrix4<T>) -> Matrix4<T>
-    where T: Copy + Add<Output=T>
+pub fn mat4_from_rows<T>(rows: [Vector4<T>; 4]) -> Matrix4<T>
+    where T: Copy
 {
     [
-        lhs[0] + rhs[0], lhs[1] + rhs[1], lhs[2] + rhs[2], lhs[3] + rhs[3],
-        lhs[4] + rhs[4], lhs[5] + rhs[5], lhs[6] + rhs[6], lhs[7] + rhs[7],
-        lhs[8] + rhs[8], lhs[9] + rhs[9], lhs[10] + rhs[10], lhs[11] + rhs[11],
-        lhs[12] + rhs[12], lhs[13] + rhs[13], lhs[14] + rhs[14], lhs[15] + rhs[15],
+        rows[0][0], rows[0][1], rows[0][2], rows[0][3],
+        rows[1][0], rows[1][1], rows[1][2], rows[1][3],
+        rows[2][0], rows[2][1], rows[2][2], rows[2][3],
+        rows[3][0], rows[3][1], rows[3][2], rows[3][3]
     ]
 }
 
-/// Subtracts a 2x2 matrix from another
+/// Builds a 4x4 matrix from its columns
 ///
-/// # Exmaples
+/// # Examples
 ///
 /// ```
-/// use stones::matrix::mat2_sub;
+/// use stones::matrix::{mat4_from_cols, mat4_col, mat4_identity, mat4_translation};
 ///
-/// let m1 = [2, 4,
-///           5, 9];
-/// let m2 = [1, 9,
-///           1, -2];
-/// assert_eq!(mat2_sub(m1, m2), [1, -5,
-///                               4, 11]);
+/// assert_eq!(mat4_from_cols([[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]), mat4_identity());
+///
+/// // The translation is the last column
+/// let m = mat4_from_cols([[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [5, -2, 3, 1]]);
+/// assert_eq!(m, mat4_translation([5, -2, 3]));
+/// assert_eq!(mat4_col(m, 3), [5, -2, 3, 1]);
 /// ```
-pub fn mat2_sub<T>(lhs: Matrix2<T>, rhs: Matrix2<T>) -> Matrix2<T>
-    where T: Copy + Sub<Output=T>
+pub fn mat4_from_cols<T>(cols: [Vector4<T>; 4]) -> Matrix4<T>
+    where T: Copy
 {
     [
-        lhs[0] - rhs[0], lhs[1] - rhs[1],
-        lhs[2] - rhs[2], lhs[3] - rhs[3],
+        cols[0][0], cols[1][0], cols[2][0], cols[3][0],
+        cols[0][1], cols[1][1], cols[2][1], cols[3][1],
+        cols[0][2], cols[1][2], cols[2][2], cols[3][2],
+        cols[0][3], cols[1][3], cols[2][3], cols[3][3]
     ]
 }
 
-/// Subtracts a 3x3 matrix from another
+/// Returns a row of a 3x3 matrix
 ///
-/// # Exmaples
+/// # Panics
+///
+/// Panics if the index is greater than 2.
+///
+/// # Examples
 ///
 /// ```
-/// use stones::matrix::mat3_sub;
+/// use stones::matrix::mat3_row;
 ///
-/// let m1 = [2, 4, 5,
-///           9, 1, 1,
-///           4, 2, 1];
-/// let m2 = [1, 9, 6,
-///           1, -2, 9,
-///           10, 3, 5];
-/// assert_eq!(mat3_sub(m1, m2), [1, -5, -1,
-///                               8, 3, -8,
-///                               -6, -1, -4]);
+/// assert_eq!(mat3_row([1, 2, 3, 4, 5, 6, 7, 8, 9], 2), [7, 8, 9]);
 /// ```
-pub fn mat3_sub<T>(lhs: Matrix3<T>, rhs: Matrix3<T>) -> Matrix3<T>
-    where T: Copy + Sub<Output=T>
+pub fn mat3_row<T>(m: Matrix3<T>, index: usize) -> Vector3<T>
+    where T: Copy
 {
-    [
-        lhs[0] - rhs[0], lhs[1] - rhs[1], lhs[2] - rhs[2],
-        lhs[3] - rhs[3], lhs[4] - rhs[4], lhs[5] - rhs[5],
-        lhs[6] - rhs[6], lhs[7] - rhs[7], lhs[8] - rhs[8],
-    ]
+    assert!(index < 3, "row index out of range: {}", index);
+    [m[index * 3], m[index * 3 + 1], m[index * 3 + 2]]
 }
 
-/// Subtracts a 4x4 matrix from another
+/// Returns a column of a 3x3 matrix
 ///
-/// # Exmaples
+/// # Panics
+///
+/// Panics if the index is greater than 2.
+///
+/// # Examples
 ///
 /// ```
-/// use stones::matrix::mat4_sub;
+/// use stones::matrix::mat3_col;
 ///
-/// let m1 = [2, 4, 5, 1,
-///           9, 1, 1, 3,
-///           4, 2, 1, 2,
-///           1, 0, 2, 2];
-/// let m2 = [1, 9, 6, 5,
-///           1, -2, 9, 9,
-///           10, 3, 5, 4,
-///            4, 3, 1, 0];
-/// assert_eq!(mat4_sub(m1, m2), [1, -5, -1, -4,
-///                              8, 3, -8, -6,
-///                              -6, -1, -4, -2,
-///                               -3, -3, 1, 2]);
+/// assert_eq!(mat3_col([1, 2, 3, 4, 5, 6, 7, 8, 9], 0), [1, 4, 7]);
 /// ```
-pub fn mat4_sub<T>(lhs: Matrix4<T>, rhs: Matrix4<T>) -> Matrix4<T>
-    where T: Copy + Sub<Output=T>
+pub fn mat3_col<T>(m: Matrix3<T>, index: usize) -> Vector3<T>
+    where T: Copy
 {
-    [
-        lhs[0] - rhs[0], lhs[1] - rhs[1], lhs[2] - rhs[2], lhs[3] - rhs[3],
-        lhs[4] - rhs[4], lhs[5] - rhs[5], lhs[6] - rhs[6], lhs[7] - rhs[7],
-        lhs[8] - rhs[8], lhs[9] - rhs[9], lhs[10] - rhs[10], lhs[11] - rhs[11],
-        lhs[12] - rhs[12], lhs[13] - rhs[13], lhs[14] - rhs[14], lhs[15] - rhs[15],
-    ]
+    assert!(index < 3, "column index out of range: {}", index);
+    [m[index], m[index + 3], m[index + 6]]
 }
 
-/// Multiplies a 2x2 matrix by a scalar
+/// Returns a row of a 4x4 matrix
+///
+/// # Panics
+///
+/// Panics if the index is greater than 3.
 ///
 /// # Examples
 ///
 /// ```
-/// use stones::matrix::mat2_scale;
+/// use stones::matrix::{mat4_row, mat4_translation};
 ///
-/// let m = [1, 2,
-///          3, 4];
-/// let scalar = 2;
-/// assert_eq!(mat2_scale(m, scalar), [2, 4,
-///                                  6, 8]);
+/// assert_eq!(mat4_row(mat4_translation([5, -2, 3]), 1), [0, 1, 0, -2]);
 /// ```
-pub fn mat2_scale<T>(lhs: Matrix2<T>, rhs: T) -> Matrix2<T>
-    where T: Copy + Mul<Output=T>
+pub fn mat4_row<T>(m: Matrix4<T>, index: usize) -> Vector4<T>
+    where T: Copy
 {
-    [
-        lhs[0] * rhs, lhs[1] * rhs,
-        lhs[2] * rhs, lhs[3] * rhs
-    ]
+    assert!(index < 4, "row index out of range: {}", index);
+    [m[index * 4], m[index * 4 + 1], m[index * 4 + 2], m[index * 4 + 3]]
 }
 
-/// Multiplies a 3x3 matrix by a scalar
+/// Returns a column of a 4x4 matrix
+///
+/// # Panics
+///
+/// Panics if the index is greater than 3.
 ///
 /// # Examples
 ///
 /// ```
-/// use stones::matrix::mat3_scale;
+/// use stones::matrix::{mat4_col, mat4_translation};
 ///
-/// let m = [1, 2, 3,
-///          4, 5, 6,
-///          7, 8, 9];
-/// let scalar = 2;
-/// assert_eq!(mat3_scale(m, scalar), [2, 4, 6,
-///                                  8, 10, 12,
-///                                  14, 16, 18]);
+/// assert_eq!(mat4_col(mat4_translation([5, -2, 3]), 3), [5, -2, 3, 1]);
 /// ```
-pub fn mat3_scale<T>(lhs: Matrix3<T>, rhs: T) -> Matrix3<T>
-    where T: Copy + Mul<Output=T>
+pub fn mat4_col<T>(m: Matrix4<T>, index: usize) -> Vector4<T>
+    where T: Copy
 {
-    [
-        lhs[0] * rhs, lhs[1] * rhs, lhs[2] * rhs,
-        lhs[3] * rhs, lhs[4] * rhs, lhs[5] * rhs,
-        lhs[6] * rhs, lhs[7] * rhs, lhs[8] * rhs
-    ]
+    assert!(index < 4, "column index out of range: {}", index);
+    [m[index], m[index + 4], m[index + 8], m[index + 12]]
 }
 
-/// Multiplies a 4x4 matrix by a scalar
+/// Returns the upper-left 3x3 block of a 4x4 matrix
 ///
 /// # Examples
 ///
 /// ```
-/// use stones::matrix::mat4_scale;
+/// use stones::matrix::{mat4_to_mat3, mat3_to_mat4, mat4_rotation_axis};
 ///
 /// let m = [1, 2, 3, 4,
 ///          5, 6, 7, 8,
 ///          9, 10, 11, 12,
 ///          13, 14, 15, 16];
-/// let scalar = 2;
-/// assert_eq!(mat4_scale(m, scalar), [2, 4, 6, 8,
-///                                  10, 12, 14, 16,
-///                                  18, 20, 22, 24,
-///                                  26, 28, 30, 32]);
+/// assert_eq!(mat4_to_mat3(m), [1, 2, 3,
+///                              5, 6, 7,
+///                              9, 10, 11]);
+///
+/// // Round trip for rotations
+/// let rotation = mat4_rotation_axis([1.0, -2.0, 0.5], 0.9);
+/// assert_eq!(mat3_to_mat4(mat4_to_mat3(rotation)), rotation);
 /// ```
-pub fn mat4_scale<T>(lhs: Matrix4<T>, rhs: T) -> Matrix4<T>
-    where T: Copy + Mul<Output=T>
+pub fn mat4_to_mat3<T>(m: Matrix4<T>) -> Matrix3<T>
+    where T: Copy
 {
     [
-        lhs[0] * rhs, lhs[1] * rhs, lhs[2] * rhs, lhs[3] * rhs,
-        lhs[4] * rhs, lhs[5] * rhs, lhs[6] * rhs, lhs[7] * rhs,
-        lhs[8] * rhs, lhs[9] * rhs, lhs[10] * rhs, lhs[11] * rhs,
-        lhs[12] * rhs, lhs[13] * rhs, lhs[14] * rhs, lhs[15] * rhs,
+        m[0], m[1], m[2],
+        m[4], m[5], m[6],
+        m[8], m[9], m[10]
     ]
 }
 
-/// Multiplies two 2x2 matrices together
+/// Embeds a 3x3 matrix in the upper-left block of a 4x4 identity matrix
 ///
 /// # Examples
 ///
 /// ```
-/// use stones::matrix::mat2_mul;
+/// use stones::matrix::{mat3_to_mat4, mat4_to_mat3, mat4_transform_vec, mat3_transform_vec};
 ///
-/// let m1 = [1, 2,
-///           3, 4];
-/// let m2 = [5, 6,
-///           7, 8];
+/// let m = [1, 2, 3,
+///          4, 5, 6,
+///          7, 8, 9];
+/// assert_eq!(mat3_to_mat4(m), [1, 2, 3, 0,
+///                              4, 5, 6, 0,
+///                              7, 8, 9, 0,
+///                              0, 0, 0, 1]);
+/// assert_eq!(mat4_to_mat3(mat3_to_mat4(m)), m);
 ///
-/// assert_eq!(mat2_mul(m1, m2), [19, 22,
-///                               43, 50]);
+/// // Directions are transformed like with the 3x3 matrix
+/// let v = mat4_transform_vec(mat3_to_mat4(m), [1, -2, 3, 0]);
+/// assert_eq!(v, [6, 12, 18, 0]);
+/// assert_eq!(v[0..3], mat3_transform_vec(m, [1, -2, 3]));
 /// ```
-pub fn mat2_mul<T>(lhs: Matrix2<T>, rhs: Matrix2<T>) -> Matrix2<T>
-    where T: Copy + Mul<Output=T> + Add<Output=T>
+pub fn mat3_to_mat4<T>(m: Matrix3<T>) -> Matrix4<T>
+    where T: One + Zero + Copy
 {
     [
-        lhs[0] * rhs[0] + lhs[1] * rhs[2], lhs[0] * rhs[1] + lhs[1] * rhs[3],
-        lhs[2] * rhs[0] + lhs[3] * rhs[2], lhs[2] * rhs[1] + lhs[3] * rhs[3]
+        m[0], m[1], m[2], T::zero(),
+        m[3], m[4], m[5], T::zero(),
+        m[6], m[7], m[8], T::zero(),
+        T::zero(), T::zero(), T::zero(), T::one()
     ]
 }
 
-
-/// Multiplies two 3x3 matrices together
+/// Returns a 4x4 translation matrix
 ///
 /// # Examples
 ///
 /// ```
-/// use stones::matrix::mat3_mul;
+/// use stones::matrix::{mat4_translation, mat4_transform_vec};
 ///
-/// let m1 = [1, 2, 3,
-///           4, 5, 6,
-///           7, 8, 9];
-/// let m2 = [10, 11, 12,
-///           13, 14, 15,
-///           16, 17, 18];
+/// let m = mat4_translation([5, -2, 3]);
+/// assert_eq!(m, [1, 0, 0, 5,
+///                0, 1, 0, -2,
+///                0, 0, 1, 3,
+///                0, 0, 0, 1]);
 ///
-/// assert_eq!(mat3_mul(m1, m2), [84, 90, 96,
-///                               201, 216, 231,
-///                               318, 342, 366]);
+/// // Points are translated, directions are not
+/// assert_eq!(mat4_transform_vec(m, [1, 1, 1, 1]), [6, -1, 4, 1]);
+/// assert_eq!(mat4_transform_vec(m, [1, 1, 1, 0]), [1, 1, 1, 0]);
 /// ```
-pub fn mat3_mul<T>(lhs: Matrix3<T>, rhs: Matrix3<T>) -> Matrix3<T>
-    where T: Copy + Mul<Output=T> + Add<Output=T>
+pub fn mat4_translation<T>(t: Vector3<T>) -> Matrix4<T>
+    where T: One + Zero + Copy
 {
     [
-        lhs[0] * rhs[0] + lhs[1] * rhs[3] + lhs[2] * rhs[6],
-        lhs[0] * rhs[1] + lhs[1] * rhs[4] + lhs[2] * rhs[7],
-        lhs[0] * rhs[2] + lhs[1] * rhs[5] + lhs[2] * rhs[8],
-
-        lhs[3] * rhs[0] + lhs[4] * rhs[3] + lhs[5] * rhs[6],
-        lhs[3] * rhs[1] + lhs[4] * rhs[4] + lhs[5] * rhs[7],
-        lhs[3] * rhs[2] + lhs[4] * rhs[5] + lhs[5] * rhs[8],
-
-        lhs[6] * rhs[0] + lhs[7] * rhs[3] + lhs[8] * rhs[6],
-        lhs[6] * rhs[1] + lhs[7] * rhs[4] + lhs[8] * rhs[7],
-        lhs[6] * rhs[2] + lhs[7] * rhs[5] + lhs[8] * rhs[8]
+        T::one(), T::zero(), T::zero(), t[0],
+        T::zero(), T::one(), T::zero(), t[1],
+        T::zero(), T::zero(), T::one(), t[2],
+        T::zero(), T::zero(), T::zero(), T::one()
     ]
 }
 
-/// Multiplies two 4x4 matrices together
+/// Returns a 4x4 scaling matrix
 ///
 /// # Examples
 ///
 /// ```
-/// use stones::matrix::mat4_mul;
+/// use stones::matrix::{mat4_scaling, mat4_transform_vec, mat4_mul};
 ///
-/// let m1 = [1, 2, 3, 4,
-///           5, 6, 7, 8,
-///           9, 10, 11, 12,
-///           13, 14, 15, 16];
-/// let m2 = [17, 18, 19, 20,
-///           21, 22, 23, 24,
-///           25, 26, 27, 28,
-///           29, 30, 31, 32];
+/// let m = mat4_scaling([2, 3, 4]);
+/// assert_eq!(m, [2, 0, 0, 0,
+///                0, 3, 0, 0,
+///                0, 0, 4, 0,
+///                0, 0, 0, 1]);
+/// assert_eq!(mat4_transform_vec(m, [1, 1, 1, 1]), [2, 3, 4, 1]);
 ///
-/// assert_eq!(mat4_mul(m1, m2), [250, 260, 270, 280,
-///                               618, 644, 670, 696,
-///                               986, 1028, 1070, 1112,
-///                               1354, 1412, 1470, 1528]);
+/// // Composing scalings multiplies the scale factors
+/// assert_eq!(mat4_mul(m, mat4_scaling([5, -1, 2])), mat4_scaling([10, -3, 8]));
 /// ```
-pub fn mat4_mul<T>(lhs: Matrix4<T>, rhs: Matrix4<T>) -> Matrix4<T>
-    where T: Copy + Mul<Output=T> + Add<Output=T>
+pub fn mat4_scaling<T>(s: Vector3<T>) -> Matrix4<T>
+    where T: One + Zero + Copy
 {
     [
-        lhs[0] * rhs[0] + lhs[1] * rhs[4] + lhs[2] * rhs[8] + lhs[3] * rhs[12],
-        lhs[0] * rhs[1] + lhs[1] * rhs[5] + lhs[2] * rhs[9] + lhs[3] * rhs[13],
-        lhs[0] * rhs[2] + lhs[1] * rhs[6] + lhs[2] * rhs[10] + lhs[3] * rhs[14],
-        lhs[0] * rhs[3] + lhs[1] * rhs[7] + lhs[2] * rhs[11] + lhs[3] * rhs[15],
-
-        lhs[4] * rhs[0] + lhs[5] * rhs[4] + lhs[6] * rhs[8] + lhs[7] * rhs[12],
-        lhs[4] * rhs[1] + lhs[5] * rhs[5] + lhs[6] * rhs[9] + lhs[7] * rhs[13],
-        lhs[4] * rhs[2] + lhs[5] * rhs[6] + lhs[6] * rhs[10] + lhs[7] * rhs[14],
-        lhs[4] * rhs[3] + lhs[5] * rhs[7] + lhs[6] * rhs[11] + lhs[7] * rhs[15],
-
-        lhs[8] * rhs[0] + lhs[9] * rhs[4] + lhs[10] * rhs[8] + lhs[11] * rhs[12],
-        lhs[8] * rhs[1] + lhs[9] * rhs[5] + lhs[10] * rhs[9] + lhs[11] * rhs[13],
-        lhs[8] * rhs[2] + lhs[9] * rhs[6] + lhs[10] * rhs[10] + lhs[11] * rhs[14],
-        lhs[8] * rhs[3] + lhs[9] * rhs[7] + lhs[10] * rhs[11] + lhs[11] * rhs[15],
-
-        lhs[12] * rhs[0] + lhs[13] * rhs[4] + lhs[14] * rhs[8] + lhs[15] * rhs[12],
-        lhs[12] * rhs[1] + lhs[13] * rhs[5] + lhs[14] * rhs[9] + lhs[15] * rhs[13],
-        lhs[12] * rhs[2] + lhs[13] * rhs[6] + lhs[14] * rhs[10] + lhs[15] * rhs[14],
-        lhs[12] * rhs[3] + lhs[13] * rhs[7] + lhs[14] * rhs[11] + lhs[15] * rhs[15]
+        s[0], T::zero(), T::zero(), T::zero(),
+        T::zero(), s[1], T::zero(), T::zero(),
+        T::zero(), T::zero(), s[2], T::zero(),
+        T::zero(), T::zero(), T::zero(), T::one()
     ]
 }
 
-/// Transforms a vector using a 4x4 matrix
+/// Returns a 4x4 matrix scaling uniformly along all the axes
 ///
 /// # Examples
 ///
-/// Identity
 /// ```
-/// use stones::matrix::mat4_transform_vec;
-/// use stones::matrix::mat4_identity;
-///
-/// let m = mat4_identity();
-/// let v = [5, 7, 2, 3];
+/// use stones::matrix::{mat4_scaling, mat4_scaling_uniform, mat4_transform_vec, mat4_mul};
 ///
-/// assert_eq!(mat4_transform_vec(m, v), [5, 7, 2, 3]);
+/// assert_eq!(mat4_scaling_uniform(3), mat4_scaling([3, 3, 3]));
+/// assert_eq!(mat4_transform_vec(mat4_scaling_uniform(3), [1, 2, 3, 1]), [3, 6, 9, 1]);
+/// assert_eq!(mat4_mul(mat4_scaling_uniform(3), mat4_scaling_uniform(2)), mat4_scaling_uniform(6));
 /// ```
-///
+pub fn mat4_scaling_uniform<T>(s: T) -> Matrix4<T>
+    where T: One + Zero + Copy
+{
+    mat4_scaling([s, s, s])
+}
+
+/// Returns a 4x4 matrix rotating by `angle` radians around the X axis
+///
+/// The rotation is counter-clockwise when looking from the positive X axis towards the
+/// origin.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_rotation_x, mat4_transform_vec};
+///
+/// let v = mat4_transform_vec(mat4_rotation_x(std::f32::consts::FRAC_PI_2), [0.0, 1.0, 0.0, 1.0]);
+/// let expected = [0.0, 0.0, 1.0, 1.0];
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-6);
+/// }
+///
+/// // A full turn goes back to the start
+/// let v = mat4_transform_vec(mat4_rotation_x(2.0 * std::f64::consts::PI), [1.0, 2.0, 3.0, 1.0]);
+/// let expected = [1.0, 2.0, 3.0, 1.0];
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat4_rotation_x<T>(angle: T) -> Matrix4<T>
+    where T: Float
+{
+    let (sin, cos) = (angle.sin(), angle.cos());
+    let (zero, one) = (T::zero(), T::one());
+    [
+        one, zero, zero, zero,
+        zero, cos, -sin, zero,
+        zero, sin, cos, zero,
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a 4x4 matrix rotating by `angle` radians around the Y axis
+///
+/// The rotation is counter-clockwise when looking from the positive Y axis towards the
+/// origin.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_rotation_y, mat4_transform_vec};
+///
+/// let v = mat4_transform_vec(mat4_rotation_y(std::f32::consts::FRAC_PI_2), [0.0, 0.0, 1.0, 1.0]);
+/// let expected = [1.0, 0.0, 0.0, 1.0];
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-6);
+/// }
+///
+/// // A full turn goes back to the start
+/// let v = mat4_transform_vec(mat4_rotation_y(2.0 * std::f64::consts::PI), [1.0, 2.0, 3.0, 1.0]);
+/// let expected = [1.0, 2.0, 3.0, 1.0];
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat4_rotation_y<T>(angle: T) -> Matrix4<T>
+    where T: Float
+{
+    let (sin, cos) = (angle.sin(), angle.cos());
+    let (zero, one) = (T::zero(), T::one());
+    [
+        cos, zero, sin, zero,
+        zero, one, zero, zero,
+        -sin, zero, cos, zero,
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a 4x4 matrix rotating by `angle` radians around the Z axis
+///
+/// The rotation is counter-clockwise when looking from the positive Z axis towards the
+/// origin.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_rotation_z, mat4_transform_vec};
+///
+/// let v = mat4_transform_vec(mat4_rotation_z(std::f32::consts::FRAC_PI_2), [1.0, 0.0, 0.0, 1.0]);
+/// let expected = [0.0, 1.0, 0.0, 1.0];
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-6);
+/// }
+///
+/// // A full turn goes back to the start
+/// let v = mat4_transform_vec(mat4_rotation_z(2.0 * std::f64::consts::PI), [1.0, 2.0, 3.0, 1.0]);
+/// let expected = [1.0, 2.0, 3.0, 1.0];
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat4_rotation_z<T>(angle: T) -> Matrix4<T>
+    where T: Float
+{
+    let (sin, cos) = (angle.sin(), angle.cos());
+    let (zero, one) = (T::zero(), T::one());
+    [
+        cos, -sin, zero, zero,
+        sin, cos, zero, zero,
+        zero, zero, one, zero,
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a 4x4 matrix rotating by `angle` radians around an arbitrary axis
+///
+/// The axis doesn't need to be normalized. The rotation is counter-clockwise when looking
+/// from the tip of the axis towards the origin. Returns the identity if the axis is zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_rotation_axis, mat4_rotation_x, mat4_rotation_y, mat4_rotation_z, mat4_transform_vec};
+///
+/// // Agrees with the rotations around the X, Y and Z axes
+/// for &angle in &[0.3f64, -1.2, 2.5] {
+///     let pairs = [(mat4_rotation_axis([2.0, 0.0, 0.0], angle), mat4_rotation_x(angle)),
+///                  (mat4_rotation_axis([0.0, 0.5, 0.0], angle), mat4_rotation_y(angle)),
+///                  (mat4_rotation_axis([0.0, 0.0, 1.0], angle), mat4_rotation_z(angle))];
+///     for (m, expected) in pairs.iter() {
+///         for i in 0..16 {
+///             assert!((m[i] - expected[i]).abs() < 1e-12);
+///         }
+///     }
+/// }
+///
+/// // A rotation of 120° around [1, 1, 1] cyclically permutes the axes
+/// let m = mat4_rotation_axis([1.0, 1.0, 1.0], 2.0 * std::f32::consts::FRAC_PI_3);
+/// for &(v, expected) in &[([1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]),
+///                         ([0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]),
+///                         ([0.0, 0.0, 1.0, 1.0], [1.0, 0.0, 0.0, 1.0])] {
+///     let v = mat4_transform_vec(m, v);
+///     for i in 0..4 {
+///         assert!((v[i] - expected[i]).abs() < 1e-6);
+///     }
+/// }
+///
+/// assert_eq!(mat4_rotation_axis([0.0, 0.0, 0.0], 1.0), stones::matrix::mat4_identity());
+/// ```
+pub fn mat4_rotation_axis<T>(axis: Vector3<T>, angle: T) -> Matrix4<T>
+    where T: Float
+{
+    let length = vec3_dot(axis, axis).sqrt();
+    if length == T::zero() {
+        return mat4_identity();
+    }
+
+    let [x, y, z] = [axis[0] / length, axis[1] / length, axis[2] / length];
+    let (sin, cos) = (angle.sin(), angle.cos());
+    let t = T::one() - cos;
+    let (zero, one) = (T::zero(), T::one());
+    [
+        cos + t * x * x, t * x * y - sin * z, t * x * z + sin * y, zero,
+        t * x * y + sin * z, cos + t * y * y, t * y * z - sin * x, zero,
+        t * x * z - sin * y, t * y * z + sin * x, cos + t * z * z, zero,
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a 4x4 matrix reflecting points across the plane dot(normal, x) + distance = 0
+///
+/// The normal doesn't need to be normalized. Returns the identity if the normal is zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_reflection, mat4_transform_point3, mat4_mul, mat4_identity, mat4_determinant};
+///
+/// // Water surface at y = 2
+/// let m = mat4_reflection([0.0, 1.0, 0.0], -2.0);
+/// assert_eq!(mat4_transform_point3(m, [1.0, 5.0, -3.0]), [1.0, -1.0, -3.0]);
+///
+/// // Points on the plane are fixed
+/// let (normal, distance) = ([1.0f64, -2.0, 0.5], 3.0);
+/// let m = mat4_reflection(normal, distance);
+/// let on_plane = [-3.0, 0.0, 0.0];
+/// let v = mat4_transform_point3(m, on_plane);
+/// for i in 0..3 {
+///     assert!((v[i] - on_plane[i]).abs() < 1e-12);
+/// }
+///
+/// // Reflecting twice is the identity
+/// let twice = mat4_mul(m, m);
+/// let identity = mat4_identity::<f64>();
+/// for i in 0..16 {
+///     assert!((twice[i] - identity[i]).abs() < 1e-12);
+/// }
+///
+/// assert!((mat4_determinant(m) + 1.0).abs() < 1e-12);
+/// ```
+pub fn mat4_reflection<T>(normal: Vector3<T>, distance: T) -> Matrix4<T>
+    where T: Float
+{
+    let length_squared = vec3_dot(normal, normal);
+    if length_squared == T::zero() {
+        return mat4_identity();
+    }
+
+    let two = T::one() + T::one();
+    let scaled = vec3_mul(normal, two / length_squared);
+    let householder = vec3_outer(scaled, normal);
+    let translation = vec3_mul(scaled, -distance);
+    let (zero, one) = (T::zero(), T::one());
+    [
+        one - householder[0], -householder[1], -householder[2], translation[0],
+        -householder[3], one - householder[4], -householder[5], translation[1],
+        -householder[6], -householder[7], one - householder[8], translation[2],
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns the 4x4 matrix mirroring the X axis, i.e. negating the x coordinates
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_mirror_x, mat4_mul, mat4_identity, mat4_transform_vec};
+///
+/// assert_eq!(mat4_transform_vec(mat4_mirror_x(), [1, 2, 3, 1]), [-1, 2, 3, 1]);
+/// assert_eq!(mat4_mul(mat4_mirror_x::<i32>(), mat4_mirror_x()), mat4_identity());
+/// ```
+pub fn mat4_mirror_x<T>() -> Matrix4<T>
+    where T: One + Zero + Copy + Neg<Output=T>
+{
+    mat4_scaling([-T::one(), T::one(), T::one()])
+}
+
+/// Returns the 4x4 matrix mirroring the Y axis, i.e. negating the y coordinates
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_mirror_y, mat4_transform_vec};
+///
+/// assert_eq!(mat4_transform_vec(mat4_mirror_y(), [1, 2, 3, 1]), [1, -2, 3, 1]);
+/// ```
+pub fn mat4_mirror_y<T>() -> Matrix4<T>
+    where T: One + Zero + Copy + Neg<Output=T>
+{
+    mat4_scaling([T::one(), -T::one(), T::one()])
+}
+
+/// Returns the 4x4 matrix mirroring the Z axis, i.e. negating the z coordinates
+///
+/// Mirroring a single axis converts between right-handed and left-handed coordinates.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_mirror_z, mat4_transform_vec, mat4_determinant};
+///
+/// assert_eq!(mat4_transform_vec(mat4_mirror_z(), [1, 2, 3, 1]), [1, 2, -3, 1]);
+/// assert_eq!(mat4_determinant(mat4_mirror_z::<i32>()), -1);
+/// ```
+pub fn mat4_mirror_z<T>() -> Matrix4<T>
+    where T: One + Zero + Copy + Neg<Output=T>
+{
+    mat4_scaling([T::one(), T::one(), -T::one()])
+}
+
+/// Returns the 4x4 matrix permuting and negating the coordinate axes
+///
+/// The coordinate i of the result is `signs[i]` times the coordinate `mapping[i]` of the
+/// transformed vector.
+///
+/// # Panics
+///
+/// Panics if `mapping` isn't a permutation of the axes or if a sign isn't 1 or -1.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_axis_swap, mat4_mul, mat4_identity, mat4_transform_vec, mat4_determinant, Axis};
+///
+/// // Z-up to Y-up, a quarter turn around X
+/// let z_up_to_y_up = mat4_axis_swap([Axis::X, Axis::Z, Axis::Y], [1, 1, -1]);
+/// assert_eq!(mat4_transform_vec(z_up_to_y_up, [1, 2, 3, 1]), [1, 3, -2, 1]);
+/// assert_eq!(mat4_transform_vec(z_up_to_y_up, [0, 0, 1, 0]), [0, 1, 0, 0]);
+/// assert_eq!(mat4_determinant(z_up_to_y_up), 1);
+///
+/// // And back
+/// let y_up_to_z_up = mat4_axis_swap([Axis::X, Axis::Z, Axis::Y], [1, -1, 1]);
+/// assert_eq!(mat4_mul(y_up_to_z_up, z_up_to_y_up), mat4_identity());
+///
+/// // Swapping the same axes twice is the identity, applying a quarter turn twice isn't
+/// let swap = mat4_axis_swap::<i32>([Axis::Y, Axis::X, Axis::Z], [1, 1, -1]);
+/// assert_eq!(mat4_mul(swap, swap), mat4_identity());
+/// assert_eq!(mat4_determinant(swap), 1);
+/// assert_ne!(mat4_mul(z_up_to_y_up, z_up_to_y_up), mat4_identity());
+///
+/// assert_eq!(mat4_axis_swap::<f32>([Axis::X, Axis::Y, Axis::Z], [1, 1, 1]), mat4_identity());
+/// ```
+///
+/// ```should_panic
+/// use stones::matrix::{mat4_axis_swap, Axis};
+///
+/// let _ = mat4_axis_swap::<f32>([Axis::X, Axis::Y, Axis::X], [1, 1, 1]);
+/// ```
+pub fn mat4_axis_swap<T>(mapping: [Axis; 3], signs: [i8; 3]) -> Matrix4<T>
+    where T: One + Zero + Copy + Neg<Output=T>
+{
+    assert!(mapping[0] != mapping[1] && mapping[0] != mapping[2] && mapping[1] != mapping[2],
+            "axis mapping is not a permutation: {:?}", mapping);
+
+    let mut m = mat4_scaling([T::zero(), T::zero(), T::zero()]);
+    for row in 0..3 {
+        let column = match mapping[row] {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        };
+        m[row * 4 + column] = match signs[row] {
+            1 => T::one(),
+            -1 => -T::one(),
+            sign => panic!("axis sign is neither 1 nor -1: {}", sign),
+        };
+    }
+    m
+}
+
+/// Returns the 4x4 matrix scaling, then rotating around an axis, then translating
+///
+/// The result is `mat4_translation(translation) * mat4_rotation_axis(axis, angle) *
+/// mat4_scaling(scale)` computed without the matrix products.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_from_trs, mat4_mul, mat4_rotation_axis, mat4_scaling, mat4_translation, mat4_transform_vec};
+///
+/// let (translation, axis, angle, scale) = ([1.0f64, -2.0, 3.5], [1.0, 2.0, -0.5], 0.7, [2.0, 0.5, -3.0]);
+/// let m = mat4_from_trs(translation, (axis, angle), scale);
+/// let composed = mat4_mul(mat4_mul(mat4_translation(translation), mat4_rotation_axis(axis, angle)),
+///                         mat4_scaling(scale));
+/// assert_eq!(m, composed);
+///
+/// let v = mat4_transform_vec(m, [1.0, 1.0, 1.0, 1.0]);
+/// let expected = mat4_transform_vec(composed, [1.0, 1.0, 1.0, 1.0]);
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // A quarter turn around Z
+/// let m = mat4_from_trs([10.0f32, 0.0, 0.0], ([0.0, 0.0, 1.0], std::f32::consts::FRAC_PI_2), [2.0, 2.0, 2.0]);
+/// let v = mat4_transform_vec(m, [1.0, 0.0, 0.0, 1.0]);
+/// let expected = [10.0, 2.0, 0.0, 1.0];
+/// for i in 0..4 {
+///     assert!((v[i] - expected[i]).abs() < 1e-6);
+/// }
+/// ```
+pub fn mat4_from_trs<T>(translation: Vector3<T>, rotation_axis_angle: (Vector3<T>, T), scale: Vector3<T>) -> Matrix4<T>
+    where T: Float
+{
+    let (axis, angle) = rotation_axis_angle;
+    let mut m = mat4_rotation_axis(axis, angle);
+    for row in 0..3 {
+        for column in 0..3 {
+            m[row * 4 + column] = m[row * 4 + column] * scale[column];
+        }
+        m[row * 4 + 3] = translation[row];
+    }
+    m
+}
+
+/// Returns the 4x4 transform applying a 3x3 rotation matrix then a translation
+///
+/// The rotation is placed in the upper-left block and the translation in the last column, so
+/// `mat4_transform_vec` rotates a point then translates it.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_from_rotation_translation, mat4_rotation_part, mat4_translation_part, mat4_transform_vec};
+///
+/// let r = [0, -1, 0,
+///          1, 0, 0,
+///          0, 0, 1];
+/// let t = [5, -2, 3];
+/// let m = mat4_from_rotation_translation(r, t);
+/// assert_eq!(m, [0, -1, 0, 5,
+///                1, 0, 0, -2,
+///                0, 0, 1, 3,
+///                0, 0, 0, 1]);
+/// assert_eq!(mat4_rotation_part(m), r);
+/// assert_eq!(mat4_translation_part(m), t);
+/// assert_eq!(mat4_from_rotation_translation(mat4_rotation_part(m), mat4_translation_part(m)), m);
+///
+/// // The origin goes to the translation, other points are rotated first
+/// assert_eq!(mat4_transform_vec(m, [0, 0, 0, 1]), [5, -2, 3, 1]);
+/// assert_eq!(mat4_transform_vec(m, [1, 0, 0, 1]), [5, -1, 3, 1]);
+/// ```
+pub fn mat4_from_rotation_translation<T>(r: Matrix3<T>, t: Vector3<T>) -> Matrix4<T>
+    where T: One + Zero + Copy
+{
+    [
+        r[0], r[1], r[2], t[0],
+        r[3], r[4], r[5], t[1],
+        r[6], r[7], r[8], t[2],
+        T::zero(), T::zero(), T::zero(), T::one()
+    ]
+}
+
+/// Returns the upper-left 3x3 block of a 4x4 transform, its rotation for a rigid transform
+///
+/// For transforms with a scale or a shear, the block contains them too, see `mat4_decompose`
+/// to extract the rotation alone.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_rotation_part, mat4_from_trs, mat3_is_orthogonal};
+///
+/// let m = mat4_from_trs([1.0f64, 2.0, 3.0], ([1.0, -1.0, 2.0], 0.8), [1.0, 1.0, 1.0]);
+/// assert!(mat3_is_orthogonal(mat4_rotation_part(m), 1e-12));
+/// ```
+pub fn mat4_rotation_part<T>(m: Matrix4<T>) -> Matrix3<T>
+    where T: Copy
+{
+    mat4_to_mat3(m)
+}
+
+/// Returns the translation of a 4x4 transform, stored in its last column
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_translation_part, mat4_translation, mat4_mul, mat4_rotation_z};
+///
+/// assert_eq!(mat4_translation_part(mat4_translation([5, -2, 3])), [5, -2, 3]);
+///
+/// // The translation is applied after the rotation
+/// let m = mat4_mul(mat4_translation([1.0, 2.0, 3.0]), mat4_rotation_z(0.5));
+/// assert_eq!(mat4_translation_part(m), [1.0, 2.0, 3.0]);
+/// ```
+pub fn mat4_translation_part<T>(m: Matrix4<T>) -> Vector3<T>
+    where T: Copy
+{
+    [m[3], m[7], m[11]]
+}
+
+/// Decomposes an affine 4x4 matrix into a translation, a rotation and a scale
+///
+/// The result (t, r, s) satisfies m = T(t) * R(r) * S(s). The scale factors are the lengths
+/// of the basis vectors, a mirroring matrix gets a negative scale on the Z axis. Returns None
+/// if the last row isn't [0, 0, 0, 1], if a scale factor is zero or if the basis vectors are
+/// not orthogonal, i.e. the matrix contains a shear, within a relative tolerance of the
+/// square root of the machine epsilon.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_decompose, mat4_from_trs, mat3_to_mat4};
+/// use stones::testing::{compare_mat4_snapshot, compare_vec3_snapshot};
+///
+/// let rotation = "
+///     mat4
+///     0.809634 0.230164 0.539923 0.000000
+///     -0.050996 0.944010 -0.325952 0.000000
+///     -0.584715 0.236368 0.776040 0.000000
+///     0.000000 0.000000 0.000000 1.000000
+/// ";
+/// let translation = "
+///     vec3
+///     1.000000 -2.000000 3.500000
+/// ";
+///
+/// let m = mat4_from_trs([1.0, -2.0, 3.5], ([1.0, 2.0, -0.5], 0.7), [2.0, 0.5, 3.0]);
+/// let (t, r, s) = mat4_decompose(m).unwrap();
+/// compare_vec3_snapshot("decompose translation", translation, t, 1e-5).unwrap();
+/// compare_mat4_snapshot("decompose rotation", rotation, mat3_to_mat4(r), 1e-5).unwrap();
+/// compare_vec3_snapshot("decompose scale", "vec3\n2.000000 0.500000 3.000000", s, 1e-5).unwrap();
+///
+/// // Mirrored, the negative scale goes to the Z axis and the rotation is unchanged
+/// let m = mat4_from_trs([1.0, -2.0, 3.5], ([1.0, 2.0, -0.5], 0.7), [2.0, 0.5, -3.0]);
+/// let (t, r, s) = mat4_decompose(m).unwrap();
+/// compare_vec3_snapshot("mirrored decompose translation", translation, t, 1e-5).unwrap();
+/// compare_mat4_snapshot("mirrored decompose rotation", rotation, mat3_to_mat4(r), 1e-5).unwrap();
+/// compare_vec3_snapshot("mirrored decompose scale", "vec3\n2.000000 0.500000 -3.000000", s, 1e-5).unwrap();
+/// ```
+///
+/// Invalid matrices
+/// ```
+/// use stones::matrix::{mat4_decompose, mat4_from_trs};
+///
+/// // Zero scale
+/// assert_eq!(mat4_decompose(mat4_from_trs([1.0, 2.0, 3.0], ([0.0, 1.0, 0.0], 0.3), [1.0, 0.0, 1.0])), None);
+///
+/// // Shear
+/// let shear = [1.0, 0.5, 0.0, 0.0,
+///              0.0, 1.0, 0.0, 0.0,
+///              0.0, 0.0, 1.0, 0.0,
+///              0.0, 0.0, 0.0, 1.0];
+/// assert_eq!(mat4_decompose(shear), None);
+///
+/// // Projection
+/// let projection = [1.0, 0.0, 0.0, 0.0,
+///                   0.0, 1.0, 0.0, 0.0,
+///                   0.0, 0.0, -2.0, -3.0,
+///                   0.0, 0.0, -1.0, 0.0];
+/// assert_eq!(mat4_decompose(projection), None);
+/// ```
+pub fn mat4_decompose<T>(m: Matrix4<T>) -> Option<(Vector3<T>, Matrix3<T>, Vector3<T>)>
+    where T: Float
+{
+    let (zero, one) = (T::zero(), T::one());
+    if m[12] != zero || m[13] != zero || m[14] != zero || m[15] != one {
+        return None;
+    }
+
+    let columns = [[m[0], m[4], m[8]], [m[1], m[5], m[9]], [m[2], m[6], m[10]]];
+    let mut scale = [
+        vec3_dot(columns[0], columns[0]).sqrt(),
+        vec3_dot(columns[1], columns[1]).sqrt(),
+        vec3_dot(columns[2], columns[2]).sqrt(),
+    ];
+    let largest = scale.iter().fold(zero, |largest, &s| if s > largest { s } else { largest });
+    if scale.iter().any(|&s| s <= largest * T::epsilon()) {
+        return None;
+    }
+
+    if vec3_dot(vec3_cross(columns[0], columns[1]), columns[2]) < zero {
+        scale[2] = -scale[2];
+    }
+
+    let axes = [
+        vec3_mul(columns[0], one / scale[0]),
+        vec3_mul(columns[1], one / scale[1]),
+        vec3_mul(columns[2], one / scale[2]),
+    ];
+    let tolerance = T::epsilon().sqrt();
+    if vec3_dot(axes[0], axes[1]).abs() > tolerance
+        || vec3_dot(axes[0], axes[2]).abs() > tolerance
+        || vec3_dot(axes[1], axes[2]).abs() > tolerance {
+        return None;
+    }
+
+    Some((
+        [m[3], m[7], m[11]],
+        [
+            axes[0][0], axes[1][0], axes[2][0],
+            axes[0][1], axes[1][1], axes[2][1],
+            axes[0][2], axes[1][2], axes[2][2]
+        ],
+        scale
+    ))
+}
+
+/// Returns a 2x2 matrix rotating 2D vectors by `angle` radians
+///
+/// The rotation is counter-clockwise with the Y axis pointing up, with the vectors multiplied
+/// on the right of the matrix as in `mat2_transform_vec`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_rotation, mat2_transform_vec, mat2_mul, mat2_determinant};
+///
+/// let v = mat2_transform_vec(mat2_rotation(std::f32::consts::FRAC_PI_2), [1.0, 0.0]);
+/// assert!(v[0].abs() < 1e-6 && (v[1] - 1.0).abs() < 1e-6);
+///
+/// // Rotations compose by adding their angles
+/// let composed = mat2_mul(mat2_rotation(0.3f64), mat2_rotation(1.1));
+/// let expected = mat2_rotation(1.4);
+/// for i in 0..4 {
+///     assert!((composed[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// assert!((mat2_determinant(mat2_rotation(2.5f64)) - 1.0).abs() < 1e-12);
+/// ```
+pub fn mat2_rotation<T>(angle: T) -> Matrix2<T>
+    where T: Float
+{
+    let (sin, cos) = (angle.sin(), angle.cos());
+    [
+        cos, -sin,
+        sin, cos
+    ]
+}
+
+/// Returns a 3x3 homogeneous matrix translating 2D points
+///
+/// Points are represented as [x, y, 1] and directions as [x, y, 0].
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_translation_2d, mat3_transform_vec};
+///
+/// let m = mat3_translation_2d([5, -2]);
+/// assert_eq!(m, [1, 0, 5,
+///                0, 1, -2,
+///                0, 0, 1]);
+///
+/// // Points are translated, directions are not
+/// assert_eq!(mat3_transform_vec(m, [1, 1, 1]), [6, -1, 1]);
+/// assert_eq!(mat3_transform_vec(m, [1, 1, 0]), [1, 1, 0]);
+/// ```
+pub fn mat3_translation_2d<T>(t: Vector2<T>) -> Matrix3<T>
+    where T: One + Zero + Copy
+{
+    [
+        T::one(), T::zero(), t[0],
+        T::zero(), T::one(), t[1],
+        T::zero(), T::zero(), T::one()
+    ]
+}
+
+/// Returns a 3x3 homogeneous matrix rotating 2D points by `angle` radians around the origin
+///
+/// The rotation is counter-clockwise with the Y axis pointing up.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_rotation_2d, mat3_transform_vec};
+///
+/// let v = mat3_transform_vec(mat3_rotation_2d(std::f32::consts::FRAC_PI_2), [1.0, 0.0, 1.0]);
+/// let expected = [0.0, 1.0, 1.0];
+/// for i in 0..3 {
+///     assert!((v[i] - expected[i]).abs() < 1e-6);
+/// }
+///
+/// let v = mat3_transform_vec(mat3_rotation_2d(std::f64::consts::FRAC_PI_4), [2.0, 0.0, 1.0]);
+/// let expected = [2f64.sqrt(), 2f64.sqrt(), 1.0];
+/// for i in 0..3 {
+///     assert!((v[i] - expected[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat3_rotation_2d<T>(angle: T) -> Matrix3<T>
+    where T: Float
+{
+    let (sin, cos) = (angle.sin(), angle.cos());
+    let (zero, one) = (T::zero(), T::one());
+    [
+        cos, -sin, zero,
+        sin, cos, zero,
+        zero, zero, one
+    ]
+}
+
+/// Returns a 3x3 homogeneous matrix scaling 2D points
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_scaling_2d, mat3_transform_vec};
+///
+/// let m = mat3_scaling_2d([2, 3]);
+/// assert_eq!(m, [2, 0, 0,
+///                0, 3, 0,
+///                0, 0, 1]);
+/// assert_eq!(mat3_transform_vec(m, [1, 1, 1]), [2, 3, 1]);
+/// ```
+///
+/// Composition
+/// ```
+/// use stones::matrix::{mat3_translation_2d, mat3_rotation_2d, mat3_scaling_2d, mat3_mul, mat3_transform_vec};
+///
+/// // Scale, then rotate a quarter turn, then translate
+/// let m = mat3_mul(mat3_translation_2d([10.0, 5.0]),
+///                  mat3_mul(mat3_rotation_2d(std::f64::consts::FRAC_PI_2), mat3_scaling_2d([2.0, 3.0])));
+/// let v = mat3_transform_vec(m, [1.0, 1.0, 1.0]);
+/// let expected = [7.0, 7.0, 1.0];
+/// for i in 0..3 {
+///     assert!((v[i] - expected[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat3_scaling_2d<T>(s: Vector2<T>) -> Matrix3<T>
+    where T: One + Zero + Copy
+{
+    [
+        s[0], T::zero(), T::zero(),
+        T::zero(), s[1], T::zero(),
+        T::zero(), T::zero(), T::one()
+    ]
+}
+
+/// Returns the 3x3 homogeneous matrix scaling, then rotating by `rotation` radians, then
+/// translating 2D points
+///
+/// The result is `mat3_translation_2d(translation) * mat3_rotation_2d(rotation) *
+/// mat3_scaling_2d(scale)` computed without the matrix products.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_from_trs_2d, mat3_translation_2d, mat3_rotation_2d, mat3_scaling_2d, mat3_mul};
+///
+/// let (translation, rotation, scale) = ([10.0f64, 5.0], 0.8, [2.0, -3.0]);
+/// let m = mat3_from_trs_2d(translation, rotation, scale);
+/// let composed = mat3_mul(mat3_translation_2d(translation),
+///                         mat3_mul(mat3_rotation_2d(rotation), mat3_scaling_2d(scale)));
+/// for i in 0..9 {
+///     assert!((m[i] - composed[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat3_from_trs_2d<T>(translation: Vector2<T>, rotation: T, scale: Vector2<T>) -> Matrix3<T>
+    where T: Float
+{
+    let (sin, cos) = (rotation.sin(), rotation.cos());
+    let (zero, one) = (T::zero(), T::one());
+    [
+        cos * scale[0], -sin * scale[1], translation[0],
+        sin * scale[0], cos * scale[1], translation[1],
+        zero, zero, one
+    ]
+}
+
+/// Decomposes a 2D homogeneous transform into a translation, a rotation and a scale
+///
+/// The result (t, angle, s) satisfies m = T(t) * R(angle) * S(s), with the angle in (-π, π].
+/// The scale factors are the lengths of the basis vectors, a mirroring matrix gets a negative
+/// scale on the Y axis. Returns None if the last row isn't [0, 0, 1], if a scale factor is zero
+/// or if the basis vectors are not orthogonal, i.e. the matrix contains a shear, within a
+/// relative tolerance of the square root of the machine epsilon.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_decompose_2d, mat3_from_trs_2d};
+///
+/// for &rotation in &[0.0f64, 0.5, -2.0, 3.0, std::f64::consts::PI] {
+///     for &scale in &[[1.0, 1.0], [2.0, 0.5], [0.1, 30.0]] {
+///         let (t, angle, s) = mat3_decompose_2d(mat3_from_trs_2d([3.0, -4.0], rotation, scale)).unwrap();
+///         assert_eq!(t, [3.0, -4.0]);
+///         assert!((angle - rotation).abs() < 1e-12);
+///         assert!((s[0] - scale[0]).abs() < 1e-12 && (s[1] - scale[1]).abs() < 1e-12);
+///     }
+/// }
+///
+/// // A horizontally mirrored sprite is a half turn with a mirrored Y axis
+/// let mirrored = mat3_from_trs_2d([3.0, -4.0], 0.0, [-2.0, 1.0]);
+/// let (t, angle, s) = mat3_decompose_2d(mirrored).unwrap();
+/// assert_eq!(t, [3.0, -4.0]);
+/// assert!((angle - std::f64::consts::PI).abs() < 1e-12);
+/// assert!((s[0] - 2.0).abs() < 1e-12 && (s[1] + 1.0).abs() < 1e-12);
+///
+/// // Round trip for mirrored transforms
+/// for &rotation in &[0.0f64, 0.5, -2.0, 3.0] {
+///     for &scale in &[[-1.0, 1.0], [2.0, -0.5], [-0.1, 30.0]] {
+///         let m = mat3_from_trs_2d([3.0, -4.0], rotation, scale);
+///         let (t, angle, s) = mat3_decompose_2d(m).unwrap();
+///         assert!(angle > -std::f64::consts::PI && angle <= std::f64::consts::PI);
+///         let recomposed = mat3_from_trs_2d(t, angle, s);
+///         for i in 0..9 {
+///             assert!((recomposed[i] - m[i]).abs() < 1e-12);
+///         }
+///     }
+/// }
+/// ```
+///
+/// Invalid matrices
+/// ```
+/// use stones::matrix::{mat3_decompose_2d, mat3_from_trs_2d};
+///
+/// // Zero scale
+/// assert_eq!(mat3_decompose_2d(mat3_from_trs_2d([1.0, 2.0], 0.3, [0.0, 1.0])), None);
+///
+/// // Shear
+/// let shear = [1.0, 0.5, 0.0,
+///              0.0, 1.0, 0.0,
+///              0.0, 0.0, 1.0];
+/// assert_eq!(mat3_decompose_2d(shear), None);
+///
+/// // Projective
+/// let projective = [1.0, 0.0, 0.0,
+///                   0.0, 1.0, 0.0,
+///                   0.5, 0.0, 1.0];
+/// assert_eq!(mat3_decompose_2d(projective), None);
+/// ```
+pub fn mat3_decompose_2d<T>(m: Matrix3<T>) -> Option<(Vector2<T>, T, Vector2<T>)>
+    where T: Float
+{
+    let (zero, one) = (T::zero(), T::one());
+    if m[6] != zero || m[7] != zero || m[8] != one {
+        return None;
+    }
+
+    let mut scale = [
+        (m[0] * m[0] + m[3] * m[3]).sqrt(),
+        (m[1] * m[1] + m[4] * m[4]).sqrt()
+    ];
+    let largest = if scale[0] > scale[1] { scale[0] } else { scale[1] };
+    if scale.iter().any(|&s| s <= largest * T::epsilon()) {
+        return None;
+    }
+
+    if m[0] * m[4] - m[1] * m[3] < zero {
+        scale[1] = -scale[1];
+    }
+
+    let x_axis = [m[0] / scale[0], m[3] / scale[0]];
+    let y_axis = [m[1] / scale[1], m[4] / scale[1]];
+    if (x_axis[0] * y_axis[0] + x_axis[1] * y_axis[1]).abs() > T::epsilon().sqrt() {
+        return None;
+    }
+
+    // atan2 returns -π for a negative zero sine
+    let pi = T::from_f64(std::f64::consts::PI);
+    let mut angle = x_axis[1].atan2(x_axis[0]);
+    if angle <= -pi {
+        angle = pi;
+    }
+
+    Some(([m[2], m[5]], angle, scale))
+}
+
+/// Returns a perspective projection matrix for an off-center frustum, like glFrustum
+///
+/// The bounds left, right, bottom and top are given on the near plane. The camera looks
+/// towards -Z and the frustum is mapped to the [-1, 1] cube, the near plane going to z = -1
+/// and the far plane to z = 1.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_frustum, mat4_transform_vec_divide};
+/// use stones::testing::compare_mat4_snapshot;
+///
+/// let (left, right, bottom, top, near, far) = (-1.0, 3.0, -2.0, 1.0, 1.0, 10.0);
+/// let m = mat4_frustum(left, right, bottom, top, near, far);
+/// let golden = "
+///     mat4
+///     0.500000 0.000000 0.500000 0.000000
+///     0.000000 0.666667 -0.333333 0.000000
+///     0.000000 0.000000 -1.222222 -2.222222
+///     0.000000 0.000000 -1.000000 0.000000
+/// ";
+/// compare_mat4_snapshot("frustum", golden, m, 1e-6).unwrap();
+///
+/// // The corners of the frustum are mapped to the corners of the cube
+/// let s = far / near;
+/// let corners = [
+///     ([left, bottom, -near], [-1.0, -1.0, -1.0]),
+///     ([right, top, -near], [1.0, 1.0, -1.0]),
+///     ([left * s, top * s, -far], [-1.0, 1.0, 1.0]),
+///     ([right * s, bottom * s, -far], [1.0, -1.0, 1.0]),
+/// ];
+/// for &(corner, expected) in &corners {
+///     let v = mat4_transform_vec_divide(m, [corner[0], corner[1], corner[2], 1.0]).unwrap();
+///     for i in 0..3 {
+///         assert!((v[i] - expected[i]).abs() < 1e-6);
+///     }
+/// }
+/// ```
+///
+/// Double precision
+/// ```
+/// use stones::matrix::{mat4_frustum, mat4_perspective};
+///
+/// let m = mat4_frustum(-1.0f64, 3.0, -2.0, 1.0, 1.0, 10.0);
+/// let expected = [0.5, 0.0, 0.5, 0.0,
+///                 0.0, 2.0 / 3.0, -1.0 / 3.0, 0.0,
+///                 0.0, 0.0, -11.0 / 9.0, -20.0 / 9.0,
+///                 0.0, 0.0, -1.0, 0.0];
+/// for i in 0..16 {
+///     assert!((m[i] - expected[i]).abs() < 1e-15);
+/// }
+///
+/// // Symmetric bounds give the same matrix as mat4_perspective
+/// let (fovy, aspect, near, far) = (0.8f64, 4.0 / 3.0, 0.5, 250.0);
+/// let top = near * (fovy / 2.0).tan();
+/// assert_eq!(mat4_frustum(-top * aspect, top * aspect, -top, top, near, far),
+///            mat4_perspective(fovy, aspect, near, far));
+/// ```
+pub fn mat4_frustum<T>(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Matrix4<T>
+    where T: Float
+{
+    let (zero, one) = (T::zero(), T::one());
+    let two = one + one;
+    [
+        two * near / (right - left), zero, (right + left) / (right - left), zero,
+        zero, two * near / (top - bottom), (top + bottom) / (top - bottom), zero,
+        zero, zero, -(far + near) / (far - near), -two * far * near / (far - near),
+        zero, zero, -one, zero
+    ]
+}
+
+/// Returns a perspective projection matrix, like gluPerspective
+///
+/// `fovy` is the vertical field of view in radians and `aspect` the ratio of the width over
+/// the height. The projection is the symmetric `mat4_frustum` with these bounds.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_frustum, mat4_perspective, mat4_transform_vec_divide};
+///
+/// let (fovy, aspect, near, far) = (1.2f64, 16.0 / 9.0, 0.1, 100.0);
+/// let top = near * (fovy / 2.0).tan();
+/// assert_eq!(mat4_perspective(fovy, aspect, near, far),
+///            mat4_frustum(-top * aspect, top * aspect, -top, top, near, far));
+///
+/// // 90° field of view, near = 1 and far = 3
+/// let m = mat4_perspective(std::f32::consts::FRAC_PI_2, 1.0, 1.0, 3.0);
+/// let expected = [1.0, 0.0, 0.0, 0.0,
+///                 0.0, 1.0, 0.0, 0.0,
+///                 0.0, 0.0, -2.0, -3.0,
+///                 0.0, 0.0, -1.0, 0.0];
+/// for i in 0..16 {
+///     assert!((m[i] - expected[i]).abs() < 1e-6);
+/// }
+/// ```
+pub fn mat4_perspective<T>(fovy: T, aspect: T, near: T, far: T) -> Matrix4<T>
+    where T: Float
+{
+    let top = near * (fovy / (T::one() + T::one())).tan();
+    let right = top * aspect;
+    mat4_frustum(-right, right, -top, top, near, far)
+}
+
+/// Returns a perspective projection matrix with a reversed depth in [0, 1]
+///
+/// The x and y components are projected like with `mat4_perspective` but the near plane is
+/// mapped to z = 1 and the far plane to z = 0, which spreads the precision of floating point
+/// depth buffers more evenly.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_perspective_reversed_z, mat4_perspective, mat4_transform_point3};
+///
+/// let (fovy, aspect, near, far) = (1.2f64, 16.0 / 9.0, 0.1, 100.0);
+/// let m = mat4_perspective_reversed_z(fovy, aspect, near, far);
+/// assert!((mat4_transform_point3(m, [0.0, 0.0, -near])[2] - 1.0).abs() < 1e-12);
+/// assert!(mat4_transform_point3(m, [0.0, 0.0, -far])[2].abs() < 1e-12);
+/// assert!((mat4_transform_point3(m, [0.0, 0.0, -1.0])[2] - 0.1 / 99.9 * 99.0).abs() < 1e-12);
+///
+/// // Only the depth differs from the regular perspective
+/// let regular = mat4_perspective(fovy, aspect, near, far);
+/// assert_eq!(m[0..8], regular[0..8]);
+/// assert_eq!(m[12..16], regular[12..16]);
+/// ```
+pub fn mat4_perspective_reversed_z<T>(fovy: T, aspect: T, near: T, far: T) -> Matrix4<T>
+    where T: Float
+{
+    let mut m = mat4_perspective(fovy, aspect, near, far);
+    m[10] = near / (far - near);
+    m[11] = near * far / (far - near);
+    m
+}
+
+/// Returns a perspective projection matrix with a reversed depth in [0, 1] and no far plane
+///
+/// The near plane is mapped to z = 1 and the infinity to z = 0, the x and y components are
+/// projected like with `mat4_perspective`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_perspective_reversed_z_infinite, mat4_perspective, mat4_transform_point3, mat4_transform_vec};
+///
+/// let (fovy, aspect, near) = (1.2f64, 16.0 / 9.0, 0.1);
+/// let m = mat4_perspective_reversed_z_infinite(fovy, aspect, near);
+/// assert!((mat4_transform_point3(m, [0.0, 0.0, -near])[2] - 1.0).abs() < 1e-12);
+/// assert!(mat4_transform_point3(m, [0.0, 0.0, -1e12])[2].abs() < 1e-12);
+///
+/// // Directions are points at infinity
+/// assert_eq!(mat4_transform_vec(m, [0.0, 0.0, -1.0, 0.0])[2], 0.0);
+///
+/// let regular = mat4_perspective(fovy, aspect, near, 100.0);
+/// assert_eq!(m[0..8], regular[0..8]);
+/// assert_eq!(m[12..16], regular[12..16]);
+/// ```
+pub fn mat4_perspective_reversed_z_infinite<T>(fovy: T, aspect: T, near: T) -> Matrix4<T>
+    where T: Float
+{
+    // The far plane only affects the depth row, which is replaced
+    let mut m = mat4_perspective(fovy, aspect, near, near + near);
+    m[10] = T::zero();
+    m[11] = near;
+    m
+}
+
+/// Returns a perspective projection matrix for an asymmetric field of view given by the
+/// tangents of its four half-angles
+///
+/// The tangents are signed like the angles of OpenXR's XrFovf, so `tan_left` and `tan_down`
+/// are negative when the view direction is inside the field of view. The projection is
+/// `mat4_frustum` with the bounds scaled by the near distance.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_perspective_fov_tangents, mat4_perspective, mat4_transform_point3};
+///
+/// let (fovy, near, far) = (1.2f64, 0.1, 100.0);
+/// let tan = (fovy / 2.0).tan();
+/// assert_eq!(mat4_perspective_fov_tangents(-tan, tan, tan, -tan, near, far),
+///            mat4_perspective(fovy, 1.0, near, far));
+///
+/// // The field of view extends further right and down, so the view direction is projected a
+/// // quarter of the width left of the center and a sixth of the height above
+/// let m = mat4_perspective_fov_tangents(-1.0, 3.0, 1.0, -2.0, near, far);
+/// let center = mat4_transform_point3(m, [0.0, 0.0, -1.0]);
+/// assert!((center[0] + 0.5).abs() < 1e-12);
+/// assert!((center[1] - 1.0 / 3.0).abs() < 1e-12);
+///
+/// // The edges of the field of view are mapped to the edges of the screen
+/// let v = mat4_transform_point3(m, [-1.0, 1.0, -1.0]);
+/// assert!((v[0] + 1.0).abs() < 1e-12 && (v[1] - 1.0).abs() < 1e-12);
+/// let v = mat4_transform_point3(m, [3.0, -2.0, -1.0]);
+/// assert!((v[0] - 1.0).abs() < 1e-12 && (v[1] + 1.0).abs() < 1e-12);
+/// ```
+pub fn mat4_perspective_fov_tangents<T>(tan_left: T, tan_right: T, tan_up: T, tan_down: T, near: T, far: T) -> Matrix4<T>
+    where T: Float
+{
+    mat4_frustum(near * tan_left, near * tan_right, near * tan_down, near * tan_up, near, far)
+}
+
+/// Returns a perspective projection matrix for an asymmetric field of view given by the
+/// tangents of its four half-angles, with a reversed depth in [0, 1] and no far plane
+///
+/// The x and y components are projected like with `mat4_perspective_fov_tangents` and the
+/// depth like with `mat4_perspective_reversed_z_infinite`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_perspective_fov_tangents_reversed_z_infinite, mat4_perspective_reversed_z_infinite,
+///                      mat4_perspective_fov_tangents, mat4_transform_point3};
+///
+/// let (fovy, near) = (1.2f64, 0.1);
+/// let tan = (fovy / 2.0).tan();
+/// assert_eq!(mat4_perspective_fov_tangents_reversed_z_infinite(-tan, tan, tan, -tan, near),
+///            mat4_perspective_reversed_z_infinite(fovy, 1.0, near));
+///
+/// let m = mat4_perspective_fov_tangents_reversed_z_infinite(-1.0, 3.0, 1.0, -2.0, near);
+/// assert!((mat4_transform_point3(m, [0.0, 0.0, -near])[2] - 1.0).abs() < 1e-12);
+/// assert!(mat4_transform_point3(m, [0.0, 0.0, -1e12])[2].abs() < 1e-12);
+///
+/// let regular = mat4_perspective_fov_tangents(-1.0, 3.0, 1.0, -2.0, near, 100.0);
+/// assert_eq!(m[0..8], regular[0..8]);
+/// assert_eq!(m[12..16], regular[12..16]);
+/// ```
+pub fn mat4_perspective_fov_tangents_reversed_z_infinite<T>(tan_left: T, tan_right: T, tan_up: T, tan_down: T, near: T) -> Matrix4<T>
+    where T: Float
+{
+    // The far plane only affects the depth row, which is replaced
+    let mut m = mat4_perspective_fov_tangents(tan_left, tan_right, tan_up, tan_down, near, near + near);
+    m[10] = T::zero();
+    m[11] = near;
+    m
+}
+
+/// Returns a viewport matrix mapping normalized device coordinates to window coordinates
+///
+/// The [-1, 1] cube is mapped to [x, x + width] x [y, y + height] x [depth_near, depth_far].
+/// As with glViewport and glDepthRange, callers usually pass 0 and 1 as the depth range.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_viewport, mat4_mul, mat4_transform_vec, mat4_project, mat4_look_at, mat4_perspective};
+///
+/// let m = mat4_viewport(10.0, 20.0, 1920.0, 1080.0, 0.0, 1.0);
+/// assert_eq!(mat4_transform_vec(m, [-1.0, -1.0, -1.0, 1.0]), [10.0, 20.0, 0.0, 1.0]);
+/// assert_eq!(mat4_transform_vec(m, [1.0, 1.0, 1.0, 1.0]), [1930.0, 1100.0, 1.0, 1.0]);
+///
+/// // Same result as mat4_project for the whole pipeline
+/// let view = mat4_look_at([2.0f64, 3.0, 10.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+/// let proj = mat4_perspective(1.0, 16.0 / 9.0, 0.1, 100.0);
+/// let pipeline = mat4_mul(mat4_viewport(10.0, 20.0, 1920.0, 1080.0, 0.0, 1.0), mat4_mul(proj, view));
+/// for &point in &[[1.5, -0.5, 2.0], [0.0, 0.0, 0.0], [-20.0, 4.0, -50.0]] {
+///     let v = mat4_transform_vec(pipeline, [point[0], point[1], point[2], 1.0]);
+///     let expected = mat4_project(point, view, proj, [10.0, 20.0, 1920.0, 1080.0]).unwrap();
+///     for i in 0..3 {
+///         assert!((v[i] / v[3] - expected[i]).abs() < 1e-9);
+///     }
+/// }
+/// ```
+pub fn mat4_viewport<T>(x: T, y: T, width: T, height: T, depth_near: T, depth_far: T) -> Matrix4<T>
+    where T: Float
+{
+    let (zero, one) = (T::zero(), T::one());
+    let two = one + one;
+    [
+        width / two, zero, zero, x + width / two,
+        zero, height / two, zero, y + height / two,
+        zero, zero, (depth_far - depth_near) / two, (depth_far + depth_near) / two,
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a perspective projection matrix with a depth in [0, 1], like the Vulkan, Direct3D,
+/// Metal and wgpu conventions
+///
+/// The x and y components are projected like with `mat4_perspective` but the near plane is
+/// mapped to z = 0 and the far plane to z = 1.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_perspective_zo, mat4_perspective, mat4_transform_point3};
+///
+/// let (fovy, aspect, near, far) = (1.2f64, 16.0 / 9.0, 0.1, 100.0);
+/// let m = mat4_perspective_zo(fovy, aspect, near, far);
+/// assert!(mat4_transform_point3(m, [0.0, 0.0, -near])[2].abs() < 1e-12);
+/// assert!((mat4_transform_point3(m, [0.0, 0.0, -far])[2] - 1.0).abs() < 1e-12);
+///
+/// // Only the depth differs from the regular perspective
+/// let regular = mat4_perspective(fovy, aspect, near, far);
+/// assert_eq!(m[0..8], regular[0..8]);
+/// assert_eq!(m[12..16], regular[12..16]);
+/// ```
+pub fn mat4_perspective_zo<T>(fovy: T, aspect: T, near: T, far: T) -> Matrix4<T>
+    where T: Float
+{
+    let mut m = mat4_perspective(fovy, aspect, near, far);
+    m[10] = far / (near - far);
+    m[11] = near * far / (near - far);
+    m
+}
+
+/// Returns the matrix converting OpenGL clip coordinates to wgpu, Vulkan or Direct3D clip
+/// coordinates
+///
+/// The depth is remapped from [-1, 1] to [0, 1]. If `flip_y` is true, the y axis is negated
+/// too, for APIs whose normalized device coordinates have Y pointing down like Vulkan.
+/// Multiplying an OpenGL projection matrix on the left by this matrix gives the equivalent
+/// projection matrix for the other API.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_gl_to_wgpu, mat4_perspective, mat4_perspective_zo, mat4_mul, mat4_transform_point3};
+///
+/// let (fovy, aspect, near, far) = (1.2f64, 16.0 / 9.0, 0.1, 100.0);
+/// let converted = mat4_mul(mat4_gl_to_wgpu(false), mat4_perspective(fovy, aspect, near, far));
+/// let zo = mat4_perspective_zo(fovy, aspect, near, far);
+/// let flipped = mat4_mul(mat4_gl_to_wgpu(true), mat4_perspective(fovy, aspect, near, far));
+/// for &point in &[[0.0, 0.0, -near], [1.0, -2.0, -5.0], [-30.0, 10.0, -far], [0.5, 0.5, -0.7]] {
+///     let v = mat4_transform_point3(converted, point);
+///     let expected = mat4_transform_point3(zo, point);
+///     for i in 0..3 {
+///         assert!((v[i] - expected[i]).abs() < 1e-12);
+///     }
+///
+///     let v = mat4_transform_point3(flipped, point);
+///     let expected = [expected[0], -expected[1], expected[2]];
+///     for i in 0..3 {
+///         assert!((v[i] - expected[i]).abs() < 1e-12);
+///     }
+/// }
+/// ```
+pub fn mat4_gl_to_wgpu<T>(flip_y: bool) -> Matrix4<T>
+    where T: Float
+{
+    let (zero, one) = (T::zero(), T::one());
+    let half = one / (one + one);
+    [
+        one, zero, zero, zero,
+        zero, if flip_y { -one } else { one }, zero, zero,
+        zero, zero, half, half,
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns the matrix converting wgpu, Vulkan or Direct3D clip coordinates to OpenGL clip
+/// coordinates, the inverse of `mat4_gl_to_wgpu`
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_wgpu_to_gl, mat4_gl_to_wgpu, mat4_perspective, mat4_perspective_zo, mat4_mul, mat4_identity};
+///
+/// for &flip_y in &[false, true] {
+///     assert_eq!(mat4_mul(mat4_wgpu_to_gl::<f32>(flip_y), mat4_gl_to_wgpu(flip_y)), mat4_identity());
+///     assert_eq!(mat4_mul(mat4_gl_to_wgpu::<f32>(flip_y), mat4_wgpu_to_gl(flip_y)), mat4_identity());
+/// }
+///
+/// let (fovy, aspect, near, far) = (1.2f64, 16.0 / 9.0, 0.1, 100.0);
+/// let converted = mat4_mul(mat4_wgpu_to_gl(false), mat4_perspective_zo(fovy, aspect, near, far));
+/// let gl = mat4_perspective(fovy, aspect, near, far);
+/// for i in 0..16 {
+///     assert!((converted[i] - gl[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat4_wgpu_to_gl<T>(flip_y: bool) -> Matrix4<T>
+    where T: Float
+{
+    let (zero, one) = (T::zero(), T::one());
+    [
+        one, zero, zero, zero,
+        zero, if flip_y { -one } else { one }, zero, zero,
+        zero, zero, one + one, -one,
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a right-handed view matrix for a camera at `eye` looking at `target`
+///
+/// The camera looks towards -Z with Y as its up direction. The up vector doesn't need to be
+/// normalized nor orthogonal to the view direction. If it is zero or parallel to the view
+/// direction, the world axis the least aligned with the view direction is used instead. If
+/// the eye and the target are equal, the camera looks towards -Z.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_look_at, mat4_transform_vec};
+/// use stones::testing::compare_mat4_snapshot;
+///
+/// let m = mat4_look_at([0.0, 0.0, 5.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+/// let golden = "
+///     mat4
+///     1.000000 0.000000 0.000000 0.000000
+///     0.000000 1.000000 0.000000 0.000000
+///     0.000000 0.000000 1.000000 -5.000000
+///     0.000000 0.000000 0.000000 1.000000
+/// ";
+/// compare_mat4_snapshot("look_at", golden, m, 1e-6).unwrap();
+///
+/// let (eye, target) = ([1.0f64, 2.0, 3.0], [-4.0, 6.0, 1.0]);
+/// let m = mat4_look_at(eye, target, [0.0, 1.0, 0.0]);
+///
+/// // The eye goes to the origin and the target on the -Z axis
+/// let v = mat4_transform_vec(m, [eye[0], eye[1], eye[2], 1.0]);
+/// for i in 0..3 {
+///     assert!(v[i].abs() < 1e-12);
+/// }
+/// let distance = (25.0f64 + 16.0 + 4.0).sqrt();
+/// let v = mat4_transform_vec(m, [target[0], target[1], target[2], 1.0]);
+/// let expected = [0.0, 0.0, -distance];
+/// for i in 0..3 {
+///     assert!((v[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // The rotation block is orthonormal
+/// for i in 0..3 {
+///     for j in 0..3 {
+///         let dot = (0..3).map(|k| m[i * 4 + k] * m[j * 4 + k]).sum::<f64>();
+///         assert!((dot - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+///     }
+/// }
+/// ```
+///
+/// Degenerate cases
+/// ```
+/// use stones::matrix::{mat4_look_at, mat4_transform_vec};
+///
+/// // Looking straight down with Y as the up vector
+/// for &m in &[mat4_look_at([0.0, 10.0, 0.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+///             mat4_look_at([0.0, 10.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+///             mat4_look_at([0.0, 10.0, 0.0], [0.0, 10.0, 0.0], [0.0, 0.0, -1.0])] {
+///     assert!(m.iter().all(|x: &f64| x.is_finite()));
+///     for i in 0..3 {
+///         for j in 0..3 {
+///             let dot = (0..3).map(|k| m[i * 4 + k] * m[j * 4 + k]).sum::<f64>();
+///             assert!((dot - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+///         }
+///     }
+/// }
+///
+/// let m = mat4_look_at([0.0f64, 10.0, 0.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+/// let v = mat4_transform_vec(m, [0.0, 0.0, 0.0, 1.0]);
+/// assert!(v[0].abs() < 1e-12 && v[1].abs() < 1e-12 && (v[2] + 10.0).abs() < 1e-12);
+/// ```
+pub fn mat4_look_at<T>(eye: Vector3<T>, target: Vector3<T>, up: Vector3<T>) -> Matrix4<T>
+    where T: Float
+{
+    let forward = vec3_sub(target, eye);
+    let forward_length = vec3_dot(forward, forward).sqrt();
+    let forward = if forward_length > T::zero() {
+        vec3_mul(forward, T::one() / forward_length)
+    } else {
+        [T::zero(), T::zero(), -T::one()]
+    };
+
+    let side = unit_cross_or_axis(forward, up);
+    let up = vec3_cross(side, forward);
+
+    let (zero, one) = (T::zero(), T::one());
+    [
+        side[0], side[1], side[2], -vec3_dot(side, eye),
+        up[0], up[1], up[2], -vec3_dot(up, eye),
+        -forward[0], -forward[1], -forward[2], vec3_dot(forward, eye),
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a 4x4 matrix placing an object at `object_pos` with its +Z axis facing the camera
+///
+/// The +Y axis of the object is aligned with the up vector of the camera as much as possible.
+/// The degenerate cases are handled like in `mat4_look_at`, the object facing +Z if it is at
+/// the camera position.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_billboard, mat4_transform_direction3, mat4_transform_point3, mat4_to_mat3, mat3_is_orthogonal, mat3_determinant};
+///
+/// let (object, camera) = ([1.0f64, 2.0, -3.0], [4.0, 6.0, 9.0]);
+/// let m = mat4_billboard(object, camera, [0.0, 1.0, 0.0]);
+///
+/// // The forward axis points at the camera
+/// let forward = mat4_transform_direction3(m, [0.0, 0.0, 1.0]);
+/// let expected = [3.0 / 13.0, 4.0 / 13.0, 12.0 / 13.0];
+/// for i in 0..3 {
+///     assert!((forward[i] - expected[i]).abs() < 1e-12);
+/// }
+/// assert_eq!(mat4_transform_point3(m, [0.0, 0.0, 0.0]), object);
+///
+/// // Rigid transform, with the side axis horizontal
+/// let rotation = mat4_to_mat3(m);
+/// assert!(mat3_is_orthogonal(rotation, 1e-12));
+/// assert!((mat3_determinant(rotation) - 1.0).abs() < 1e-12);
+/// assert!(mat4_transform_direction3(m, [1.0, 0.0, 0.0])[1].abs() < 1e-12);
+///
+/// // Camera right above the object
+/// let m = mat4_billboard(object, [1.0, 10.0, -3.0], [0.0, 1.0, 0.0]);
+/// assert!(m.iter().all(|x| x.is_finite()));
+/// assert_eq!(mat4_transform_direction3(m, [0.0, 0.0, 1.0]), [0.0, 1.0, 0.0]);
+/// ```
+pub fn mat4_billboard<T>(object_pos: Vector3<T>, camera_pos: Vector3<T>, camera_up: Vector3<T>) -> Matrix4<T>
+    where T: Float
+{
+    let to_camera = vec3_sub(camera_pos, object_pos);
+    let length = vec3_dot(to_camera, to_camera).sqrt();
+    let z = if length > T::zero() {
+        vec3_mul(to_camera, T::one() / length)
+    } else {
+        [T::zero(), T::zero(), T::one()]
+    };
+    let x = unit_cross_or_axis(vec3_mul(z, -T::one()), camera_up);
+    let y = vec3_cross(z, x);
+
+    let (zero, one) = (T::zero(), T::one());
+    [
+        x[0], y[0], z[0], object_pos[0],
+        x[1], y[1], z[1], object_pos[1],
+        x[2], y[2], z[2], object_pos[2],
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns a 4x4 matrix placing an object at `object_pos` with its +Y axis along `axis` and
+/// its +Z axis facing the camera as much as possible
+///
+/// The object only rotates around the axis, like a tree sprite. The axis doesn't need to be
+/// normalized but must not be zero. If the camera is on the axis, the +Z axis of the object
+/// is perpendicular to the axis in an arbitrary direction.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_billboard_axial, mat4_transform_direction3, mat4_to_mat3, mat3_is_orthogonal, mat3_determinant};
+///
+/// // Tree growing along +Y
+/// let (object, camera) = ([1.0f64, 0.0, -3.0], [4.0, 25.0, 1.0]);
+/// let m = mat4_billboard_axial(object, camera, [0.0, 2.0, 0.0]);
+///
+/// // The axis is kept and the forward axis faces the camera horizontally
+/// assert_eq!(mat4_transform_direction3(m, [0.0, 1.0, 0.0]), [0.0, 1.0, 0.0]);
+/// let forward = mat4_transform_direction3(m, [0.0, 0.0, 1.0]);
+/// let expected = [0.6, 0.0, 0.8];
+/// for i in 0..3 {
+///     assert!((forward[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// let rotation = mat4_to_mat3(m);
+/// assert!(mat3_is_orthogonal(rotation, 1e-12));
+/// assert!((mat3_determinant(rotation) - 1.0).abs() < 1e-12);
+///
+/// // Tilted axis
+/// let axis = [1.0, 1.0, 0.0];
+/// let m = mat4_billboard_axial(object, camera, axis);
+/// let y = mat4_transform_direction3(m, [0.0, 1.0, 0.0]);
+/// for i in 0..3 {
+///     assert!((y[i] - axis[i] / 2f64.sqrt()).abs() < 1e-12);
+/// }
+///
+/// // Camera on the axis
+/// let m = mat4_billboard_axial(object, [1.0, 10.0, -3.0], [0.0, 1.0, 0.0]);
+/// assert!(m.iter().all(|x| x.is_finite()));
+/// assert!(mat3_is_orthogonal(mat4_to_mat3(m), 1e-12));
+/// ```
+pub fn mat4_billboard_axial<T>(object_pos: Vector3<T>, camera_pos: Vector3<T>, axis: Vector3<T>) -> Matrix4<T>
+    where T: Float
+{
+    let y = vec3_mul(axis, T::one() / vec3_dot(axis, axis).sqrt());
+    let to_camera = vec3_sub(camera_pos, object_pos);
+    let z = vec3_sub(to_camera, vec3_mul(y, vec3_dot(y, to_camera)));
+    let z_length = vec3_dot(z, z).sqrt();
+    let to_camera_length = vec3_dot(to_camera, to_camera).sqrt();
+    let z = if z_length > to_camera_length * T::epsilon() {
+        vec3_mul(z, T::one() / z_length)
+    } else {
+        unit_cross_or_axis(y, [T::zero(); 3])
+    };
+    let x = vec3_cross(y, z);
+
+    let (zero, one) = (T::zero(), T::one());
+    [
+        x[0], y[0], z[0], object_pos[0],
+        x[1], y[1], z[1], object_pos[1],
+        x[2], y[2], z[2], object_pos[2],
+        zero, zero, zero, one
+    ]
+}
+
+/// Returns the normalized cross product of the unit vector v and up, or of v and the world
+/// axis the least aligned with it if up is zero or parallel to v
+fn unit_cross_or_axis<T>(v: Vector3<T>, up: Vector3<T>) -> Vector3<T>
+    where T: Float
+{
+    let up_length = vec3_dot(up, up).sqrt();
+    let cross = vec3_cross(v, up);
+    let cross_length = vec3_dot(cross, cross).sqrt();
+    if cross_length > up_length * T::epsilon() {
+        return vec3_mul(cross, T::one() / cross_length);
+    }
+
+    let axis = if v[0].abs() < v[1].abs() && v[0].abs() < v[2].abs() {
+        [T::one(), T::zero(), T::zero()]
+    } else if v[1].abs() < v[2].abs() {
+        [T::zero(), T::one(), T::zero()]
+    } else {
+        [T::zero(), T::zero(), T::one()]
+    };
+    let cross = vec3_cross(v, axis);
+    vec3_mul(cross, T::one() / vec3_dot(cross, cross).sqrt())
+}
+
+/// Adds two 2x2 matrices together
+///
+/// # Exmaples
+///
+/// ```
+/// use stones::matrix::mat2_add;
+///
+/// let m1 = [2, 4,
+///           5, 9];
+/// let m2 = [1, 9,
+///           1, -2];
+/// assert_eq!(mat2_add(m1, m2), [3, 13,
+///                               6, 7]);
+/// ```
+pub fn mat2_add<T>(lhs: Matrix2<T>, rhs: Matrix2<T>) -> Matrix2<T>
+    where T: Copy + Add<Output=T>
+{
+    [
+        lhs[0] + rhs[0], lhs[1] + rhs[1],
+        lhs[2] + rhs[2], lhs[3] + rhs[3],
+    ]
+}
+
+/// Adds two 3x3 matrices together
+///
+/// # Exmaples
+///
+/// ```
+/// use stones::matrix::mat3_add;
+///
+/// let m1 = [2, 4, 5,
+///           9, 1, 1,
+///           4, 2, 1];
+/// let m2 = [1, 9, 6,
+///           1, -2, 9,
+///           10, 3, 5];
+/// assert_eq!(mat3_add(m1, m2), [3, 13, 11,
+///                               10, -1, 10,
+///                               14, 5, 6]);
+/// ```
+pub fn mat3_add<T>(lhs: Matrix3<T>, rhs: Matrix3<T>) -> Matrix3<T>
+    where T: Copy + Add<Output=T>
+{
+    [
+        lhs[0] + rhs[0], lhs[1] + rhs[1], lhs[2] + rhs[2],
+        lhs[3] + rhs[3], lhs[4] + rhs[4], lhs[5] + rhs[5],
+        lhs[6] + rhs[6], lhs[7] + rhs[7], lhs[8] + rhs[8],
+    ]
+}
+
+/// Adds two 4x4 matrices together
+///
+/// # Exmaples
+///
+/// ```
+/// use stones::matrix::mat4_add;
+///
+/// let m1 = [2, 4, 5, 1,
+///           9, 1, 1, 3,
+///           4, 2, 1, 2,
+///           1, 0, 2, 2];
+/// let m2 = [1, 9, 6, 5,
+///           1, -2, 9, 9,
+///           10, 3, 5, 4,
+///            4, 3, 1, 0];
+/// assert_eq!(mat4_add(m1, m2), [3, 13, 11, 6,
+///                              10, -1, 10, 12,
+///                              14, 5, 6, 6,
+///                               5, 3, 3, 2]);
+/// ```
+pub fn mat4_add<T>(lhs: Matrix4<T>, rhs: Matrix4<T>) -> Matrix4<T>
+    where T: Copy + Add<Output=T>
+{
+    [
+        lhs[0] + rhs[0], lhs[1] + rhs[1], lhs[2] + rhs[2], lhs[3] + rhs[3],
+        lhs[4] + rhs[4], lhs[5] + rhs[5], lhs[6] + rhs[6], lhs[7] + rhs[7],
+        lhs[8] + rhs[8], lhs[9] + rhs[9], lhs[10] + rhs[10], lhs[11] + rhs[11],
+        lhs[12] + rhs[12], lhs[13] + rhs[13], lhs[14] + rhs[14], lhs[15] + rhs[15],
+    ]
+}
+
+/// Subtracts a 2x2 matrix from another
+///
+/// # Exmaples
+///
+/// ```
+/// use stones::matrix::mat2_sub;
+///
+/// let m1 = [2, 4,
+///           5, 9];
+/// let m2 = [1, 9,
+///           1, -2];
+/// assert_eq!(mat2_sub(m1, m2), [1, -5,
+///                               4, 11]);
+/// ```
+pub fn mat2_sub<T>(lhs: Matrix2<T>, rhs: Matrix2<T>) -> Matrix2<T>
+    where T: Copy + Sub<Output=T>
+{
+    [
+        lhs[0] - rhs[0], lhs[1] - rhs[1],
+        lhs[2] - rhs[2], lhs[3] - rhs[3],
+    ]
+}
+
+/// Subtracts a 3x3 matrix from another
+///
+/// # Exmaples
+///
+/// ```
+/// use stones::matrix::mat3_sub;
+///
+/// let m1 = [2, 4, 5,
+///           9, 1, 1,
+///           4, 2, 1];
+/// let m2 = [1, 9, 6,
+///           1, -2, 9,
+///           10, 3, 5];
+/// assert_eq!(mat3_sub(m1, m2), [1, -5, -1,
+///                               8, 3, -8,
+///                               -6, -1, -4]);
+/// ```
+pub fn mat3_sub<T>(lhs: Matrix3<T>, rhs: Matrix3<T>) -> Matrix3<T>
+    where T: Copy + Sub<Output=T>
+{
+    [
+        lhs[0] - rhs[0], lhs[1] - rhs[1], lhs[2] - rhs[2],
+        lhs[3] - rhs[3], lhs[4] - rhs[4], lhs[5] - rhs[5],
+        lhs[6] - rhs[6], lhs[7] - rhs[7], lhs[8] - rhs[8],
+    ]
+}
+
+/// Subtracts a 4x4 matrix from another
+///
+/// # Exmaples
+///
+/// ```
+/// use stones::matrix::mat4_sub;
+///
+/// let m1 = [2, 4, 5, 1,
+///           9, 1, 1, 3,
+///           4, 2, 1, 2,
+///           1, 0, 2, 2];
+/// let m2 = [1, 9, 6, 5,
+///           1, -2, 9, 9,
+///           10, 3, 5, 4,
+///            4, 3, 1, 0];
+/// assert_eq!(mat4_sub(m1, m2), [1, -5, -1, -4,
+///                              8, 3, -8, -6,
+///                              -6, -1, -4, -2,
+///                               -3, -3, 1, 2]);
+/// ```
+pub fn mat4_sub<T>(lhs: Matrix4<T>, rhs: Matrix4<T>) -> Matrix4<T>
+    where T: Copy + Sub<Output=T>
+{
+    [
+        lhs[0] - rhs[0], lhs[1] - rhs[1], lhs[2] - rhs[2], lhs[3] - rhs[3],
+        lhs[4] - rhs[4], lhs[5] - rhs[5], lhs[6] - rhs[6], lhs[7] - rhs[7],
+        lhs[8] - rhs[8], lhs[9] - rhs[9], lhs[10] - rhs[10], lhs[11] - rhs[11],
+        lhs[12] - rhs[12], lhs[13] - rhs[13], lhs[14] - rhs[14], lhs[15] - rhs[15],
+    ]
+}
+
+/// Multiplies a 2x2 matrix by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_scale;
+///
+/// let m = [1, 2,
+///          3, 4];
+/// let scalar = 2;
+/// assert_eq!(mat2_scale(m, scalar), [2, 4,
+///                                  6, 8]);
+/// ```
+pub fn mat2_scale<T>(lhs: Matrix2<T>, rhs: T) -> Matrix2<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs, lhs[1] * rhs,
+        lhs[2] * rhs, lhs[3] * rhs
+    ]
+}
+
+/// Multiplies a 3x3 matrix by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_scale;
+///
+/// let m = [1, 2, 3,
+///          4, 5, 6,
+///          7, 8, 9];
+/// let scalar = 2;
+/// assert_eq!(mat3_scale(m, scalar), [2, 4, 6,
+///                                  8, 10, 12,
+///                                  14, 16, 18]);
+/// ```
+pub fn mat3_scale<T>(lhs: Matrix3<T>, rhs: T) -> Matrix3<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs, lhs[1] * rhs, lhs[2] * rhs,
+        lhs[3] * rhs, lhs[4] * rhs, lhs[5] * rhs,
+        lhs[6] * rhs, lhs[7] * rhs, lhs[8] * rhs
+    ]
+}
+
+/// Multiplies a 4x4 matrix by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat4_scale;
+///
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// let scalar = 2;
+/// assert_eq!(mat4_scale(m, scalar), [2, 4, 6, 8,
+///                                  10, 12, 14, 16,
+///                                  18, 20, 22, 24,
+///                                  26, 28, 30, 32]);
+/// ```
+pub fn mat4_scale<T>(lhs: Matrix4<T>, rhs: T) -> Matrix4<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs, lhs[1] * rhs, lhs[2] * rhs, lhs[3] * rhs,
+        lhs[4] * rhs, lhs[5] * rhs, lhs[6] * rhs, lhs[7] * rhs,
+        lhs[8] * rhs, lhs[9] * rhs, lhs[10] * rhs, lhs[11] * rhs,
+        lhs[12] * rhs, lhs[13] * rhs, lhs[14] * rhs, lhs[15] * rhs,
+    ]
+}
+
+/// Multiplies two 2x2 matrices component by component
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_hadamard, mat2_mul};
+///
+/// let m = [1, 2,
+///          3, 4];
+/// assert_eq!(mat2_hadamard(m, [2, 0,
+///                              -1, 3]), [2, 0,
+///                                        -3, 12]);
+///
+/// // The all-ones matrix is the neutral element, unlike with mat2_mul
+/// assert_eq!(mat2_hadamard(m, [1, 1, 1, 1]), m);
+/// assert_ne!(mat2_mul(m, [1, 1, 1, 1]), m);
+/// ```
+pub fn mat2_hadamard<T>(lhs: Matrix2<T>, rhs: Matrix2<T>) -> Matrix2<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs[0], lhs[1] * rhs[1],
+        lhs[2] * rhs[2], lhs[3] * rhs[3]
+    ]
+}
+
+/// Multiplies two 3x3 matrices component by component
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_hadamard;
+///
+/// let m = [1, 2, 3,
+///          4, 5, 6,
+///          7, 8, 9];
+/// let mask = [1, 0, 1,
+///             0, 1, 0,
+///             1, 0, 1];
+/// assert_eq!(mat3_hadamard(m, mask), [1, 0, 3,
+///                                     0, 5, 0,
+///                                     7, 0, 9]);
+/// assert_eq!(mat3_hadamard(m, [1; 9]), m);
+/// ```
+pub fn mat3_hadamard<T>(lhs: Matrix3<T>, rhs: Matrix3<T>) -> Matrix3<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs[0], lhs[1] * rhs[1], lhs[2] * rhs[2],
+        lhs[3] * rhs[3], lhs[4] * rhs[4], lhs[5] * rhs[5],
+        lhs[6] * rhs[6], lhs[7] * rhs[7], lhs[8] * rhs[8]
+    ]
+}
+
+/// Multiplies two 4x4 matrices component by component
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_hadamard, mat4_identity};
+///
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// assert_eq!(mat4_hadamard(m, mat4_identity()), [1, 0, 0, 0,
+///                                                0, 6, 0, 0,
+///                                                0, 0, 11, 0,
+///                                                0, 0, 0, 16]);
+/// assert_eq!(mat4_hadamard(m, [1; 16]), m);
+/// ```
+pub fn mat4_hadamard<T>(lhs: Matrix4<T>, rhs: Matrix4<T>) -> Matrix4<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs[0], lhs[1] * rhs[1], lhs[2] * rhs[2], lhs[3] * rhs[3],
+        lhs[4] * rhs[4], lhs[5] * rhs[5], lhs[6] * rhs[6], lhs[7] * rhs[7],
+        lhs[8] * rhs[8], lhs[9] * rhs[9], lhs[10] * rhs[10], lhs[11] * rhs[11],
+        lhs[12] * rhs[12], lhs[13] * rhs[13], lhs[14] * rhs[14], lhs[15] * rhs[15]
+    ]
+}
+
+/// Negates every component of a 2x2 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_neg;
+///
+/// assert_eq!(mat2_neg([1, -2,
+///                      0, 4]), [-1, 2,
+///                               0, -4]);
+/// assert_eq!(mat2_neg([0.5, -2.0, 1.0, 0.0]), [-0.5, 2.0, -1.0, -0.0]);
+/// ```
+pub fn mat2_neg<T>(m: Matrix2<T>) -> Matrix2<T>
+    where T: Copy + Neg<Output=T>
+{
+    [
+        -m[0], -m[1],
+        -m[2], -m[3]
+    ]
+}
+
+/// Negates every component of a 3x3 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_neg, mat3_add};
+///
+/// let m = [1, 2, 3,
+///          4, 5, 6,
+///          7, 8, 9];
+/// assert_eq!(mat3_neg(m), [-1, -2, -3,
+///                          -4, -5, -6,
+///                          -7, -8, -9]);
+/// assert_eq!(mat3_add(m, mat3_neg(m)), [0; 9]);
+/// ```
+pub fn mat3_neg<T>(m: Matrix3<T>) -> Matrix3<T>
+    where T: Copy + Neg<Output=T>
+{
+    [
+        -m[0], -m[1], -m[2],
+        -m[3], -m[4], -m[5],
+        -m[6], -m[7], -m[8]
+    ]
+}
+
+/// Negates every component of a 4x4 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_neg, mat4_scaling};
+///
+/// assert_eq!(mat4_neg(mat4_scaling([2, 3, 4])), [-2, 0, 0, 0,
+///                                                0, -3, 0, 0,
+///                                                0, 0, -4, 0,
+///                                                0, 0, 0, -1]);
+/// assert_eq!(mat4_neg(mat4_neg([1.5f32; 16])), [1.5; 16]);
+/// ```
+pub fn mat4_neg<T>(m: Matrix4<T>) -> Matrix4<T>
+    where T: Copy + Neg<Output=T>
+{
+    [
+        -m[0], -m[1], -m[2], -m[3],
+        -m[4], -m[5], -m[6], -m[7],
+        -m[8], -m[9], -m[10], -m[11],
+        -m[12], -m[13], -m[14], -m[15]
+    ]
+}
+
+/// Divides every component of a 2x2 matrix by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_div;
+///
+/// assert_eq!(mat2_div([2, 4,
+///                      7, -8], 2), [1, 2,
+///                                   3, -4]);
+/// assert_eq!(mat2_div([1.0, 2.0, 3.0, 4.0], 4.0), [0.25, 0.5, 0.75, 1.0]);
+/// ```
+pub fn mat2_div<T>(lhs: Matrix2<T>, rhs: T) -> Matrix2<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs, lhs[1] / rhs,
+        lhs[2] / rhs, lhs[3] / rhs
+    ]
+}
+
+/// Divides every component of a 3x3 matrix by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_div;
+///
+/// let m = [3, 6, 9,
+///          12, 15, 18,
+///          21, 24, 27];
+/// assert_eq!(mat3_div(m, 3), [1, 2, 3,
+///                             4, 5, 6,
+///                             7, 8, 9]);
+/// assert_eq!(mat3_div([1.0f64; 9], 0.5), [2.0; 9]);
+/// ```
+pub fn mat3_div<T>(lhs: Matrix3<T>, rhs: T) -> Matrix3<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs, lhs[1] / rhs, lhs[2] / rhs,
+        lhs[3] / rhs, lhs[4] / rhs, lhs[5] / rhs,
+        lhs[6] / rhs, lhs[7] / rhs, lhs[8] / rhs
+    ]
+}
+
+/// Divides every component of a 4x4 matrix by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_div, mat4_add, mat4_scaling, mat4_translation};
+///
+/// assert_eq!(mat4_div([10; 16], 5), [2; 16]);
+///
+/// // Average of two matrices
+/// let a = mat4_scaling([2.0, 4.0, 6.0]);
+/// let b = mat4_translation([1.0, -2.0, 3.0]);
+/// assert_eq!(mat4_div(mat4_add(a, b), 2.0), [1.5, 0.0, 0.0, 0.5,
+///                                            0.0, 2.5, 0.0, -1.0,
+///                                            0.0, 0.0, 3.5, 1.5,
+///                                            0.0, 0.0, 0.0, 1.0]);
+/// ```
+pub fn mat4_div<T>(lhs: Matrix4<T>, rhs: T) -> Matrix4<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs, lhs[1] / rhs, lhs[2] / rhs, lhs[3] / rhs,
+        lhs[4] / rhs, lhs[5] / rhs, lhs[6] / rhs, lhs[7] / rhs,
+        lhs[8] / rhs, lhs[9] / rhs, lhs[10] / rhs, lhs[11] / rhs,
+        lhs[12] / rhs, lhs[13] / rhs, lhs[14] / rhs, lhs[15] / rhs
+    ]
+}
+
+/// Linearly interpolates every component of two 2x2 matrices
+///
+/// `t` = 0 gives `a` and `t` = 1 gives `b`, values outside of [0, 1] extrapolate. The
+/// interpolation is done component by component, so the intermediate matrices of two rotations
+/// are generally not rotations.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_lerp;
+///
+/// let a = [0.0, 2.0,
+///          4.0, -2.0];
+/// let b = [1.0, 4.0,
+///          0.0, 2.0];
+/// assert_eq!(mat2_lerp(a, b, 0.0), a);
+/// assert_eq!(mat2_lerp(a, b, 1.0), b);
+/// assert_eq!(mat2_lerp(a, b, 0.5), [0.5, 3.0,
+///                                   2.0, 0.0]);
+/// assert_eq!(mat2_lerp(a, b, 2.0), [2.0, 6.0,
+///                                   -4.0, 6.0]);
+/// ```
+pub fn mat2_lerp<T>(a: Matrix2<T>, b: Matrix2<T>, t: T) -> Matrix2<T>
+    where T: Copy + One + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    mat2_add(mat2_scale(a, T::one() - t), mat2_scale(b, t))
+}
+
+/// Linearly interpolates every component of two 3x3 matrices
+///
+/// `t` = 0 gives `a` and `t` = 1 gives `b`, values outside of [0, 1] extrapolate. The
+/// interpolation is done component by component, so the intermediate matrices of two rotations
+/// are generally not rotations.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_lerp, mat3_identity, mat3_scale};
+///
+/// let a = mat3_identity::<f32>();
+/// let b = mat3_scale(mat3_identity(), 3.0);
+/// assert_eq!(mat3_lerp(a, b, 0.0), a);
+/// assert_eq!(mat3_lerp(a, b, 1.0), b);
+/// assert_eq!(mat3_lerp(a, b, 0.5), mat3_scale(mat3_identity(), 2.0));
+/// assert_eq!(mat3_lerp(a, b, 2.0), mat3_scale(mat3_identity(), 5.0));
+/// ```
+pub fn mat3_lerp<T>(a: Matrix3<T>, b: Matrix3<T>, t: T) -> Matrix3<T>
+    where T: Copy + One + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    mat3_add(mat3_scale(a, T::one() - t), mat3_scale(b, t))
+}
+
+/// Linearly interpolates every component of two 4x4 matrices
+///
+/// `t` = 0 gives `a` and `t` = 1 gives `b`, values outside of [0, 1] extrapolate. This is
+/// suitable to blend projection matrices, but not to interpolate rotations: the intermediate
+/// matrices are generally not rotations anymore. Interpolate the decomposed rotations with
+/// [`quat_slerp`](crate::quaternion::quat_slerp) instead.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_lerp, mat4_perspective, mat4_rotation_z, mat4_determinant};
+///
+/// let a = mat4_perspective(1.0f64, 1.0, 1.0, 100.0);
+/// let b = mat4_perspective(0.5f64, 1.0, 1.0, 100.0);
+/// assert_eq!(mat4_lerp(a, b, 0.0), a);
+/// assert_eq!(mat4_lerp(a, b, 1.0), b);
+///
+/// let halfway = mat4_lerp(a, b, 0.5);
+/// assert_eq!(halfway[0], (a[0] + b[0]) / 2.0);
+/// assert_eq!(halfway[10], a[10]);
+///
+/// let extrapolated = mat4_lerp(a, b, 2.0);
+/// assert_eq!(extrapolated[5], 2.0 * b[5] - a[5]);
+///
+/// // Halfway between two opposite rotations is not a rotation
+/// let halfway = mat4_lerp(mat4_rotation_z(0.0f32), mat4_rotation_z(std::f32::consts::PI), 0.5);
+/// assert!(mat4_determinant(halfway).abs() < 1e-6);
+/// ```
+pub fn mat4_lerp<T>(a: Matrix4<T>, b: Matrix4<T>, t: T) -> Matrix4<T>
+    where T: Copy + One + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    mat4_add(mat4_scale(a, T::one() - t), mat4_scale(b, t))
+}
+
+/// Multiplies two 2x2 matrices together
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_mul;
+///
+/// let m1 = [1, 2,
+///           3, 4];
+/// let m2 = [5, 6,
+///           7, 8];
+///
+/// assert_eq!(mat2_mul(m1, m2), [19, 22,
+///                               43, 50]);
+/// ```
+pub fn mat2_mul<T>(lhs: Matrix2<T>, rhs: Matrix2<T>) -> Matrix2<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        lhs[0] * rhs[0] + lhs[1] * rhs[2], lhs[0] * rhs[1] + lhs[1] * rhs[3],
+        lhs[2] * rhs[0] + lhs[3] * rhs[2], lhs[2] * rhs[1] + lhs[3] * rhs[3]
+    ]
+}
+
+
+/// Multiplies two 3x3 matrices together
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_mul;
+///
+/// let m1 = [1, 2, 3,
+///           4, 5, 6,
+///           7, 8, 9];
+/// let m2 = [10, 11, 12,
+///           13, 14, 15,
+///           16, 17, 18];
+///
+/// assert_eq!(mat3_mul(m1, m2), [84, 90, 96,
+///                               201, 216, 231,
+///                               318, 342, 366]);
+/// ```
+pub fn mat3_mul<T>(lhs: Matrix3<T>, rhs: Matrix3<T>) -> Matrix3<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        lhs[0] * rhs[0] + lhs[1] * rhs[3] + lhs[2] * rhs[6],
+        lhs[0] * rhs[1] + lhs[1] * rhs[4] + lhs[2] * rhs[7],
+        lhs[0] * rhs[2] + lhs[1] * rhs[5] + lhs[2] * rhs[8],
+
+        lhs[3] * rhs[0] + lhs[4] * rhs[3] + lhs[5] * rhs[6],
+        lhs[3] * rhs[1] + lhs[4] * rhs[4] + lhs[5] * rhs[7],
+        lhs[3] * rhs[2] + lhs[4] * rhs[5] + lhs[5] * rhs[8],
+
+        lhs[6] * rhs[0] + lhs[7] * rhs[3] + lhs[8] * rhs[6],
+        lhs[6] * rhs[1] + lhs[7] * rhs[4] + lhs[8] * rhs[7],
+        lhs[6] * rhs[2] + lhs[7] * rhs[5] + lhs[8] * rhs[8]
+    ]
+}
+
+/// Multiplies two 4x4 matrices together
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat4_mul;
+///
+/// let m1 = [1, 2, 3, 4,
+///           5, 6, 7, 8,
+///           9, 10, 11, 12,
+///           13, 14, 15, 16];
+/// let m2 = [17, 18, 19, 20,
+///           21, 22, 23, 24,
+///           25, 26, 27, 28,
+///           29, 30, 31, 32];
+///
+/// assert_eq!(mat4_mul(m1, m2), [250, 260, 270, 280,
+///                               618, 644, 670, 696,
+///                               986, 1028, 1070, 1112,
+///                               1354, 1412, 1470, 1528]);
+/// ```
+pub fn mat4_mul<T>(lhs: Matrix4<T>, rhs: Matrix4<T>) -> Matrix4<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        lhs[0] * rhs[0] + lhs[1] * rhs[4] + lhs[2] * rhs[8] + lhs[3] * rhs[12],
+        lhs[0] * rhs[1] + lhs[1] * rhs[5] + lhs[2] * rhs[9] + lhs[3] * rhs[13],
+        lhs[0] * rhs[2] + lhs[1] * rhs[6] + lhs[2] * rhs[10] + lhs[3] * rhs[14],
+        lhs[0] * rhs[3] + lhs[1] * rhs[7] + lhs[2] * rhs[11] + lhs[3] * rhs[15],
+
+        lhs[4] * rhs[0] + lhs[5] * rhs[4] + lhs[6] * rhs[8] + lhs[7] * rhs[12],
+        lhs[4] * rhs[1] + lhs[5] * rhs[5] + lhs[6] * rhs[9] + lhs[7] * rhs[13],
+        lhs[4] * rhs[2] + lhs[5] * rhs[6] + lhs[6] * rhs[10] + lhs[7] * rhs[14],
+        lhs[4] * rhs[3] + lhs[5] * rhs[7] + lhs[6] * rhs[11] + lhs[7] * rhs[15],
+
+        lhs[8] * rhs[0] + lhs[9] * rhs[4] + lhs[10] * rhs[8] + lhs[11] * rhs[12],
+        lhs[8] * rhs[1] + lhs[9] * rhs[5] + lhs[10] * rhs[9] + lhs[11] * rhs[13],
+        lhs[8] * rhs[2] + lhs[9] * rhs[6] + lhs[10] * rhs[10] + lhs[11] * rhs[14],
+        lhs[8] * rhs[3] + lhs[9] * rhs[7] + lhs[10] * rhs[11] + lhs[11] * rhs[15],
+
+        lhs[12] * rhs[0] + lhs[13] * rhs[4] + lhs[14] * rhs[8] + lhs[15] * rhs[12],
+        lhs[12] * rhs[1] + lhs[13] * rhs[5] + lhs[14] * rhs[9] + lhs[15] * rhs[13],
+        lhs[12] * rhs[2] + lhs[13] * rhs[6] + lhs[14] * rhs[10] + lhs[15] * rhs[14],
+        lhs[12] * rhs[3] + lhs[13] * rhs[7] + lhs[14] * rhs[11] + lhs[15] * rhs[15]
+    ]
+}
+
+/// Raises a 2x2 matrix to an integer power
+///
+/// Uses exponentiation by squaring, the power 0 is the identity.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_pow, mat2_identity};
+///
+/// // Fibonacci numbers
+/// assert_eq!(mat2_pow([1, 1, 1, 0], 10), [89, 55, 55, 34]);
+/// assert_eq!(mat2_pow([1, 1, 1, 0], 1), [1, 1, 1, 0]);
+/// assert_eq!(mat2_pow([1, 1, 1, 0], 0), mat2_identity());
+/// ```
+pub fn mat2_pow<T>(m: Matrix2<T>, exponent: u32) -> Matrix2<T>
+    where T: One + Zero + Copy + Mul<Output=T> + Add<Output=T>
+{
+    pow_by_squaring(m, exponent, mat2_identity(), mat2_mul)
+}
+
+/// Raises a 3x3 matrix to an integer power
+///
+/// Uses exponentiation by squaring, the power 0 is the identity.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_pow, mat3_identity, mat3_translation_2d};
+///
+/// assert_eq!(mat3_pow(mat3_translation_2d([2, -1]), 7), mat3_translation_2d([14, -7]));
+/// assert_eq!(mat3_pow(mat3_translation_2d([2, -1]), 0), mat3_identity());
+/// ```
+pub fn mat3_pow<T>(m: Matrix3<T>, exponent: u32) -> Matrix3<T>
+    where T: One + Zero + Copy + Mul<Output=T> + Add<Output=T>
+{
+    pow_by_squaring(m, exponent, mat3_identity(), mat3_mul)
+}
+
+/// Raises a 4x4 matrix to an integer power
+///
+/// Uses exponentiation by squaring, the power 0 is the identity.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_pow, mat4_mul, mat4_identity};
+///
+/// let m = [1, 2, 0, -1,
+///          0, 1, 3, 0,
+///          2, 0, 1, 1,
+///          -1, 0, 0, 2];
+/// let expected = mat4_mul(m, mat4_mul(m, mat4_mul(m, mat4_mul(m, m))));
+/// assert_eq!(mat4_pow(m, 5), expected);
+/// assert_eq!(mat4_pow(m, 0), mat4_identity());
+/// ```
+pub fn mat4_pow<T>(m: Matrix4<T>, exponent: u32) -> Matrix4<T>
+    where T: One + Zero + Copy + Mul<Output=T> + Add<Output=T>
+{
+    pow_by_squaring(m, exponent, mat4_identity(), mat4_mul)
+}
+
+fn pow_by_squaring<M>(m: M, mut exponent: u32, identity: M, mul: fn(M, M) -> M) -> M
+    where M: Copy
+{
+    let mut result = identity;
+    let mut base = m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul(result, base);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul(base, base);
+        }
+    }
+    result
+}
+
+/// Transposes a 2x2 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_transpose;
+///
+/// let m = [1, 2,
+///          3, 4];
+/// assert_eq!(mat2_transpose(m), [1, 3,
+///                                2, 4]);
+/// assert_eq!(mat2_transpose(mat2_transpose(m)), m);
+/// ```
+pub fn mat2_transpose<T>(m: Matrix2<T>) -> Matrix2<T>
+    where T: Copy
+{
+    [
+        m[0], m[2],
+        m[1], m[3]
+    ]
+}
+
+/// Transposes a 3x3 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_transpose;
+///
+/// let m = [1, 2, 3,
+///          4, 5, 6,
+///          7, 8, 9];
+/// assert_eq!(mat3_transpose(m), [1, 4, 7,
+///                                2, 5, 8,
+///                                3, 6, 9]);
+/// assert_eq!(mat3_transpose(mat3_transpose(m)), m);
+/// ```
+pub fn mat3_transpose<T>(m: Matrix3<T>) -> Matrix3<T>
+    where T: Copy
+{
+    [
+        m[0], m[3], m[6],
+        m[1], m[4], m[7],
+        m[2], m[5], m[8]
+    ]
+}
+
+/// Transposes a 4x4 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_identity, mat4_transpose};
+///
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// assert_eq!(mat4_transpose(m), [1, 5, 9, 13,
+///                                2, 6, 10, 14,
+///                                3, 7, 11, 15,
+///                                4, 8, 12, 16]);
+/// assert_eq!(mat4_transpose(mat4_transpose(m)), m);
+/// assert_eq!(mat4_transpose(mat4_identity::<i32>()), mat4_identity());
+/// ```
+pub fn mat4_transpose<T>(m: Matrix4<T>) -> Matrix4<T>
+    where T: Copy
+{
+    [
+        m[0], m[4], m[8], m[12],
+        m[1], m[5], m[9], m[13],
+        m[2], m[6], m[10], m[14],
+        m[3], m[7], m[11], m[15]
+    ]
+}
+
+/// Returns the components of a 2x2 matrix in column-major order
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_to_col_major;
+///
+/// assert_eq!(mat2_to_col_major([1, 2,
+///                               3, 4]), [1, 3, 2, 4]);
+/// ```
+pub fn mat2_to_col_major<T>(m: Matrix2<T>) -> [T; 4]
+    where T: Copy
+{
+    mat2_transpose(m)
+}
+
+/// Builds a 2x2 matrix from components in column-major order
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_from_col_major, mat2_to_col_major};
+///
+/// assert_eq!(mat2_from_col_major([1, 3, 2, 4]), [1, 2,
+///                                                3, 4]);
+/// assert_eq!(mat2_from_col_major(mat2_to_col_major([1, 2, 3, 4])), [1, 2, 3, 4]);
+/// ```
+pub fn mat2_from_col_major<T>(a: [T; 4]) -> Matrix2<T>
+    where T: Copy
+{
+    mat2_transpose(a)
+}
+
+/// Returns the components of a 3x3 matrix in column-major order
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_to_col_major;
+///
+/// assert_eq!(mat3_to_col_major([1, 2, 3,
+///                               4, 5, 6,
+///                               7, 8, 9]), [1, 4, 7, 2, 5, 8, 3, 6, 9]);
+/// ```
+pub fn mat3_to_col_major<T>(m: Matrix3<T>) -> [T; 9]
+    where T: Copy
+{
+    mat3_transpose(m)
+}
+
+/// Builds a 3x3 matrix from components in column-major order
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_from_col_major, mat3_translation_2d};
+///
+/// // Column-major 2D translation, as stored by OpenGL
+/// assert_eq!(mat3_from_col_major([1, 0, 0, 0, 1, 0, 5, -2, 1]), mat3_translation_2d([5, -2]));
+/// ```
+pub fn mat3_from_col_major<T>(a: [T; 9]) -> Matrix3<T>
+    where T: Copy
+{
+    mat3_transpose(a)
+}
+
+/// Returns the components of a 4x4 matrix in column-major order
+///
+/// This is the layout expected by OpenGL uniforms, the translation ends up in the
+/// components 12, 13 and 14.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_to_col_major, mat4_translation, mat4_transpose, mat4_transform_vec};
+///
+/// let m = mat4_translation([5, -2, 3]);
+/// let a = mat4_to_col_major(m);
+/// assert_eq!(a[12..15], [5, -2, 3]);
+/// assert_eq!(a, mat4_transpose(m));
+///
+/// // Multiplying with a column-major array matches mat4_transform_vec
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// let a = mat4_to_col_major(m);
+/// let v = [1, -1, 2, 3];
+/// let mut product = [0; 4];
+/// for column in 0..4 {
+///     for row in 0..4 {
+///         product[row] += a[column * 4 + row] * v[column];
+///     }
+/// }
+/// assert_eq!(product, mat4_transform_vec(m, v));
+/// ```
+pub fn mat4_to_col_major<T>(m: Matrix4<T>) -> [T; 16]
+    where T: Copy
+{
+    mat4_transpose(m)
+}
+
+/// Builds a 4x4 matrix from components in column-major order
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_from_col_major, mat4_to_col_major, mat4_translation, mat4_transform_vec};
+///
+/// let a = [1, 0, 0, 0,
+///          0, 1, 0, 0,
+///          0, 0, 1, 0,
+///          5, -2, 3, 1];
+/// let m = mat4_from_col_major(a);
+/// assert_eq!(m, mat4_translation([5, -2, 3]));
+/// assert_eq!(mat4_transform_vec(m, [1, 1, 1, 1]), [6, -1, 4, 1]);
+/// assert_eq!(mat4_to_col_major(m), a);
+/// ```
+pub fn mat4_from_col_major<T>(a: [T; 16]) -> Matrix4<T>
+    where T: Copy
+{
+    mat4_transpose(a)
+}
+
+/// Computes the determinant of a 2x2 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_determinant, mat2_identity};
+///
+/// assert_eq!(mat2_determinant(mat2_identity::<i32>()), 1);
+///
+/// // Singular
+/// assert_eq!(mat2_determinant([2, 4,
+///                              1, 2]), 0);
+///
+/// // Reflection
+/// assert_eq!(mat2_determinant([0, 1,
+///                              1, 0]), -1);
+/// assert_eq!(mat2_determinant([3, 1,
+///                              5, -2]), -11);
+/// ```
+pub fn mat2_determinant<T>(m: Matrix2<T>) -> T
+    where T: Copy + Mul<Output=T> + Sub<Output=T>
+{
+    m[0] * m[3] - m[1] * m[2]
+}
+
+/// Computes the determinant of a 3x3 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_determinant, mat3_identity};
+///
+/// assert_eq!(mat3_determinant(mat3_identity::<i32>()), 1);
+///
+/// // Singular, the third row is the sum of the first two
+/// assert_eq!(mat3_determinant([1, 2, 3,
+///                              4, 5, 6,
+///                              5, 7, 9]), 0);
+///
+/// // Reflection
+/// assert_eq!(mat3_determinant([-1, 0, 0,
+///                              0, 1, 0,
+///                              0, 0, 1]), -1);
+/// assert_eq!(mat3_determinant([2, 0, 1,
+///                              1, 3, 2,
+///                              1, 1, -1]), -12);
+/// ```
+pub fn mat3_determinant<T>(m: Matrix3<T>) -> T
+    where T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    m[0] * (m[4] * m[8] - m[5] * m[7])
+        - m[1] * (m[3] * m[8] - m[5] * m[6])
+        + m[2] * (m[3] * m[7] - m[4] * m[6])
+}
+
+/// Computes the determinant of a 4x4 matrix
+///
+/// Uses the cofactor expansion along the first row.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_determinant, mat4_identity};
+///
+/// assert_eq!(mat4_determinant(mat4_identity::<i32>()), 1);
+///
+/// // Translation
+/// assert_eq!(mat4_determinant([1, 0, 0, 5,
+///                              0, 1, 0, -3,
+///                              0, 0, 1, 8,
+///                              0, 0, 0, 1]), 1);
+///
+/// // Scaling
+/// assert_eq!(mat4_determinant([2, 0, 0, 0,
+///                              0, 3, 0, 0,
+///                              0, 0, -4, 0,
+///                              0, 0, 0, 1]), -24);
+///
+/// // Singular, the second row is repeated
+/// assert_eq!(mat4_determinant([1, 2, 3, 4,
+///                              5, 6, 7, 8,
+///                              5, 6, 7, 8,
+///                              2, 0, 1, 3]), 0);
+///
+/// assert_eq!(mat4_determinant([1, 2, 0, 1,
+///                              0, 1, 3, 2,
+///                              4, 0, 1, 0,
+///                              1, 1, 0, 2]), 39);
+/// ```
+pub fn mat4_determinant<T>(m: Matrix4<T>) -> T
+    where T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    let minor = |c0: usize, c1: usize, c2: usize| mat3_determinant([
+        m[4 + c0], m[4 + c1], m[4 + c2],
+        m[8 + c0], m[8 + c1], m[8 + c2],
+        m[12 + c0], m[12 + c1], m[12 + c2]
+    ]);
+
+    m[0] * minor(1, 2, 3) - m[1] * minor(0, 2, 3) + m[2] * minor(0, 1, 3) - m[3] * minor(0, 1, 2)
+}
+
+/// Computes the cofactor matrix of a 3x3 matrix
+///
+/// The component (i, j) of the cofactor matrix is (-1)^(i + j) times the determinant of the
+/// matrix without its row i and its column j.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_cofactor;
+///
+/// let m = [1, 2, 3,
+///          0, 1, 4,
+///          5, 6, 0];
+/// assert_eq!(mat3_cofactor(m), [-24, 20, -5,
+///                               18, -15, 4,
+///                               5, -4, 1]);
+/// ```
+pub fn mat3_cofactor<T>(m: Matrix3<T>) -> Matrix3<T>
+    where T: Copy + Sub<Output=T> + Mul<Output=T>
+{
+    let cofactor = |row: usize, column: usize| {
+        let (r0, r1) = ((row + 1) % 3 * 3, (row + 2) % 3 * 3);
+        let (c0, c1) = ((column + 1) % 3, (column + 2) % 3);
+        m[r0 + c0] * m[r1 + c1] - m[r0 + c1] * m[r1 + c0]
+    };
+
+    [
+        cofactor(0, 0), cofactor(0, 1), cofactor(0, 2),
+        cofactor(1, 0), cofactor(1, 1), cofactor(1, 2),
+        cofactor(2, 0), cofactor(2, 1), cofactor(2, 2)
+    ]
+}
+
+/// Computes the adjugate of a 3x3 matrix, the transpose of its cofactor matrix
+///
+/// The product of a matrix and its adjugate is its determinant times the identity, so the
+/// adjugate is the inverse scaled by the determinant.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_adjugate, mat3_mul, mat3_determinant, mat3_identity, mat3_scale};
+///
+/// let matrices = [
+///     [1, 2, 3,
+///      0, 1, 4,
+///      5, 6, 0],
+///     [2, -1, 0,
+///      -1, 2, -1,
+///      0, -1, 2],
+///     // Singular
+///     [1, 2, 3,
+///      4, 5, 6,
+///      7, 8, 9],
+/// ];
+/// for &m in &matrices {
+///     let expected = mat3_scale(mat3_identity(), mat3_determinant(m));
+///     assert_eq!(mat3_mul(m, mat3_adjugate(m)), expected);
+///     assert_eq!(mat3_mul(mat3_adjugate(m), m), expected);
+/// }
+/// ```
+pub fn mat3_adjugate<T>(m: Matrix3<T>) -> Matrix3<T>
+    where T: Copy + Sub<Output=T> + Mul<Output=T>
+{
+    mat3_transpose(mat3_cofactor(m))
+}
+
+/// Computes the cofactor matrix of a 4x4 matrix
+///
+/// The component (i, j) of the cofactor matrix is (-1)^(i + j) times the determinant of the
+/// matrix without its row i and its column j.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_cofactor, mat4_identity};
+///
+/// assert_eq!(mat4_cofactor(mat4_identity::<i32>()), mat4_identity());
+///
+/// let m = [2, 0, 0, 0,
+///          0, 3, 0, 0,
+///          0, 0, 4, 0,
+///          0, 0, 0, 1];
+/// assert_eq!(mat4_cofactor(m), [12, 0, 0, 0,
+///                               0, 8, 0, 0,
+///                               0, 0, 6, 0,
+///                               0, 0, 0, 24]);
+/// ```
+pub fn mat4_cofactor<T>(m: Matrix4<T>) -> Matrix4<T>
+    where T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Neg<Output=T>
+{
+    let cofactor = |row: usize, column: usize| {
+        let mut minor = [m[0]; 9];
+        let rows = (0..4).filter(|&r| r != row);
+        for (i, r) in rows.enumerate() {
+            let columns = (0..4).filter(|&c| c != column);
+            for (j, c) in columns.enumerate() {
+                minor[i * 3 + j] = m[r * 4 + c];
+            }
+        }
+
+        let determinant = mat3_determinant(minor);
+        if (row + column) % 2 == 1 { -determinant } else { determinant }
+    };
+
+    let mut cofactors = m;
+    for row in 0..4 {
+        for column in 0..4 {
+            cofactors[row * 4 + column] = cofactor(row, column);
+        }
+    }
+    cofactors
+}
+
+/// Computes the adjugate of a 4x4 matrix, the transpose of its cofactor matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_adjugate, mat4_mul, mat4_determinant, mat4_identity, mat4_scale};
+///
+/// let m = [2, 1, 0, 3,
+///          -1, 4, 2, 0,
+///          0, 1, 1, 5,
+///          3, 0, -2, 1];
+/// let expected = mat4_scale(mat4_identity(), mat4_determinant(m));
+/// assert_eq!(mat4_mul(m, mat4_adjugate(m)), expected);
+/// assert_eq!(mat4_mul(mat4_adjugate(m), m), expected);
+///
+/// // Singular
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// assert_eq!(mat4_mul(m, mat4_adjugate(m)), [0; 16]);
+/// ```
+pub fn mat4_adjugate<T>(m: Matrix4<T>) -> Matrix4<T>
+    where T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Neg<Output=T>
+{
+    mat4_transpose(mat4_cofactor(m))
+}
+
+/// Returns whether a 3x3 matrix preserves the orientation, i.e. whether its determinant is
+/// positive
+///
+/// Transforms that don't preserve the orientation mirror the space and flip the winding order
+/// of the triangles.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_preserves_orientation, mat3_identity};
+///
+/// assert!(mat3_preserves_orientation(mat3_identity::<i32>()));
+/// assert!(!mat3_preserves_orientation([-1, 0, 0,
+///                                      0, 1, 0,
+///                                      0, 0, 1]));
+/// assert!(!mat3_preserves_orientation([0; 9]));
+/// ```
+pub fn mat3_preserves_orientation<T>(m: Matrix3<T>) -> bool
+    where T: Copy + PartialOrd + Zero + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    mat3_determinant(m) > T::zero()
+}
+
+/// Returns whether a 4x4 transform preserves the orientation, i.e. whether the determinant of
+/// its upper-left 3x3 block is positive
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_preserves_orientation, mat4_rotation_axis, mat4_reflection, mat4_scaling};
+///
+/// assert!(mat4_preserves_orientation(mat4_rotation_axis([1.0, 2.0, -1.0], 2.5)));
+/// assert!(!mat4_preserves_orientation(mat4_reflection([0.0, 1.0, 0.0], 3.0)));
+/// assert!(!mat4_preserves_orientation(mat4_scaling([2.0, -1.0, 1.0])));
+/// assert!(mat4_preserves_orientation(mat4_scaling([-2.0, -1.0, 1.0])));
+/// ```
+pub fn mat4_preserves_orientation<T>(m: Matrix4<T>) -> bool
+    where T: Copy + PartialOrd + Zero + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    mat3_preserves_orientation(mat4_to_mat3(m))
+}
+
+/// Returns the handedness of the basis of a 4x4 transform
+///
+/// The basis is degenerate if the absolute value of the determinant of the upper-left 3x3
+/// block is lower than or equal to `epsilon`. As the determinant is the product of the scale
+/// factors, the tolerance should be chosen according to the scale of the transforms.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_handedness, mat4_rotation_axis, mat4_reflection, mat4_from_trs, Handedness};
+///
+/// assert_eq!(mat4_handedness(mat4_rotation_axis([1.0, 2.0, -1.0], 2.5), 1e-6), Handedness::RightHanded);
+/// assert_eq!(mat4_handedness(mat4_reflection([1.0, 1.0, 0.0], -2.0), 1e-6), Handedness::LeftHanded);
+///
+/// let flattened = mat4_from_trs([1.0, 2.0, 3.0], ([0.0, 1.0, 0.0], 0.4), [1.0, 0.0, 2.0]);
+/// assert_eq!(mat4_handedness(flattened, 1e-6), Handedness::Degenerate);
+/// let nearly_flattened = mat4_from_trs([1.0, 2.0, 3.0], ([0.0, 1.0, 0.0], 0.4), [1.0, -1e-8, 2.0]);
+/// assert_eq!(mat4_handedness(nearly_flattened, 1e-6), Handedness::Degenerate);
+/// assert_eq!(mat4_handedness(nearly_flattened, 0.0), Handedness::LeftHanded);
+/// ```
+pub fn mat4_handedness<T>(m: Matrix4<T>, epsilon: T) -> Handedness
+    where T: Float
+{
+    let determinant = mat3_determinant(mat4_to_mat3(m));
+    if determinant.abs() <= epsilon {
+        Handedness::Degenerate
+    } else if determinant > T::zero() {
+        Handedness::RightHanded
+    } else {
+        Handedness::LeftHanded
+    }
+}
+
+/// Applies a function to each element of a 4x4 matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_map, mat4_scale, mat4_identity};
+///
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// assert_eq!(mat4_map(m, |x| x * 3), mat4_scale(m, 3));
+/// assert_eq!(mat4_map(mat4_identity::<f32>(), |x| x as i32), mat4_identity());
+/// ```
+pub fn mat4_map<T, U, F>(m: Matrix4<T>, f: F) -> Matrix4<U>
+    where T: Copy, F: Fn(T) -> U
+{
+    [
+        f(m[0]), f(m[1]), f(m[2]), f(m[3]),
+        f(m[4]), f(m[5]), f(m[6]), f(m[7]),
+        f(m[8]), f(m[9]), f(m[10]), f(m[11]),
+        f(m[12]), f(m[13]), f(m[14]), f(m[15])
+    ]
+}
+
+/// Combines the elements of two 4x4 matrices pairwise using a function
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_zip_with, mat4_add, mat4_sub, mat4_identity};
+///
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// let identity = mat4_identity();
+/// assert_eq!(mat4_zip_with(m, identity, |a, b| a + b), mat4_add(m, identity));
+/// assert_eq!(mat4_zip_with(m, identity, |a, b| a - b), mat4_sub(m, identity));
+/// assert_eq!(mat4_zip_with(m, identity, |a, b| a * b), [1, 0, 0, 0,
+///                                                       0, 6, 0, 0,
+///                                                       0, 0, 11, 0,
+///                                                       0, 0, 0, 16]);
+/// ```
+pub fn mat4_zip_with<T, U, F>(lhs: Matrix4<T>, rhs: Matrix4<T>, f: F) -> Matrix4<U>
+    where T: Copy, F: Fn(T, T) -> U
+{
+    [
+        f(lhs[0], rhs[0]), f(lhs[1], rhs[1]), f(lhs[2], rhs[2]), f(lhs[3], rhs[3]),
+        f(lhs[4], rhs[4]), f(lhs[5], rhs[5]), f(lhs[6], rhs[6]), f(lhs[7], rhs[7]),
+        f(lhs[8], rhs[8]), f(lhs[9], rhs[9]), f(lhs[10], rhs[10]), f(lhs[11], rhs[11]),
+        f(lhs[12], rhs[12]), f(lhs[13], rhs[13]), f(lhs[14], rhs[14]), f(lhs[15], rhs[15])
+    ]
+}
+
+/// Computes the mean of a set of 4x4 matrices
+///
+/// The mean is computed componentwise, the mean of rotation matrices generally isn't a
+/// rotation. The values are accumulated in f64. Returns None if the slice is empty.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_mean, mat4_identity};
+///
+/// let translation = [1.0, 0.0, 0.0, 4.0,
+///                    0.0, 1.0, 0.0, -2.0,
+///                    0.0, 0.0, 1.0, 1.0,
+///                    0.0, 0.0, 0.0, 1.0];
+/// assert_eq!(mat4_mean(&[translation, mat4_identity()]), Some([1.0, 0.0, 0.0, 2.0,
+///                                                              0.0, 1.0, 0.0, -1.0,
+///                                                              0.0, 0.0, 1.0, 0.5,
+///                                                              0.0, 0.0, 0.0, 1.0]));
+///
+/// // Rotations of +90° and -90° around Z average to a projection on the Z axis
+/// let rotation = [0.0, -1.0, 0.0, 0.0,
+///                 1.0, 0.0, 0.0, 0.0,
+///                 0.0, 0.0, 1.0, 0.0,
+///                 0.0, 0.0, 0.0, 1.0];
+/// let inverse_rotation = [0.0, 1.0, 0.0, 0.0,
+///                         -1.0, 0.0, 0.0, 0.0,
+///                         0.0, 0.0, 1.0, 0.0,
+///                         0.0, 0.0, 0.0, 1.0];
+/// assert_eq!(mat4_mean(&[rotation, inverse_rotation]), Some([0.0, 0.0, 0.0, 0.0,
+///                                                           0.0, 0.0, 0.0, 0.0,
+///                                                           0.0, 0.0, 1.0, 0.0,
+///                                                           0.0, 0.0, 0.0, 1.0]));
+///
+/// assert_eq!(mat4_mean(&[translation]), Some(translation));
+/// assert_eq!(mat4_mean::<f32>(&[]), None);
+///
+/// // Large counts, where a naive f32 sum drifts
+/// let matrices = vec![[0.1f32; 16]; 4096];
+/// let naive_sum: f32 = matrices.iter().map(|m| m[0]).sum();
+/// assert_ne!(naive_sum / 4096.0, 0.1);
+/// assert_eq!(mat4_mean(&matrices), Some([0.1; 16]));
+/// ```
+pub fn mat4_mean<T>(matrices: &[Matrix4<T>]) -> Option<Matrix4<T>>
+    where T: Float
+{
+    if matrices.is_empty() {
+        return None;
+    }
+
+    let sum = matrices.iter().fold([0.0f64; 16], |sum, m| mat4_zip_with(sum, mat4_map(*m, T::to_f64), |a, b| a + b));
+    let count = matrices.len() as f64;
+    Some(mat4_map(sum, |s| T::from_f64(s / count)))
+}
+
+/// Tests whether two 2x2 matrices are equal within a tolerance
+///
+/// Two components a and b are equal if |a - b| <= epsilon * max(1, |a|, |b|): the tolerance
+/// is absolute for components smaller than 1 and relative for larger ones, such as big
+/// translations. NaN components are never equal.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat2_approx_eq;
+///
+/// let a = [1.0, 2.0, 3.0, 4.0];
+/// assert!(mat2_approx_eq(a, [1.0, 2.0, 3.0, 4.0 + 1e-7], 1e-6));
+/// assert!(!mat2_approx_eq(a, [1.0, 2.0, 3.0, 4.0 + 1e-4], 1e-6));
+/// assert!(!mat2_approx_eq([f64::NAN, 0.0, 0.0, 0.0], [f64::NAN, 0.0, 0.0, 0.0], 1.0));
+/// ```
+pub fn mat2_approx_eq<T>(lhs: Matrix2<T>, rhs: Matrix2<T>, epsilon: T) -> bool
+    where T: Float
+{
+    approx_eq(&lhs, &rhs, epsilon)
+}
+
+/// Tests whether two 3x3 matrices are equal within a tolerance
+///
+/// See `mat2_approx_eq` for the tolerance.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_approx_eq, mat3_identity};
+///
+/// let mut m = mat3_identity::<f64>();
+/// m[4] += 1e-7;
+/// assert!(mat3_approx_eq(mat3_identity(), m, 1e-6));
+/// assert!(!mat3_approx_eq(mat3_identity(), m, 1e-8));
+/// ```
+pub fn mat3_approx_eq<T>(lhs: Matrix3<T>, rhs: Matrix3<T>, epsilon: T) -> bool
+    where T: Float
+{
+    approx_eq(&lhs, &rhs, epsilon)
+}
+
+/// Tests whether two 4x4 matrices are equal within a tolerance
+///
+/// See `mat2_approx_eq` for the tolerance.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_approx_eq, mat4_from_trs, mat4_mul, mat4_rotation_axis, mat4_scaling, mat4_translation};
+///
+/// // Different composition orders
+/// let (translation, axis, angle, scale) = ([1e6f64, -2.0, 3.5], [1.0, 2.0, -0.5], 0.7, [2.0, 0.5, 3.0]);
+/// let a = mat4_from_trs(translation, (axis, angle), scale);
+/// let b = mat4_mul(mat4_translation(translation), mat4_mul(mat4_rotation_axis(axis, angle), mat4_scaling(scale)));
+/// assert!(mat4_approx_eq(a, b, 1e-12));
+///
+/// // 1e-7 apart
+/// let mut c = a;
+/// c[5] += 1e-7;
+/// assert!(mat4_approx_eq(a, c, 1e-6));
+/// assert!(!mat4_approx_eq(a, c, 1e-8));
+///
+/// // The tolerance is relative for large components
+/// let mut d = a;
+/// d[3] += 0.5;
+/// assert!(mat4_approx_eq(a, d, 1e-6));
+/// assert!(!mat4_approx_eq(a, d, 1e-8));
+///
+/// // NaN components always differ
+/// let mut e = a;
+/// e[0] = f64::NAN;
+/// assert!(!mat4_approx_eq(e, e, 1.0));
+/// assert!(!mat4_approx_eq(a, e, f64::INFINITY));
+/// ```
+pub fn mat4_approx_eq<T>(lhs: Matrix4<T>, rhs: Matrix4<T>, epsilon: T) -> bool
+    where T: Float
+{
+    approx_eq(&lhs, &rhs, epsilon)
+}
+
+fn approx_eq<T>(lhs: &[T], rhs: &[T], epsilon: T) -> bool
+    where T: Float
+{
+    lhs.iter().zip(rhs).all(|(&a, &b)| {
+        let (a_abs, b_abs) = (a.abs(), b.abs());
+        let scale = if a_abs > b_abs { a_abs } else { b_abs };
+        let scale = if scale > T::one() { scale } else { T::one() };
+        (a - b).abs() <= epsilon * scale
+    })
+}
+
+/// Tests whether a 4x4 matrix is the identity within a tolerance
+///
+/// See `mat2_approx_eq` for the tolerance.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_is_identity, mat4_identity};
+///
+/// assert!(mat4_is_identity(mat4_identity::<f64>(), 0.0));
+///
+/// let mut m = mat4_identity::<f64>();
+/// m[5] = 1.0 + 0.9e-6;
+/// assert!(mat4_is_identity(m, 1e-6));
+/// m[5] = 1.0 + 1.1e-6;
+/// assert!(!mat4_is_identity(m, 1e-6));
+///
+/// let mut m = mat4_identity::<f64>();
+/// m[3] = -0.9e-6;
+/// assert!(mat4_is_identity(m, 1e-6));
+/// m[3] = -1.1e-6;
+/// assert!(!mat4_is_identity(m, 1e-6));
+/// ```
+pub fn mat4_is_identity<T>(m: Matrix4<T>, epsilon: T) -> bool
+    where T: Float
+{
+    mat4_approx_eq(m, mat4_identity(), epsilon)
+}
+
+/// Tests whether a 3x3 matrix is symmetric within a tolerance
+///
+/// See `mat2_approx_eq` for the tolerance.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_is_symmetric;
+///
+/// let m = [1.0, 2.0, 3.0,
+///          2.0, 4.0, 5.0,
+///          3.0, 5.0, 6.0];
+/// assert!(mat3_is_symmetric(m, 0.0));
+///
+/// // Relative tolerance for large components
+/// let m = [1.0, 2e6, 3.0,
+///          2e6 + 1.9, 4.0, 5.0,
+///          3.0, 5.0, 6.0];
+/// assert!(mat3_is_symmetric(m, 1e-6));
+/// let m = [1.0, 2e6, 3.0,
+///          2e6 + 2.1, 4.0, 5.0,
+///          3.0, 5.0, 6.0];
+/// assert!(!mat3_is_symmetric(m, 1e-6));
+/// ```
+pub fn mat3_is_symmetric<T>(m: Matrix3<T>, epsilon: T) -> bool
+    where T: Float
+{
+    mat3_approx_eq(m, mat3_transpose(m), epsilon)
+}
+
+/// Tests whether a 3x3 matrix is orthogonal, i.e. m * transpose(m) is the identity, within a
+/// tolerance
+///
+/// The tolerance applies to the components of m * transpose(m), see `mat2_approx_eq`.
+/// Reflections are orthogonal too.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_is_orthogonal, mat3_rotation_2d, mat3_scaling_2d};
+///
+/// assert!(mat3_is_orthogonal(mat3_rotation_2d(0.7f64), 1e-12));
+/// assert!(mat3_is_orthogonal(mat3_scaling_2d([-1.0, 1.0]), 0.0));
+///
+/// // m * transpose(m) has 1 + 1.8e-6 on its diagonal
+/// assert!(mat3_is_orthogonal(mat3_scaling_2d([1.0 + 0.9e-6, 1.0]), 2e-6));
+/// assert!(!mat3_is_orthogonal(mat3_scaling_2d([1.0 + 0.9e-6, 1.0]), 1.6e-6));
+/// ```
+pub fn mat3_is_orthogonal<T>(m: Matrix3<T>, epsilon: T) -> bool
+    where T: Float
+{
+    mat3_approx_eq(mat3_mul(m, mat3_transpose(m)), mat3_identity(), epsilon)
+}
+
+/// Tests whether a 4x4 matrix is affine, i.e. its last row is [0, 0, 0, 1], within a
+/// tolerance
+///
+/// See `mat2_approx_eq` for the tolerance.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_is_affine, mat4_from_trs, mat4_perspective};
+///
+/// assert!(mat4_is_affine(mat4_from_trs([1e6, 2.0, 3.0], ([0.0, 1.0, 0.0], 0.5), [2.0, 2.0, 2.0]), 0.0));
+/// assert!(!mat4_is_affine(mat4_perspective(1.2, 1.5, 0.1, 100.0), 1e-6));
+///
+/// let mut m = mat4_from_trs([1.0, 2.0, 3.0], ([0.0, 1.0, 0.0], 0.5), [2.0, 2.0, 2.0]);
+/// m[12] = 0.9e-6;
+/// assert!(mat4_is_affine(m, 1e-6));
+/// m[12] = 1.1e-6;
+/// assert!(!mat4_is_affine(m, 1e-6));
+/// ```
+pub fn mat4_is_affine<T>(m: Matrix4<T>, epsilon: T) -> bool
+    where T: Float
+{
+    approx_eq(&m[12..16], &[T::zero(), T::zero(), T::zero(), T::one()], epsilon)
+}
+
+/// Transforms a vector using a 2x2 matrix
+///
+/// # Examples
+///
+/// Identity
+/// ```
+/// use stones::matrix::mat2_transform_vec;
+/// use stones::matrix::mat2_identity;
+///
+/// let m = mat2_identity();
+/// let v = [5, 7];
+///
+/// assert_eq!(mat2_transform_vec(m, v), [5, 7]);
+/// ```
+///
+/// Rotation of 90°
+/// ```
+/// use stones::matrix::mat2_transform_vec;
+///
+/// let m = [0, -1,
+///          1, 0];
+/// let v = [5, 7];
+///
+/// assert_eq!(mat2_transform_vec(m, v), [-7, 5]);
+/// ```
+///
+/// Scaling
+/// ```
+/// use stones::matrix::mat2_transform_vec;
+///
+/// let m = [3, 0,
+///          0, 2];
+/// let v = [5, 7];
+///
+/// assert_eq!(mat2_transform_vec(m, v), [15, 14]);
+/// ```
+pub fn mat2_transform_vec<T>(lhs: Matrix2<T>, rhs: Vector2<T>) -> Vector2<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        lhs[0] * rhs[0] + lhs[1] * rhs[1],
+        lhs[2] * rhs[0] + lhs[3] * rhs[1]
+    ]
+}
+
+/// Transforms a vector using a 3x3 matrix
+///
+/// # Examples
+///
+/// Identity
+/// ```
+/// use stones::matrix::mat3_transform_vec;
+/// use stones::matrix::mat3_identity;
+///
+/// let m = mat3_identity();
+/// let v = [5, 7, 2];
+///
+/// assert_eq!(mat3_transform_vec(m, v), [5, 7, 2]);
+/// ```
+///
+/// Rotation of 90° around Z
+/// ```
+/// use stones::matrix::mat3_transform_vec;
+///
+/// let m = [0, -1, 0,
+///          1, 0, 0,
+///          0, 0, 1];
+/// let v = [5, 7, 2];
+///
+/// assert_eq!(mat3_transform_vec(m, v), [-7, 5, 2]);
+/// ```
+///
+/// Scaling
+/// ```
+/// use stones::matrix::mat3_transform_vec;
+///
+/// let m = [3, 0, 0,
+///          0, 2, 0,
+///          0, 0, 1];
+/// let v = [5, 7, 2];
+///
+/// assert_eq!(mat3_transform_vec(m, v), [15, 14, 2]);
+/// ```
+pub fn mat3_transform_vec<T>(lhs: Matrix3<T>, rhs: Vector3<T>) -> Vector3<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        lhs[0] * rhs[0] + lhs[1] * rhs[1] + lhs[2] * rhs[2],
+        lhs[3] * rhs[0] + lhs[4] * rhs[1] + lhs[5] * rhs[2],
+        lhs[6] * rhs[0] + lhs[7] * rhs[1] + lhs[8] * rhs[2]
+    ]
+}
+
+/// Transforms a vector using a 4x4 matrix
+///
+/// # Examples
+///
+/// Identity
+/// ```
+/// use stones::matrix::mat4_transform_vec;
+/// use stones::matrix::mat4_identity;
+///
+/// let m = mat4_identity();
+/// let v = [5, 7, 2, 3];
+///
+/// assert_eq!(mat4_transform_vec(m, v), [5, 7, 2, 3]);
+/// ```
+///
 /// Scaling
 /// ```
-/// use stones::matrix::mat4_transform_vec;
-/// use stones::matrix::mat4_identity;
+/// use stones::matrix::mat4_transform_vec;
+/// use stones::matrix::mat4_identity;
+///
+/// let m = [3, 0, 0, 0,
+///          0, 2, 0, 0,
+///          0, 0, 1, 0,
+///          0, 0, 0, 1];
+/// let v = [5, 7, 2, 3];
+///
+/// assert_eq!(mat4_transform_vec(m, v), [15, 14, 2, 3]);
+/// ```
+///
+/// Projection, the resulting w component is no longer 1 and the result still has to be
+/// divided by it, see `mat4_transform_vec_divide`
+/// ```
+/// use stones::matrix::mat4_transform_vec;
+///
+/// // Perspective projection with a 90° field of view, near = 1 and far = 3
+/// let m = [1, 0, 0, 0,
+///          0, 1, 0, 0,
+///          0, 0, -2, -3,
+///          0, 0, -1, 0];
+/// let v = [2, 1, -2, 1];
+///
+/// assert_eq!(mat4_transform_vec(m, v), [2, 1, 1, 2]);
+/// ```
+pub fn mat4_transform_vec<T>(lhs: Matrix4<T>, rhs: Vector4<T>) -> Vector4<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        lhs[0] * rhs[0] + lhs[1] * rhs[1] + lhs[2] * rhs[2] + lhs[3] * rhs[3],
+        lhs[4] * rhs[0] + lhs[5] * rhs[1] + lhs[6] * rhs[2] + lhs[7] * rhs[3],
+        lhs[8] * rhs[0] + lhs[9] * rhs[1] + lhs[10] * rhs[2] + lhs[11] * rhs[3],
+        lhs[12] * rhs[0] + lhs[13] * rhs[1] + lhs[14] * rhs[2] + lhs[15] * rhs[3]
+    ]
+}
+
+/// Transforms a vector using a 4x4 matrix and divides the result by its w component
+///
+/// Returns None if the resulting w component is zero, which is the case for directions
+/// transformed by an affine matrix and for points lying on the camera plane of a
+/// perspective projection.
+///
+/// # Examples
+///
+/// Projection
+/// ```
+/// use stones::matrix::mat4_transform_vec_divide;
+///
+/// // Perspective projection with a 90° field of view, near = 1 and far = 3
+/// let m = [1.0, 0.0, 0.0, 0.0,
+///          0.0, 1.0, 0.0, 0.0,
+///          0.0, 0.0, -2.0, -3.0,
+///          0.0, 0.0, -1.0, 0.0];
+///
+/// assert_eq!(mat4_transform_vec_divide(m, [2.0, 1.0, -2.0, 1.0]), Some([1.0, 0.5, 0.5, 1.0]));
+/// assert_eq!(mat4_transform_vec_divide(m, [0.0, 0.0, -1.0, 1.0]), Some([0.0, 0.0, -1.0, 1.0]));
+/// assert_eq!(mat4_transform_vec_divide(m, [0.0, 0.0, -3.0, 1.0]), Some([0.0, 0.0, 1.0, 1.0]));
+///
+/// // Points on the camera plane have no projection
+/// assert_eq!(mat4_transform_vec_divide(m, [5.0, 2.0, 0.0, 1.0]), None);
+/// ```
+///
+/// Directions
+/// ```
+/// use stones::matrix::mat4_transform_vec_divide;
+///
+/// let m = [1.0, 0.0, 0.0, 4.0,
+///          0.0, 1.0, 0.0, 5.0,
+///          0.0, 0.0, 1.0, 6.0,
+///          0.0, 0.0, 0.0, 1.0];
+///
+/// assert_eq!(mat4_transform_vec_divide(m, [1.0, 2.0, 3.0, 0.0]), None);
+/// ```
+pub fn mat4_transform_vec_divide<T>(lhs: Matrix4<T>, rhs: Vector4<T>) -> Option<Vector4<T>>
+    where T: Copy + PartialEq + Zero + One + Mul<Output=T> + Add<Output=T> + Div<Output=T>
+{
+    let v = mat4_transform_vec(lhs, rhs);
+    if v[3] == T::zero() {
+        return None;
+    }
+
+    Some([
+        v[0] / v[3],
+        v[1] / v[3],
+        v[2] / v[3],
+        T::one()
+    ])
+}
+
+/// Transforms a 3D point using a 4x4 matrix
+///
+/// The point is extended with w = 1 and the result is divided by its w component when it
+/// isn't 1, as with a perspective projection. A zero w component gives infinite or NaN
+/// components, `mat4_transform_vec_divide` detects this case.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_transform_point3, mat4_translation};
+///
+/// assert_eq!(mat4_transform_point3(mat4_translation([5, -2, 3]), [1, 1, 1]), [6, -1, 4]);
+///
+/// // Perspective projection with a 90° field of view, near = 1 and far = 3
+/// let m = [1.0, 0.0, 0.0, 0.0,
+///          0.0, 1.0, 0.0, 0.0,
+///          0.0, 0.0, -2.0, -3.0,
+///          0.0, 0.0, -1.0, 0.0];
+/// assert_eq!(mat4_transform_point3(m, [2.0, 1.0, -2.0]), [1.0, 0.5, 0.5]);
+/// assert_eq!(mat4_transform_point3(m, [0.0, 0.0, -3.0]), [0.0, 0.0, 1.0]);
+/// ```
+pub fn mat4_transform_point3<T>(m: Matrix4<T>, p: Vector3<T>) -> Vector3<T>
+    where T: Copy + PartialEq + Zero + One + Mul<Output=T> + Add<Output=T> + Div<Output=T>
+{
+    let v = mat4_transform_vec(m, [p[0], p[1], p[2], T::one()]);
+    if v[3] == T::one() {
+        [v[0], v[1], v[2]]
+    } else {
+        [v[0] / v[3], v[1] / v[3], v[2] / v[3]]
+    }
+}
+
+/// Transforms a 3D direction using a 4x4 matrix
+///
+/// The direction is extended with w = 0, so the translation doesn't apply, and the w
+/// component of the result is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_transform_direction3, mat4_translation, mat4_scaling, mat4_mul};
+///
+/// assert_eq!(mat4_transform_direction3(mat4_translation([5, -2, 3]), [1, 1, 1]), [1, 1, 1]);
+///
+/// let m = mat4_mul(mat4_translation([5, -2, 3]), mat4_scaling([2, 3, 4]));
+/// assert_eq!(mat4_transform_direction3(m, [1, 1, 1]), [2, 3, 4]);
+/// ```
+pub fn mat4_transform_direction3<T>(m: Matrix4<T>, d: Vector3<T>) -> Vector3<T>
+    where T: Copy + Zero + Mul<Output=T> + Add<Output=T>
+{
+    let v = mat4_transform_vec(m, [d[0], d[1], d[2], T::zero()]);
+    [v[0], v[1], v[2]]
+}
+
+/// Transforms a vector using a 4x4 matrix, debug-asserting the w component of the result
+///
+/// This is `mat4_transform_vec` for callers expecting a given w component, typically 1 for
+/// points and 0 for directions transformed by an affine matrix, for which w is preserved
+/// exactly. In debug builds, it panics if the resulting w component differs from
+/// `expected_w`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat4_transform_vec_checked;
+///
+/// let m = [1.0, 0.0, 0.0, 4.0,
+///          0.0, 1.0, 0.0, 5.0,
+///          0.0, 0.0, 1.0, 6.0,
+///          0.0, 0.0, 0.0, 1.0];
+///
+/// assert_eq!(mat4_transform_vec_checked(m, [1.0, 2.0, 3.0, 1.0], 1.0), [5.0, 7.0, 9.0, 1.0]);
+/// assert_eq!(mat4_transform_vec_checked(m, [1.0, 2.0, 3.0, 0.0], 0.0), [1.0, 2.0, 3.0, 0.0]);
+/// ```
+///
+/// Projection
+/// ```should_panic
+/// use stones::matrix::mat4_transform_vec_checked;
+///
+/// let m = [1.0, 0.0, 0.0, 0.0,
+///          0.0, 1.0, 0.0, 0.0,
+///          0.0, 0.0, -2.0, -3.0,
+///          0.0, 0.0, -1.0, 0.0];
+///
+/// mat4_transform_vec_checked(m, [2.0, 1.0, -2.0, 1.0], 1.0);
+/// ```
+pub fn mat4_transform_vec_checked<T>(lhs: Matrix4<T>, rhs: Vector4<T>, expected_w: T) -> Vector4<T>
+    where T: Copy + PartialEq + Mul<Output=T> + Add<Output=T>
+{
+    let v = mat4_transform_vec(lhs, rhs);
+    debug_assert!(v[3] == expected_w, "unexpected w component after transformation");
+    v
+}
+
+
+/// Builds a 3x3 UV transformation matrix
+///
+/// The UVs are scaled, then rotated counter-clockwise by `rotation` radians, both around
+/// `pivot`, and finally offset. With a pivot of [0, 0] this is the same
+/// translation * rotation * scale ordering as the glTF KHR_texture_transform extension,
+/// including its rotation matrix which accounts for the V axis pointing down.
+///
+/// # Examples
+///
+/// Identity parameters
+/// ```
+/// use stones::matrix::{mat3_uv_transform, mat3_identity};
+///
+/// let m = mat3_uv_transform([0.0, 0.0], [1.0, 1.0], 0.0, [0.5, 0.5]);
+/// assert_eq!(m, mat3_identity());
+/// ```
+///
+/// Offset
+/// ```
+/// use stones::matrix::{mat3_uv_transform, mat3_transform_uv};
+///
+/// let m = mat3_uv_transform([0.25, -0.5], [1.0, 1.0], 0.0, [0.0, 0.0]);
+/// assert_eq!(mat3_transform_uv(m, [0.5, 0.5]), [0.75, 0.0]);
+/// ```
+///
+/// Rotation around the center of the texture
+/// ```
+/// use stones::matrix::{mat3_uv_transform, mat3_transform_uv};
+///
+/// let m = mat3_uv_transform([0.0, 0.0], [1.0, 1.0], 1.2, [0.5, 0.5]);
+/// let center = mat3_transform_uv(m, [0.5, 0.5]);
+/// assert!((center[0] - 0.5f64).abs() < 1e-12);
+/// assert!((center[1] - 0.5f64).abs() < 1e-12);
+/// ```
+///
+/// KHR_texture_transform ordering
+/// ```
+/// use stones::matrix::{mat3_uv_transform, mat3_transform_uv, mat3_mul};
+///
+/// let (offset, rotation, scale) = ([0.5, 0.0], std::f64::consts::FRAC_PI_2, [2.0, 3.0]);
+/// let translation_matrix = [1.0, 0.0, offset[0],
+///                           0.0, 1.0, offset[1],
+///                           0.0, 0.0, 1.0];
+/// let rotation_matrix = [rotation.cos(), rotation.sin(), 0.0,
+///                        -rotation.sin(), rotation.cos(), 0.0,
+///                        0.0, 0.0, 1.0];
+/// let scale_matrix = [scale[0], 0.0, 0.0,
+///                     0.0, scale[1], 0.0,
+///                     0.0, 0.0, 1.0];
+/// let reference = mat3_mul(mat3_mul(translation_matrix, rotation_matrix), scale_matrix);
+///
+/// let m = mat3_uv_transform(offset, scale, rotation, [0.0, 0.0]);
+/// assert_eq!(m, reference);
+///
+/// let uv = mat3_transform_uv(m, [1.0, 0.0]);
+/// assert!((uv[0] - 0.5).abs() < 1e-12);
+/// assert!((uv[1] + 2.0).abs() < 1e-12);
+/// ```
+pub fn mat3_uv_transform<T>(offset: Vector2<T>, scale: Vector2<T>, rotation: T, pivot: Vector2<T>) -> Matrix3<T>
+    where T: Float
+{
+    let (sin, cos) = (rotation.sin(), rotation.cos());
+    let (a, b) = (cos * scale[0], sin * scale[1]);
+    let (c, d) = (-sin * scale[0], cos * scale[1]);
+
+    [
+        a, b, offset[0] + pivot[0] - (a * pivot[0] + b * pivot[1]),
+        c, d, offset[1] + pivot[1] - (c * pivot[0] + d * pivot[1]),
+        T::zero(), T::zero(), T::one()
+    ]
+}
+
+/// Transforms UV coordinates using a 3x3 UV transformation matrix
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::mat3_transform_uv;
+///
+/// let m = [2, 0, 1,
+///          0, 3, -1,
+///          0, 0, 1];
+///
+/// assert_eq!(mat3_transform_uv(m, [4, 5]), [9, 14]);
+/// ```
+pub fn mat3_transform_uv<T>(m: Matrix3<T>, uv: Vector2<T>) -> Vector2<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        m[0] * uv[0] + m[1] * uv[1] + m[2],
+        m[3] * uv[0] + m[4] * uv[1] + m[5]
+    ]
+}
+
+/// Computes the eigenvalues and eigenvectors of a symmetric 2x2 matrix
+///
+/// The eigenvalues are sorted in descending order and the matching unit eigenvectors are
+/// the columns of the returned matrix, which is a rotation. They are computed in closed form,
+/// only the upper triangle of the matrix is read.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_eigen_symmetric, mat2_mul, mat2_transpose};
+///
+/// // Diagonal
+/// assert_eq!(mat2_eigen_symmetric([5.0, 0.0, 0.0, 3.0]), ([5.0, 3.0], [1.0, 0.0, 0.0, 1.0]));
+/// let (values, vectors) = mat2_eigen_symmetric([3.0f64, 0.0, 0.0, 5.0]);
+/// assert_eq!(values, [5.0, 3.0]);
+/// let expected = [0.0, -1.0, 1.0, 0.0];
+/// for i in 0..4 {
+///     assert!((vectors[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // Rotated diagonal matrix
+/// let (sin, cos) = 0.4f64.sin_cos();
+/// let r = [cos, -sin, sin, cos];
+/// let m = mat2_mul(mat2_mul(r, [4.0, 0.0, 0.0, 1.0]), mat2_transpose(r));
+/// let (values, vectors) = mat2_eigen_symmetric(m);
+/// assert!((values[0] - 4.0).abs() < 1e-12 && (values[1] - 1.0).abs() < 1e-12);
+/// for i in 0..4 {
+///     assert!((vectors[i] - r[i]).abs() < 1e-12);
+/// }
+///
+/// // Repeated eigenvalues
+/// assert_eq!(mat2_eigen_symmetric([2.0, 0.0, 0.0, 2.0]), ([2.0, 2.0], [1.0, 0.0, 0.0, 1.0]));
+/// ```
+pub fn mat2_eigen_symmetric<T>(m: Matrix2<T>) -> (Vector2<T>, Matrix2<T>)
+    where T: Float
+{
+    let two = T::one() + T::one();
+    let mean = (m[0] + m[3]) / two;
+    let half_difference = (m[0] - m[3]) / two;
+    let radius = (half_difference * half_difference + m[1] * m[1]).sqrt();
+
+    let angle = (m[1] + m[1]).atan2(m[0] - m[3]) / two;
+    let (sin, cos) = (angle.sin(), angle.cos());
+
+    ([mean + radius, mean - radius], [cos, -sin, sin, cos])
+}
+
+/// Computes the eigenvalues and eigenvectors of a symmetric 3x3 matrix
+///
+/// The eigenvalues are sorted in descending order and the matching unit eigenvectors are
+/// the columns of the returned matrix. They are computed using cyclic Jacobi rotations,
+/// only the upper triangle of the matrix is read.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_eigen_symmetric, mat3_mul};
+///
+/// let m = [2.0, 1.0, 0.0,
+///          1.0, 2.0, 0.0,
+///          0.0, 0.0, 5.0];
+/// let (values, vectors) = mat3_eigen_symmetric(m);
+/// assert!((values[0] - 5.0f64).abs() < 1e-12);
+/// assert!((values[1] - 3.0f64).abs() < 1e-12);
+/// assert!((values[2] - 1.0f64).abs() < 1e-12);
+///
+/// // m * v = lambda * v for each column v
+/// let mv = mat3_mul(m, vectors);
+/// for column in 0..3 {
+///     for row in 0..3 {
+///         let expected = values[column] * vectors[row * 3 + column];
+///         assert!((mv[row * 3 + column] - expected).abs() < 1e-12);
+///     }
+/// }
+/// ```
+///
+/// Principal axes
+/// ```
+/// use stones::matrix::{mat3_eigen_symmetric, mat3_mul, mat3_transpose, mat4_rotation_axis, mat4_to_mat3};
+///
+/// // Diagonal
+/// let (values, _) = mat3_eigen_symmetric([2.0, 0.0, 0.0,
+///                                         0.0, 7.0, 0.0,
+///                                         0.0, 0.0, -1.0]);
+/// assert_eq!(values, [7.0, 2.0, -1.0]);
+///
+/// // Rotated diagonal matrix, the eigenvectors are the rotated axes up to their sign
+/// let r = mat4_to_mat3(mat4_rotation_axis([1.0f64, 2.0, -0.5], 0.8));
+/// let m = mat3_mul(mat3_mul(r, [3.0, 0.0, 0.0,
+///                               0.0, 2.0, 0.0,
+///                               0.0, 0.0, 1.0]), mat3_transpose(r));
+/// let (values, vectors) = mat3_eigen_symmetric(m);
+/// for column in 0..3 {
+///     assert!((values[column] - (3 - column) as f64).abs() < 1e-12);
+///     let dot = (0..3).map(|row| vectors[row * 3 + column] * r[row * 3 + column]).sum::<f64>();
+///     assert!((dot.abs() - 1.0).abs() < 1e-12);
+/// }
+///
+/// // Repeated eigenvalues
+/// let m = mat3_mul(mat3_mul(r, [2.0, 0.0, 0.0,
+///                               0.0, 2.0, 0.0,
+///                               0.0, 0.0, 1.0]), mat3_transpose(r));
+/// let (values, vectors) = mat3_eigen_symmetric(m);
+/// assert!(values.iter().chain(vectors.iter()).all(|x| x.is_finite()));
+/// assert!((values[0] - 2.0).abs() < 1e-12 && (values[1] - 2.0).abs() < 1e-12);
+/// ```
+pub fn mat3_eigen_symmetric<T>(m: Matrix3<T>) -> (Vector3<T>, Matrix3<T>)
+    where T: Float
+{
+    const MAX_SWEEPS: usize = 32;
+
+    let mut a = [
+        m[0], m[1], m[2],
+        m[1], m[4], m[5],
+        m[2], m[5], m[8]
+    ];
+    let mut v = mat3_identity::<T>();
+
+    let norm = a.iter().fold(T::zero(), |acc, &x| acc + x * x);
+    let tolerance = T::epsilon() * T::epsilon() * norm;
+    for _ in 0..MAX_SWEEPS {
+        let off_diagonal = a[1] * a[1] + a[2] * a[2] + a[5] * a[5];
+        if off_diagonal <= tolerance {
+            break;
+        }
+
+        for &(p, q) in &[(0, 1), (0, 2), (1, 2)] {
+            let apq = a[p * 3 + q];
+            if apq == T::zero() {
+                continue;
+            }
+
+            let theta = (a[q * 3 + q] - a[p * 3 + p]) / (apq + apq);
+            let t = T::one() / (theta.abs() + (theta * theta + T::one()).sqrt());
+            let t = if theta < T::zero() { -t } else { t };
+            let c = T::one() / (t * t + T::one()).sqrt();
+            let s = t * c;
+
+            for k in 0..3 {
+                let (akp, akq) = (a[k * 3 + p], a[k * 3 + q]);
+                a[k * 3 + p] = c * akp - s * akq;
+                a[k * 3 + q] = s * akp + c * akq;
+            }
+            for k in 0..3 {
+                let (apk, aqk) = (a[p * 3 + k], a[q * 3 + k]);
+                a[p * 3 + k] = c * apk - s * aqk;
+                a[q * 3 + k] = s * apk + c * aqk;
+            }
+            for k in 0..3 {
+                let (vkp, vkq) = (v[k * 3 + p], v[k * 3 + q]);
+                v[k * 3 + p] = c * vkp - s * vkq;
+                v[k * 3 + q] = s * vkp + c * vkq;
+            }
+        }
+    }
+
+    let mut order = [0, 1, 2];
+    order.sort_by(|&i, &j| a[j * 4].partial_cmp(&a[i * 4]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let values = [a[order[0] * 4], a[order[1] * 4], a[order[2] * 4]];
+    let mut vectors = v;
+    for (column, &source) in order.iter().enumerate() {
+        for row in 0..3 {
+            vectors[row * 3 + column] = v[row * 3 + source];
+        }
+    }
+
+    (values, vectors)
+}
+
+/// Computes the reprojection matrices between two view-projection matrices
+///
+/// Returns the matrix transforming clip space coordinates of the current frame to the clip
+/// space coordinates of the previous frame, e.g. to compute motion vectors, along with its
+/// inverse. Returns None if either view-projection matrix isn't invertible.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_velocity_matrices, mat4_mul, mat4_transform_vec};
+///
+/// let projection = [1.0, 0.0, 0.0, 0.0,
+///                   0.0, 1.0, 0.0, 0.0,
+///                   0.0, 0.0, -2.0, -3.0,
+///                   0.0, 0.0, -1.0, 0.0];
+/// let view = |x: f64| [1.0, 0.0, 0.0, -x,
+///                 0.0, 1.0, 0.0, 0.0,
+///                 0.0, 0.0, 1.0, 0.0,
+///                 0.0, 0.0, 0.0, 1.0];
+/// let prev_vp = mat4_mul(projection, view(0.0));
+/// let curr_vp = mat4_mul(projection, view(0.5));
+///
+/// let (to_prev, to_curr) = mat4_velocity_matrices(prev_vp, curr_vp).unwrap();
+///
+/// let world = [1.0, 2.0, -2.0, 1.0];
+/// let prev_clip = mat4_transform_vec(prev_vp, world);
+/// let curr_clip = mat4_transform_vec(curr_vp, world);
+/// let reprojected_prev = mat4_transform_vec(to_prev, curr_clip);
+/// let reprojected_curr = mat4_transform_vec(to_curr, prev_clip);
+/// for i in 0..4 {
+///     assert!((reprojected_prev[i] - prev_clip[i]).abs() < 1e-12);
+///     assert!((reprojected_curr[i] - curr_clip[i]).abs() < 1e-12);
+/// }
+///
+/// assert_eq!(mat4_velocity_matrices(prev_vp, [0.0; 16]), None);
+/// ```
+pub fn mat4_velocity_matrices<T>(prev_vp: Matrix4<T>, curr_vp: Matrix4<T>) -> Option<(Matrix4<T>, Matrix4<T>)>
+    where T: Float
+{
+    let prev_inverse = mat4_inverse(prev_vp)?;
+    let curr_inverse = mat4_inverse(curr_vp)?;
+    Some((mat4_mul(prev_vp, curr_inverse), mat4_mul(curr_vp, prev_inverse)))
+}
+
+/// Computes the Frobenius norm of a 2x2 matrix, the square root of the sum of its squared
+/// components
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_frobenius_norm, mat2_identity};
+///
+/// assert_eq!(mat2_frobenius_norm(mat2_identity::<f64>()), 2f64.sqrt());
+/// assert_eq!(mat2_frobenius_norm([1.0, -2.0,
+///                                 2.0, 4.0]), 5.0);
+/// ```
+pub fn mat2_frobenius_norm<T>(m: Matrix2<T>) -> T
+    where T: Float
+{
+    frobenius_norm(&m)
+}
+
+/// Computes the Frobenius norm of a 3x3 matrix, the square root of the sum of its squared
+/// components
+///
+/// The norm is invariant under rotations, the norm of a rotation matrix being √3.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_frobenius_norm, mat3_identity, mat4_to_mat3, mat4_rotation_axis};
+///
+/// assert_eq!(mat3_frobenius_norm(mat3_identity::<f64>()), 3f64.sqrt());
+/// let rotation = mat4_to_mat3(mat4_rotation_axis([1.0f64, 2.0, 3.0], 0.7));
+/// assert!((mat3_frobenius_norm(rotation) - 3f64.sqrt()).abs() < 1e-12);
+/// assert_eq!(mat3_frobenius_norm([0.0f32; 9]), 0.0);
+/// ```
+pub fn mat3_frobenius_norm<T>(m: Matrix3<T>) -> T
+    where T: Float
+{
+    frobenius_norm(&m)
+}
+
+/// Computes the Frobenius norm of a 4x4 matrix, the square root of the sum of its squared
+/// components
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_frobenius_norm, mat4_identity, mat4_scale};
+///
+/// assert_eq!(mat4_frobenius_norm(mat4_identity::<f32>()), 2.0);
+/// assert_eq!(mat4_frobenius_norm(mat4_scale(mat4_identity::<f32>(), -3.0)), 6.0);
+/// ```
+pub fn mat4_frobenius_norm<T>(m: Matrix4<T>) -> T
+    where T: Float
+{
+    frobenius_norm(&m)
+}
+
+/// Estimates the condition number of a 3x3 matrix
+///
+/// The estimate is the 1-norm condition number norm(m) * norm(inverse(m)), it grows as the
+/// matrix gets closer to being singular. Returns None if the matrix is singular.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_condition_estimate, mat3_identity};
+///
+/// assert_eq!(mat3_condition_estimate(mat3_identity::<f64>()), Some(1.0));
+///
+/// let diagonal = [2.0, 0.0, 0.0,
+///                 0.0, -4.0, 0.0,
+///                 0.0, 0.0, 8.0];
+/// assert_eq!(mat3_condition_estimate(diagonal), Some(4.0));
+///
+/// let nearly_singular = [1.0, 2.0, 3.0,
+///                        4.0, 5.0, 6.0,
+///                        7.0, 8.0, 9.000001];
+/// assert!(mat3_condition_estimate(nearly_singular).unwrap() > 1e6);
+///
+/// let singular = [1.0, 2.0, 3.0,
+///                 4.0, 5.0, 6.0,
+///                 7.0, 8.0, 9.0];
+/// assert_eq!(mat3_condition_estimate(singular), None);
+/// ```
+pub fn mat3_condition_estimate<T>(m: Matrix3<T>) -> Option<T>
+    where T: Float
+{
+    let inverse = mat3_inverse(m)?;
+    Some(one_norm(&m, 3) * one_norm(&inverse, 3))
+}
+
+/// Estimates the condition number of a 4x4 matrix
+///
+/// The estimate is the 1-norm condition number norm(m) * norm(inverse(m)), it grows as the
+/// matrix gets closer to being singular. Returns None if the matrix is singular.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_condition_estimate, mat4_identity, mat4_from_trs};
+///
+/// assert_eq!(mat4_condition_estimate(mat4_identity::<f64>()), Some(1.0));
+///
+/// let diagonal = [0.5, 0.0, 0.0, 0.0,
+///                 0.0, 2.0, 0.0, 0.0,
+///                 0.0, 0.0, 4.0, 0.0,
+///                 0.0, 0.0, 0.0, 1.0];
+/// assert_eq!(mat4_condition_estimate(diagonal), Some(8.0));
+///
+/// let nearly_singular = [1.0, 2.0, 3.0, 4.0,
+///                        2.0, 4.0, 6.0, 8.000001,
+///                        0.0, 1.0, 0.0, 0.0,
+///                        0.0, 0.0, 1.0, 0.0];
+/// assert!(mat4_condition_estimate(nearly_singular).unwrap() > 1e6);
+///
+/// let singular = [1.0, 2.0, 3.0, 4.0,
+///                 2.0, 4.0, 6.0, 8.0,
+///                 0.0, 1.0, 0.0, 0.0,
+///                 0.0, 0.0, 1.0, 0.0];
+/// assert_eq!(mat4_condition_estimate(singular), None);
+///
+/// // Translated rigid transform, the estimate grows with the translation but stays finite
+/// let rigid = mat4_from_trs([500.0f32, 20.0, -300.0], ([0.0, 1.0, 0.0], 0.3), [1.0; 3]);
+/// let estimate = mat4_condition_estimate(rigid).unwrap();
+/// assert!(estimate > 1.0 && estimate < 1e6);
+/// ```
+pub fn mat4_condition_estimate<T>(m: Matrix4<T>) -> Option<T>
+    where T: Float
+{
+    let inverse = mat4_inverse(m)?;
+    Some(one_norm(&m, 4) * one_norm(&inverse, 4))
+}
+
+/// Checks whether the condition number estimate of a 4x4 matrix is below a threshold
+///
+/// Singular matrices are never well-conditioned.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_is_well_conditioned, mat4_identity, mat4_from_trs};
+///
+/// assert!(mat4_is_well_conditioned(mat4_identity::<f64>(), 10.0));
+///
+/// let nearly_singular = [1.0, 2.0, 3.0, 4.0,
+///                        2.0, 4.0, 6.0, 8.000001,
+///                        0.0, 1.0, 0.0, 0.0,
+///                        0.0, 0.0, 1.0, 0.0];
+/// assert!(!mat4_is_well_conditioned(nearly_singular, 1e6));
+/// assert!(!mat4_is_well_conditioned([0.0; 16], 1e6));
+///
+/// let rigid = mat4_from_trs([500.0f32, 20.0, -300.0], ([0.0, 1.0, 0.0], 0.3), [1.0; 3]);
+/// assert!(mat4_is_well_conditioned(rigid, 1e6));
+/// ```
+pub fn mat4_is_well_conditioned<T>(m: Matrix4<T>, threshold: T) -> bool
+    where T: Float
+{
+    match mat4_condition_estimate(m) {
+        Some(condition) => condition <= threshold,
+        None => false
+    }
+}
+
+/// Computes the inverse of a 2x2 matrix
+///
+/// Returns None if the matrix is singular or nearly singular, with the same criterion as
+/// `mat3_lu`: Gaussian elimination with partial pivoting finds a pivot smaller than the
+/// largest component of the matrix times the machine epsilon times the size of the matrix.
+/// The criterion doesn't depend on the scale of the matrix, and a translation only matters
+/// once it's about 1 / epsilon times larger than the other components. `mat3_inverse` and
+/// `mat4_inverse` use the same criterion.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_inverse, mat2_mul, mat2_identity};
+///
+/// let m: [f32; 4] = [4.0, 7.0,
+///                    2.0, 6.0];
+/// let inverse = mat2_inverse(m).unwrap();
+/// let product = mat2_mul(m, inverse);
+/// let identity = mat2_identity::<f32>();
+/// for i in 0..4 {
+///     assert!((product[i] - identity[i]).abs() < 1e-6);
+/// }
+///
+/// let m: [f64; 4] = [2.0, 0.0,
+///                    0.0, 0.5];
+/// assert_eq!(mat2_inverse(m), Some([0.5, 0.0,
+///                                   0.0, 2.0]));
+///
+/// // Singular
+/// assert_eq!(mat2_inverse([1.0, 2.0,
+///                          2.0, 4.0]), None);
+///
+/// // Nearly singular
+/// assert_eq!(mat2_inverse([1.0, 2.0,
+///                          1.0, 2.0 + 1e-15]), None);
+/// assert_eq!(mat2_inverse([1e-20, 0.0,
+///                          0.0, 1.0]), None);
+/// assert_eq!(mat2_inverse([1e-20, 0.0,
+///                          0.0, 1e-20]), Some([1e20, 0.0,
+///                                              0.0, 1e20]));
+/// ```
+pub fn mat2_inverse<T>(m: Matrix2<T>) -> Option<Matrix2<T>>
+    where T: Float
+{
+    let determinant = mat2_determinant(m);
+    if is_nearly_singular(&m, 2) {
+        return None;
+    }
+
+    let inverse_determinant = T::one() / determinant;
+    Some(mat2_scale([
+        m[3], -m[1],
+        -m[2], m[0]
+    ], inverse_determinant))
+}
+
+/// Computes the inverse of a 3x3 matrix
+///
+/// The inverse is computed as the adjugate divided by the determinant. Returns None if the
+/// matrix is singular or nearly singular, with the same criterion as `mat2_inverse`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_inverse, mat3_mul, mat3_identity};
+///
+/// let m: [f64; 9] = [2.0, 0.0, 1.0,
+///                    1.0, 3.0, 2.0,
+///                    1.0, 1.0, -1.0];
+/// let product = mat3_mul(m, mat3_inverse(m).unwrap());
+/// let identity = mat3_identity::<f64>();
+/// for i in 0..9 {
+///     assert!((product[i] - identity[i]).abs() < 1e-12);
+/// }
+///
+/// // Rotation and scale
+/// let (sin, cos) = 0.3f32.sin_cos();
+/// let m = [2.0 * cos, -sin, 0.0,
+///          2.0 * sin, cos, 0.0,
+///          0.0, 0.0, 0.5];
+/// let product = mat3_mul(m, mat3_inverse(m).unwrap());
+/// let identity = mat3_identity::<f32>();
+/// for i in 0..9 {
+///     assert!((product[i] - identity[i]).abs() < 1e-6);
+/// }
+///
+/// // Two identical rows
+/// assert_eq!(mat3_inverse([1.0, 2.0, 3.0,
+///                          1.0, 2.0, 3.0,
+///                          4.0, 5.0, 6.0]), None);
+///
+/// // Nearly singular
+/// assert_eq!(mat3_inverse([1.0, 2.0, 3.0,
+///                          4.0, 5.0, 6.0,
+///                          7.0, 8.0, 9.0 + 1e-15]), None);
+///
+/// // Small but well-conditioned
+/// assert!(mat3_inverse([1e-6, 0.0, 0.0,
+///                       0.0, 1e-6, 0.0,
+///                       0.0, 0.0, 1.0]).is_some());
+/// ```
+pub fn mat3_inverse<T>(m: Matrix3<T>) -> Option<Matrix3<T>>
+    where T: Float
+{
+    let c0 = m[4] * m[8] - m[5] * m[7];
+    let c1 = m[5] * m[6] - m[3] * m[8];
+    let c2 = m[3] * m[7] - m[4] * m[6];
+
+    let determinant = m[0] * c0 + m[1] * c1 + m[2] * c2;
+    if is_nearly_singular(&m, 3) {
+        return None;
+    }
+
+    let inverse_determinant = T::one() / determinant;
+    Some(mat3_scale([
+        c0, m[2] * m[7] - m[1] * m[8], m[1] * m[5] - m[2] * m[4],
+        c1, m[0] * m[8] - m[2] * m[6], m[2] * m[3] - m[0] * m[5],
+        c2, m[1] * m[6] - m[0] * m[7], m[0] * m[4] - m[1] * m[3]
+    ], inverse_determinant))
+}
+
+/// Computes the inverse of a 4x4 matrix
+///
+/// The inverse is computed using cofactors. Returns None if the matrix is singular or nearly
+/// singular, with the same criterion as `mat2_inverse`.
+///
+/// # Examples
+///
+/// Translation, rotation and scale
+/// ```
+/// use stones::matrix::{mat4_inverse, mat4_mul, mat4_identity, mat4_transform_vec};
+///
+/// let (sin, cos) = 0.5f32.sin_cos();
+/// let translation = [1.0, 0.0, 0.0, 3.0,
+///                    0.0, 1.0, 0.0, -2.0,
+///                    0.0, 0.0, 1.0, 5.0,
+///                    0.0, 0.0, 0.0, 1.0];
+/// let rotation = [cos, 0.0, sin, 0.0,
+///                 0.0, 1.0, 0.0, 0.0,
+///                 -sin, 0.0, cos, 0.0,
+///                 0.0, 0.0, 0.0, 1.0];
+/// let scale = [2.0, 0.0, 0.0, 0.0,
+///              0.0, 0.5, 0.0, 0.0,
+///              0.0, 0.0, 3.0, 0.0,
+///              0.0, 0.0, 0.0, 1.0];
+/// let m = mat4_mul(translation, mat4_mul(rotation, scale));
+/// let inverse = mat4_inverse(m).unwrap();
+///
+/// let product = mat4_mul(m, inverse);
+/// let identity = mat4_identity::<f32>();
+/// for i in 0..16 {
+///     assert!((product[i] - identity[i]).abs() < 1e-6);
+/// }
+///
+/// let v = [1.0, -4.0, 2.5, 1.0];
+/// let round_trip = mat4_transform_vec(inverse, mat4_transform_vec(m, v));
+/// for i in 0..4 {
+///     assert!((round_trip[i] - v[i]).abs() < 1e-5);
+/// }
+/// ```
+///
+/// Perspective projection
+/// ```
+/// use stones::matrix::{mat4_inverse, mat4_mul, mat4_identity};
+///
+/// // 90° field of view, near = 1 and far = 3
+/// let m: [f64; 16] = [1.0, 0.0, 0.0, 0.0,
+///                     0.0, 1.0, 0.0, 0.0,
+///                     0.0, 0.0, -2.0, -3.0,
+///                     0.0, 0.0, -1.0, 0.0];
+/// let inverse = mat4_inverse(m).unwrap();
+/// assert_eq!(inverse, [1.0, 0.0, 0.0, 0.0,
+///                      0.0, 1.0, 0.0, 0.0,
+///                      0.0, 0.0, 0.0, -1.0,
+///                      0.0, 0.0, -1.0 / 3.0, 2.0 / 3.0]);
+/// assert_eq!(mat4_mul(m, inverse), mat4_identity());
+///
+/// // Singular
+/// assert_eq!(mat4_inverse([1.0, 2.0, 3.0, 4.0,
+///                          5.0, 6.0, 7.0, 8.0,
+///                          5.0, 6.0, 7.0, 8.0,
+///                          2.0, 0.0, 1.0, 3.0]), None);
+///
+/// // Nearly singular
+/// assert_eq!(mat4_inverse([1.0, 2.0, 3.0, 4.0,
+///                          5.0, 6.0, 7.0, 8.0,
+///                          5.0, 6.0, 7.0, 8.0 + 1e-15,
+///                          2.0, 0.0, 1.0, 3.0]), None);
+///
+/// // Uniform scaling by a small factor
+/// assert!(mat4_inverse([1e-6, 0.0, 0.0, 0.0,
+///                       0.0, 1e-6, 0.0, 0.0,
+///                       0.0, 0.0, 1e-6, 0.0,
+///                       0.0, 0.0, 0.0, 1.0]).is_some());
+/// ```
+///
+/// Large translations
+/// ```
+/// use stones::matrix::{mat4_inverse, mat4_translation, mat4_from_trs, mat4_transform_vec};
+///
+/// assert_eq!(mat4_inverse(mat4_translation([200.0f32, 200.0, 200.0])),
+///            Some(mat4_translation([-200.0, -200.0, -200.0])));
+/// assert_eq!(mat4_inverse(mat4_translation([1e6f64, -1e6, 1e6])),
+///            Some(mat4_translation([-1e6, 1e6, -1e6])));
+/// assert!(mat4_inverse(mat4_from_trs([500.0f32, 20.0, -300.0], ([0.0, 1.0, 0.0], 0.3), [1.0; 3])).is_some());
+///
+/// // Model matrix placed thousands of units away from the origin
+/// let m = mat4_from_trs([4000.0f32, -2500.0, 7000.0], ([1.0, 0.0, 0.0], 1.2), [2.0, 0.5, 3.0]);
+/// let inverse = mat4_inverse(m).unwrap();
+/// let v = [4001.0, -2503.0, 6998.5, 1.0];
+/// let round_trip = mat4_transform_vec(m, mat4_transform_vec(inverse, v));
+/// for i in 0..4 {
+///     assert!((round_trip[i] - v[i]).abs() < 1e-2);
+/// }
+/// ```
+pub fn mat4_inverse<T>(m: Matrix4<T>) -> Option<Matrix4<T>>
+    where T: Float
+{
+    let s0 = m[0] * m[5] - m[4] * m[1];
+    let s1 = m[0] * m[6] - m[4] * m[2];
+    let s2 = m[0] * m[7] - m[4] * m[3];
+    let s3 = m[1] * m[6] - m[5] * m[2];
+    let s4 = m[1] * m[7] - m[5] * m[3];
+    let s5 = m[2] * m[7] - m[6] * m[3];
+
+    let c5 = m[10] * m[15] - m[14] * m[11];
+    let c4 = m[9] * m[15] - m[13] * m[11];
+    let c3 = m[9] * m[14] - m[13] * m[10];
+    let c2 = m[8] * m[15] - m[12] * m[11];
+    let c1 = m[8] * m[14] - m[12] * m[10];
+    let c0 = m[8] * m[13] - m[12] * m[9];
+
+    let determinant = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+    if is_nearly_singular(&m, 4) {
+        return None;
+    }
+
+    let inverse_determinant = T::one() / determinant;
+    Some(mat4_scale([
+        m[5] * c5 - m[6] * c4 + m[7] * c3,
+        -m[1] * c5 + m[2] * c4 - m[3] * c3,
+        m[13] * s5 - m[14] * s4 + m[15] * s3,
+        -m[9] * s5 + m[10] * s4 - m[11] * s3,
+
+        -m[4] * c5 + m[6] * c2 - m[7] * c1,
+        m[0] * c5 - m[2] * c2 + m[3] * c1,
+        -m[12] * s5 + m[14] * s2 - m[15] * s1,
+        m[8] * s5 - m[10] * s2 + m[11] * s1,
+
+        m[4] * c4 - m[5] * c2 + m[7] * c0,
+        -m[0] * c4 + m[1] * c2 - m[3] * c0,
+        m[12] * s4 - m[13] * s2 + m[15] * s0,
+        -m[8] * s4 + m[9] * s2 - m[11] * s0,
+
+        -m[4] * c3 + m[5] * c1 - m[6] * c0,
+        m[0] * c3 - m[1] * c1 + m[2] * c0,
+        -m[12] * s3 + m[13] * s1 - m[14] * s0,
+        m[8] * s3 - m[9] * s1 + m[10] * s0
+    ], inverse_determinant))
+}
+
+/// Computes the inverse of an affine 4x4 matrix
+///
+/// Only inverts the upper-left 3x3 block and recomputes the translation, which is faster than
+/// `mat4_inverse`. Returns None if the last row isn't [0, 0, 0, 1] or if the matrix is
+/// singular.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_inverse_affine, mat4_inverse, mat4_mul};
+///
+/// let (sin, cos) = 1.2f32.sin_cos();
+/// let translation = [1.0, 0.0, 0.0, -4.0,
+///                    0.0, 1.0, 0.0, 2.5,
+///                    0.0, 0.0, 1.0, 7.0,
+///                    0.0, 0.0, 0.0, 1.0];
+/// let rotation = [cos, -sin, 0.0, 0.0,
+///                 sin, cos, 0.0, 0.0,
+///                 0.0, 0.0, 1.0, 0.0,
+///                 0.0, 0.0, 0.0, 1.0];
+/// let scale = [0.5, 0.0, 0.0, 0.0,
+///              0.0, 2.0, 0.0, 0.0,
+///              0.0, 0.0, 1.5, 0.0,
+///              0.0, 0.0, 0.0, 1.0];
+/// let m = mat4_mul(translation, mat4_mul(rotation, scale));
+///
+/// let affine = mat4_inverse_affine(m).unwrap();
+/// let general = mat4_inverse(m).unwrap();
+/// for i in 0..16 {
+///     assert!((affine[i] - general[i]).abs() < 1e-6);
+/// }
+/// assert_eq!(affine[12..16], [0.0, 0.0, 0.0, 1.0]);
+///
+/// // Not affine
+/// let projection = [1.0, 0.0, 0.0, 0.0,
+///                   0.0, 1.0, 0.0, 0.0,
+///                   0.0, 0.0, -2.0, -3.0,
+///                   0.0, 0.0, -1.0, 0.0];
+/// assert_eq!(mat4_inverse_affine(projection), None);
+///
+/// // Singular
+/// let flatten = [1.0, 0.0, 0.0, 0.0,
+///                0.0, 1.0, 0.0, 0.0,
+///                0.0, 0.0, 0.0, 0.0,
+///                0.0, 0.0, 0.0, 1.0];
+/// assert_eq!(mat4_inverse_affine(mat4_mul(translation, flatten)), None);
+/// ```
+pub fn mat4_inverse_affine<T>(m: Matrix4<T>) -> Option<Matrix4<T>>
+    where T: Float
+{
+    if m[12] != T::zero() || m[13] != T::zero() || m[14] != T::zero() || m[15] != T::one() {
+        return None;
+    }
+
+    let inverse = mat3_inverse(mat4_to_mat3(m))?;
+    let translation = mat3_transform_vec(inverse, [-m[3], -m[7], -m[11]]);
+
+    Some([
+        inverse[0], inverse[1], inverse[2], translation[0],
+        inverse[3], inverse[4], inverse[5], translation[1],
+        inverse[6], inverse[7], inverse[8], translation[2],
+        T::zero(), T::zero(), T::zero(), T::one()
+    ])
+}
+
+/// Returns the matrix converting coordinates expressed in the basis `from` to coordinates
+/// expressed in the basis `to`
+///
+/// Both bases are given by their basis vectors in world space, stored as the columns of the
+/// matrices. The result is to⁻¹ * from. Returns None if one of the bases is degenerate or
+/// nearly degenerate, with the same criterion as `mat3_inverse`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_change_of_basis, mat3_identity, mat3_transform_vec, mat3_approx_eq};
+///
+/// // A frame rotated by a quarter turn around Z
+/// let frame = [0.0, -1.0, 0.0,
+///              1.0, 0.0, 0.0,
+///              0.0, 0.0, 1.0];
+/// let world = mat3_identity();
+///
+/// let to_world = mat3_change_of_basis(frame, world).unwrap();
+/// assert_eq!(mat3_transform_vec(to_world, [1.0, 2.0, 3.0]), [-2.0, 1.0, 3.0]);
+/// let to_frame = mat3_change_of_basis(world, frame).unwrap();
+/// assert_eq!(mat3_transform_vec(to_frame, [-2.0, 1.0, 3.0]), [1.0, 2.0, 3.0]);
+///
+/// let skewed = [2.0f64, 1.0, 0.0,
+///               0.0, 1.0, 3.0,
+///               1.0, 0.0, 1.0];
+/// assert!(mat3_approx_eq(mat3_change_of_basis(skewed, skewed).unwrap(), mat3_identity(), 1e-12));
+///
+/// // Degenerate basis
+/// let flat = [1.0, 0.0, 1.0,
+///             0.0, 1.0, 1.0,
+///             0.0, 0.0, 0.0];
+/// assert_eq!(mat3_change_of_basis(flat, world), None);
+/// assert_eq!(mat3_change_of_basis(world, flat), None);
+///
+/// // Nearly degenerate basis
+/// let nearly_flat = [1.0, 2.0, 3.0,
+///                    4.0, 5.0, 6.0,
+///                    7.0, 8.0, 9.0 + 1e-15];
+/// assert_eq!(mat3_change_of_basis(nearly_flat, world), None);
+/// assert_eq!(mat3_change_of_basis(world, nearly_flat), None);
+/// ```
+pub fn mat3_change_of_basis<T>(from: Matrix3<T>, to: Matrix3<T>) -> Option<Matrix3<T>>
+    where T: Float
+{
+    if is_nearly_singular(&from, 3) {
+        return None;
+    }
+
+    Some(mat3_mul(mat3_inverse(to)?, from))
+}
+
+/// Returns the matrix converting coordinates expressed in the frame `from` to coordinates
+/// expressed in the frame `to`
+///
+/// Both frames are given as 4x4 transforms from the frame to world space, i.e. with their
+/// basis vectors in the first three columns and their origin in the last one. The result is
+/// to⁻¹ * from. Returns None if one of the frames is degenerate or nearly degenerate, with
+/// the same criterion as `mat4_inverse`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_change_of_basis, mat4_from_trs, mat4_identity, mat4_transform_point3, mat4_approx_eq};
+///
+/// let frame = mat4_from_trs([10.0f64, 0.0, -2.0], ([0.0, 0.0, 1.0], std::f64::consts::FRAC_PI_2), [1.0, 1.0, 1.0]);
+/// let world = mat4_identity();
+///
+/// let to_world = mat4_change_of_basis(frame, world).unwrap();
+/// let point = mat4_transform_point3(to_world, [1.0, 2.0, 3.0]);
+/// let expected = [8.0, 1.0, 1.0];
+/// for i in 0..3 {
+///     assert!((point[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// let to_frame = mat4_change_of_basis(world, frame).unwrap();
+/// let back = mat4_transform_point3(to_frame, point);
+/// let expected = [1.0, 2.0, 3.0];
+/// for i in 0..3 {
+///     assert!((back[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // Between two arbitrary frames, through world space
+/// let other = mat4_from_trs([-1.0, 4.0, 0.5], ([1.0, 1.0, 0.0], 0.3), [2.0, 0.5, 1.0]);
+/// let direct = mat4_transform_point3(mat4_change_of_basis(other, frame).unwrap(), [1.0, 2.0, 3.0]);
+/// let through_world = mat4_transform_point3(to_frame, mat4_transform_point3(other, [1.0, 2.0, 3.0]));
+/// for i in 0..3 {
+///     assert!((direct[i] - through_world[i]).abs() < 1e-12);
+/// }
+/// assert!(mat4_approx_eq(mat4_change_of_basis(other, other).unwrap(), mat4_identity(), 1e-12));
+///
+/// // Degenerate frame
+/// let flat = mat4_from_trs([1.0, 2.0, 3.0], ([0.0, 1.0, 0.0], 0.3), [1.0, 0.0, 1.0]);
+/// assert_eq!(mat4_change_of_basis(flat, world), None);
+/// assert_eq!(mat4_change_of_basis(world, flat), None);
+///
+/// // Nearly degenerate frame
+/// let nearly_flat = mat4_from_trs([1.0, 2.0, 3.0], ([0.0, 1.0, 0.0], 0.3), [1.0, 1e-17, 1.0]);
+/// assert_eq!(mat4_change_of_basis(nearly_flat, world), None);
+/// assert_eq!(mat4_change_of_basis(world, nearly_flat), None);
+/// ```
+///
+/// Frame away from the origin
+/// ```
+/// use stones::matrix::{mat4_change_of_basis, mat4_from_trs, mat4_identity, mat4_transform_point3};
+///
+/// let frame = mat4_from_trs([500.0f32, -20.0, 300.0], ([0.0, 1.0, 0.0], 0.3), [1.0; 3]);
+/// let to_world = mat4_change_of_basis(frame, mat4_identity()).unwrap();
+/// let to_frame = mat4_change_of_basis(mat4_identity(), frame).unwrap();
+///
+/// let point = [1.0, 2.0, 3.0];
+/// let back = mat4_transform_point3(to_frame, mat4_transform_point3(to_world, point));
+/// for i in 0..3 {
+///     assert!((back[i] - point[i]).abs() < 1e-4);
+/// }
+/// ```
+pub fn mat4_change_of_basis<T>(from: Matrix4<T>, to: Matrix4<T>) -> Option<Matrix4<T>>
+    where T: Float
+{
+    if is_nearly_singular(&from, 4) {
+        return None;
+    }
+
+    Some(mat4_mul(mat4_inverse(to)?, from))
+}
+
+/// Computes the normal matrix of a 4x4 matrix, the inverse transpose of its upper-left 3x3
+/// block
+///
+/// Normals transformed by the normal matrix stay perpendicular to the surfaces transformed by
+/// the matrix, even with a non-uniform scale. Returns None if the 3x3 block is singular.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_normal_matrix, mat4_from_trs, mat4_rotation_axis, mat4_transform_vec, mat3_transform_vec};
+/// use stones::vector::vec3_dot;
+///
+/// // The normal matrix of a rotation is the rotation
+/// let rotation = mat4_rotation_axis([1.0f64, -2.0, 0.5], 0.9);
+/// let normal_matrix = mat4_normal_matrix(rotation).unwrap();
+/// for i in 0..3 {
+///     for j in 0..3 {
+///         assert!((normal_matrix[i * 3 + j] - rotation[i * 4 + j]).abs() < 1e-12);
+///     }
+/// }
+///
+/// // Normals stay perpendicular to tangents under a non-uniform scale
+/// let m = mat4_from_trs([5.0f64, 1.0, -2.0], ([0.0, 0.0, 1.0], 0.4), [3.0, 0.5, 1.0]);
+/// let (normal, tangent) = ([1.0, 1.0, 0.0], [1.0, -1.0, 2.0]);
+/// let transformed_tangent = mat4_transform_vec(m, [tangent[0], tangent[1], tangent[2], 0.0]);
+/// let transformed_normal = mat3_transform_vec(mat4_normal_matrix(m).unwrap(), normal);
+/// let transformed_tangent = [transformed_tangent[0], transformed_tangent[1], transformed_tangent[2]];
+/// assert!(vec3_dot(transformed_normal, transformed_tangent).abs() < 1e-12);
+///
+/// // Transforming the normal with the matrix itself doesn't work
+/// let wrong_normal = mat4_transform_vec(m, [normal[0], normal[1], normal[2], 0.0]);
+/// assert!(vec3_dot([wrong_normal[0], wrong_normal[1], wrong_normal[2]], transformed_tangent).abs() > 1.0);
+///
+/// // Singular
+/// assert_eq!(mat4_normal_matrix(mat4_from_trs([0.0f64; 3], ([0.0, 0.0, 1.0], 0.4), [3.0, 0.0, 1.0])), None);
+/// ```
+pub fn mat4_normal_matrix<T>(m: Matrix4<T>) -> Option<Matrix3<T>>
+    where T: Float
+{
+    let inverse = mat3_inverse(mat4_to_mat3(m))?;
+    Some(mat3_transpose(inverse))
+}
+
+/// Computes the LU decomposition of a 3x3 matrix with partial pivoting
+///
+/// Returns (L, U, permutation) where L is lower triangular with a unit diagonal, U is upper
+/// triangular and the row i of P * m is the row permutation[i] of m. Returns None if the
+/// matrix is singular, i.e. if a pivot is smaller than the largest component of the matrix
+/// times the machine epsilon times the size of the matrix.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_lu, mat3_lu_solve, mat3_mul, mat3_row, mat3_from_rows};
+///
+/// let m = [2.0, 1.0, 1.0,
+///          4.0, -6.0, 0.0,
+///          -2.0, 7.0, 2.0];
+/// let (l, u, permutation) = mat3_lu(m).unwrap();
+/// assert_eq!(permutation, [1, 0, 2]);
+/// for row in 0..3 {
+///     for column in 0..3 {
+///         assert!(column <= row || l[row * 3 + column] == 0.0);
+///         assert!(column >= row || u[row * 3 + column] == 0.0);
+///     }
+///     assert_eq!(l[row * 4], 1.0);
+/// }
+///
+/// let permuted = mat3_from_rows([mat3_row(m, permutation[0]), mat3_row(m, permutation[1]), mat3_row(m, permutation[2])]);
+/// assert_eq!(mat3_mul(l, u), permuted);
+///
+/// // 2x + y + z = 5, 4x - 6y = -2, -2x + 7y + 2z = 9
+/// assert_eq!(mat3_lu_solve((l, u, permutation), [5.0, -2.0, 9.0]), [1.0, 1.0, 2.0]);
+///
+/// // Singular
+/// assert_eq!(mat3_lu([1.0, 2.0, 3.0,
+///                     4.0, 5.0, 6.0,
+///                     7.0, 8.0, 9.0]), None);
+/// ```
+pub fn mat3_lu<T>(m: Matrix3<T>) -> Option<(Matrix3<T>, Matrix3<T>, [usize; 3])>
+    where T: Float
+{
+    let mut lu = m;
+    let mut permutation = [0; 3];
+    if !lu_decompose(&mut lu, 3, &mut permutation) {
+        return None;
+    }
+
+    let mut l = mat3_identity();
+    let mut u = [T::zero(); 9];
+    lu_split(&lu, 3, &mut l, &mut u);
+    Some((l, u, permutation))
+}
+
+/// Computes the LU decomposition of a 4x4 matrix with partial pivoting
+///
+/// See `mat3_lu` for the meaning of the result.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_lu, mat4_mul, mat4_row, mat4_from_rows};
+///
+/// let mut seed = 12345u64;
+/// let mut random = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+/// };
+///
+/// for _ in 0..20 {
+///     let mut m = [0.0; 16];
+///     for x in m.iter_mut() {
+///         *x = random();
+///     }
+///
+///     let (l, u, p) = mat4_lu(m).unwrap();
+///     let permuted = mat4_from_rows([mat4_row(m, p[0]), mat4_row(m, p[1]), mat4_row(m, p[2]), mat4_row(m, p[3])]);
+///     let product = mat4_mul(l, u);
+///     for i in 0..16 {
+///         assert!((product[i] - permuted[i]).abs() < 1e-12);
+///     }
+///
+///     // Partial pivoting keeps the multipliers bounded
+///     assert!(l.iter().all(|x| x.abs() <= 1.0));
+/// }
+///
+/// assert_eq!(mat4_lu([0.0f64; 16]), None);
+/// ```
+pub fn mat4_lu<T>(m: Matrix4<T>) -> Option<(Matrix4<T>, Matrix4<T>, [usize; 4])>
+    where T: Float
+{
+    let mut lu = m;
+    let mut permutation = [0; 4];
+    if !lu_decompose(&mut lu, 4, &mut permutation) {
+        return None;
+    }
+
+    let mut l = mat4_identity();
+    let mut u = [T::zero(); 16];
+    lu_split(&lu, 4, &mut l, &mut u);
+    Some((l, u, permutation))
+}
+
+/// Solves m * x = b for x from the LU decomposition of the 3x3 matrix m
+///
+/// See `mat3_lu` for an example.
+pub fn mat3_lu_solve<T>(lu: (Matrix3<T>, Matrix3<T>, [usize; 3]), b: Vector3<T>) -> Vector3<T>
+    where T: Float
+{
+    let (l, u, permutation) = lu;
+    let mut x = [T::zero(); 3];
+    lu_solve(&l, &u, &permutation, &b, &mut x);
+    x
+}
+
+/// Solves m * x = b for x from the LU decomposition of the 4x4 matrix m
+///
+/// The decomposition can be reused for several right-hand sides.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_lu, mat4_lu_solve, mat4_transform_vec};
+///
+/// let m = [4.0, -2.0, 1.0, 3.0,
+///          3.0, 6.0, -4.0, 2.0,
+///          2.0, 1.0, 8.0, -5.0,
+///          1.0, -3.0, 2.0, 7.0];
+/// let lu = mat4_lu(m).unwrap();
+/// for &x in &[[1.0f64, 2.0, 3.0, 4.0], [-0.5, 0.0, 10.0, 2.5]] {
+///     let solution = mat4_lu_solve(lu, mat4_transform_vec(m, x));
+///     for i in 0..4 {
+///         assert!((solution[i] - x[i]).abs() < 1e-12);
+///     }
+/// }
+/// ```
+pub fn mat4_lu_solve<T>(lu: (Matrix4<T>, Matrix4<T>, [usize; 4]), b: Vector4<T>) -> Vector4<T>
+    where T: Float
+{
+    let (l, u, permutation) = lu;
+    let mut x = [T::zero(); 4];
+    lu_solve(&l, &u, &permutation, &b, &mut x);
+    x
+}
+
+/// Solves the linear system m * x = b for x with Cramer's rule
+///
+/// Returns None if the matrix is singular or nearly singular, with the same criterion as
+/// `mat2_inverse`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat2_solve, mat2_inverse, mat2_transform_vec};
+///
+/// // 2x + y = 5, x - 3y = -1
+/// let m = [2.0f64, 1.0,
+///          1.0, -3.0];
+/// assert_eq!(mat2_solve(m, [5.0, -1.0]), Some([2.0, 1.0]));
+///
+/// let b = [0.3, -7.0];
+/// let x = mat2_solve(m, b).unwrap();
+/// let expected = mat2_transform_vec(mat2_inverse(m).unwrap(), b);
+/// for i in 0..2 {
+///     assert!((x[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// assert_eq!(mat2_solve([1.0, 2.0, 2.0, 4.0], [1.0, 1.0]), None);
+/// assert_eq!(mat2_solve([1.0, 2.0, 1.0, 2.0 + 1e-15], [1.0, 1.0]), None);
+/// ```
+pub fn mat2_solve<T>(m: Matrix2<T>, b: Vector2<T>) -> Option<Vector2<T>>
+    where T: Float
+{
+    let determinant = mat2_determinant(m);
+    if is_nearly_singular(&m, 2) {
+        return None;
+    }
+
+    Some([
+        (b[0] * m[3] - m[1] * b[1]) / determinant,
+        (m[0] * b[1] - b[0] * m[2]) / determinant
+    ])
+}
+
+/// Solves the linear system m * x = b for x with Cramer's rule
+///
+/// Returns None if the matrix is singular or nearly singular, with the same criterion as
+/// `mat3_inverse`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_solve, mat3_inverse, mat3_transform_vec, mat3_translation_2d};
+///
+/// // Barycentric coordinates of p in the triangle abc
+/// let (a, b, c) = ([0.0, 0.0], [4.0, 0.0], [0.0, 2.0]);
+/// let p = [1.0, 0.5];
+/// let m = [a[0], b[0], c[0],
+///          a[1], b[1], c[1],
+///          1.0, 1.0, 1.0];
+/// assert_eq!(mat3_solve(m, [p[0], p[1], 1.0]), Some([0.5, 0.25, 0.25]));
+///
+/// let m = [2.0f64, 0.0, 1.0,
+///          1.0, 3.0, 2.0,
+///          1.0, 1.0, -1.0];
+/// let b = [1.0, -2.0, 0.5];
+/// let x = mat3_solve(m, b).unwrap();
+/// let expected = mat3_transform_vec(mat3_inverse(m).unwrap(), b);
+/// for i in 0..3 {
+///     assert!((x[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// assert_eq!(mat3_solve([1.0, 2.0, 3.0,
+///                        1.0, 2.0, 3.0,
+///                        4.0, 5.0, 6.0], [1.0, 1.0, 1.0]), None);
+///
+/// // Nearly singular, Cramer's rule would return components around 1e15
+/// assert_eq!(mat3_solve([1.0, 2.0, 3.0,
+///                        4.0, 5.0, 6.0,
+///                        7.0, 8.0, 9.0 + 1e-15], [1.0, 0.0, 0.0]), None);
+///
+/// // 2D homogeneous translation at pixel-scale coordinates
+/// let translation = mat3_translation_2d([1e4f32, 1e4]);
+/// assert_eq!(mat3_solve(translation, [1.0, 1.0, 1.0]), Some([-9999.0, -9999.0, 1.0]));
+/// assert_eq!(mat3_inverse(translation), Some(mat3_translation_2d([-1e4, -1e4])));
+/// ```
+pub fn mat3_solve<T>(m: Matrix3<T>, b: Vector3<T>) -> Option<Vector3<T>>
+    where T: Float
+{
+    let determinant = mat3_determinant(m);
+    if is_nearly_singular(&m, 3) {
+        return None;
+    }
+
+    let mut x = [T::zero(); 3];
+    for (column, x) in x.iter_mut().enumerate() {
+        let mut replaced = m;
+        for row in 0..3 {
+            replaced[row * 3 + column] = b[row];
+        }
+        *x = mat3_determinant(replaced) / determinant;
+    }
+    Some(x)
+}
+
+/// Solves the linear system m * x = b for x with Gaussian elimination
 ///
-/// let m = [3, 0, 0, 0,
-///          0, 2, 0, 0,
-///          0, 0, 1, 0,
-///          0, 0, 0, 1];
-/// let v = [5, 7, 2, 3];
+/// Returns None if the matrix is singular, with the same criterion as `mat4_lu`.
+///
+/// # Examples
 ///
-/// assert_eq!(mat4_transform_vec(m, v), [15, 14, 2, 3]);
 /// ```
-pub fn mat4_transform_vec<T>(lhs: Matrix4<T>, rhs: Vector4<T>) -> Vector4<T>
-    where T: Copy + Mul<Output=T> + Add<Output=T>
+/// use stones::matrix::{mat4_solve, mat4_inverse, mat4_transform_vec};
+///
+/// let m = [4.0f64, -2.0, 1.0, 3.0,
+///          3.0, 6.0, -4.0, 2.0,
+///          2.0, 1.0, 8.0, -5.0,
+///          1.0, -3.0, 2.0, 7.0];
+/// let x = mat4_solve(m, [15.0, 11.0, 8.0, 29.0]).unwrap();
+/// let expected = [1.0, 2.0, 3.0, 4.0];
+/// for i in 0..4 {
+///     assert!((x[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// let b = [1.0, -2.0, 0.5, 3.0];
+/// let x = mat4_solve(m, b).unwrap();
+/// let expected = mat4_transform_vec(mat4_inverse(m).unwrap(), b);
+/// for i in 0..4 {
+///     assert!((x[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// assert_eq!(mat4_solve([1.0; 16], b), None);
+/// ```
+pub fn mat4_solve<T>(m: Matrix4<T>, b: Vector4<T>) -> Option<Vector4<T>>
+    where T: Float
+{
+    mat4_lu(m).map(|lu| mat4_lu_solve(lu, b))
+}
+
+/// Maps window coordinates back to world space, like gluUnProject
+///
+/// The viewport is [x, y, width, height] and the window depth is in [0, 1], 0 being the near
+/// plane. The window coordinates are mapped to normalized device coordinates in [-1, 1] and
+/// transformed by the inverse of proj * view. Returns None if proj * view isn't invertible
+/// or if the resulting w component is zero relatively to the others.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_unproject, mat4_look_at, mat4_perspective, mat4_mul, mat4_transform_point3};
+///
+/// let view = mat4_look_at([2.0f64, 3.0, 10.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+/// let proj = mat4_perspective(1.0, 16.0 / 9.0, 0.1, 100.0);
+/// let viewport = [10.0, 20.0, 1920.0, 1080.0];
+///
+/// // Project a world point to the window
+/// let point = [1.5, -0.5, 2.0];
+/// let ndc = mat4_transform_point3(mat4_mul(proj, view), point);
+/// let window = [
+///     viewport[0] + (ndc[0] + 1.0) / 2.0 * viewport[2],
+///     viewport[1] + (ndc[1] + 1.0) / 2.0 * viewport[3],
+///     (ndc[2] + 1.0) / 2.0,
+/// ];
+///
+/// let unprojected = mat4_unproject(window, view, proj, viewport).unwrap();
+/// for i in 0..3 {
+///     assert!((unprojected[i] - point[i]).abs() < 1e-4);
+/// }
+///
+/// // The center of the window at depth 0 is on the near plane, along the view direction
+/// let near = mat4_unproject([970.0, 560.0, 0.0], view, proj, viewport).unwrap();
+/// let eye_distance = ((near[0] - 2.0).powi(2) + (near[1] - 3.0).powi(2) + (near[2] - 10.0).powi(2)).sqrt();
+/// assert!((eye_distance - 0.1).abs() < 1e-9);
+///
+/// // Not invertible
+/// assert_eq!(mat4_unproject(window, [0.0; 16], proj, viewport), None);
+/// ```
+///
+/// Camera far from the origin
+/// ```
+/// use stones::matrix::{mat4_unproject, mat4_project, mat4_look_at, mat4_perspective};
+///
+/// let view = mat4_look_at([5000.0f32, 300.0, -8000.0], [5010.0, 298.0, -8020.0], [0.0, 1.0, 0.0]);
+/// let proj = mat4_perspective(1.0, 16.0 / 9.0, 0.1, 100.0);
+/// let viewport = [0.0, 0.0, 1920.0, 1080.0];
+///
+/// let point = [5008.0, 301.0, -8012.0];
+/// let window = mat4_project(point, view, proj, viewport).unwrap();
+/// let unprojected = mat4_unproject(window, view, proj, viewport).unwrap();
+/// for i in 0..3 {
+///     assert!((unprojected[i] - point[i]).abs() < 0.1);
+/// }
+/// ```
+pub fn mat4_unproject<T>(win: Vector3<T>, view: Matrix4<T>, proj: Matrix4<T>, viewport: [T; 4]) -> Option<Vector3<T>>
+    where T: Float
+{
+    let inverse = mat4_inverse(mat4_mul(proj, view))?;
+
+    let (one, two) = (T::one(), T::one() + T::one());
+    let ndc = [
+        (win[0] - viewport[0]) / viewport[2] * two - one,
+        (win[1] - viewport[1]) / viewport[3] * two - one,
+        win[2] * two - one,
+        one
+    ];
+    let v = mat4_transform_vec(inverse, ndc);
+
+    let largest = v[..3].iter().fold(T::zero(), |largest, x| if x.abs() > largest { x.abs() } else { largest });
+    if v[3].abs() <= largest * T::epsilon() {
+        return None;
+    }
+
+    Some([v[0] / v[3], v[1] / v[3], v[2] / v[3]])
+}
+
+/// Maps world coordinates to window coordinates, like gluProject
+///
+/// The viewport is [x, y, width, height]. The point is transformed by proj * view, divided by
+/// its w component and mapped from normalized device coordinates to the viewport, the depth
+/// being mapped to [0, 1]. Returns None if the point is behind the camera, i.e. if the w
+/// component isn't positive.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_project, mat4_unproject, mat4_look_at, mat4_perspective, mat4_identity};
+///
+/// let view = mat4_look_at([2.0f64, 3.0, 10.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+/// let proj = mat4_perspective(1.0, 16.0 / 9.0, 0.1, 100.0);
+/// let viewport = [10.0, 20.0, 1920.0, 1080.0];
+///
+/// for &point in &[[1.5, -0.5, 2.0], [0.0, 0.0, 0.0], [-20.0, 4.0, -50.0]] {
+///     let window = mat4_project(point, view, proj, viewport).unwrap();
+///     assert!(window[2] >= 0.0 && window[2] <= 1.0);
+///
+///     let unprojected = mat4_unproject(window, view, proj, viewport).unwrap();
+///     for i in 0..3 {
+///         assert!((unprojected[i] - point[i]).abs() < 1e-4);
+///     }
+/// }
+///
+/// // Behind the camera
+/// assert_eq!(mat4_project([4.0, 6.0, 20.0], view, proj, viewport), None);
+///
+/// // Symmetric orthographic projection of the [-2, 2] x [-1, 1] x [-1, 1] box
+/// let ortho = [0.5, 0.0, 0.0, 0.0,
+///              0.0, 1.0, 0.0, 0.0,
+///              0.0, 0.0, -1.0, 0.0,
+///              0.0, 0.0, 0.0, 1.0];
+/// assert_eq!(mat4_project([0.0, 0.0, 0.0], mat4_identity(), ortho, viewport), Some([970.0, 560.0, 0.5]));
+/// ```
+pub fn mat4_project<T>(world: Vector3<T>, view: Matrix4<T>, proj: Matrix4<T>, viewport: [T; 4]) -> Option<Vector3<T>>
+    where T: Float
+{
+    let clip = mat4_transform_vec(mat4_mul(proj, view), [world[0], world[1], world[2], T::one()]);
+    if clip[3] <= T::zero() {
+        return None;
+    }
+
+    let (one, two) = (T::one(), T::one() + T::one());
+    Some([
+        viewport[0] + (clip[0] / clip[3] + one) / two * viewport[2],
+        viewport[1] + (clip[1] / clip[3] + one) / two * viewport[3],
+        (clip[2] / clip[3] + one) / two
+    ])
+}
+
+/// Decomposes a square matrix in place, storing the multipliers of L below the diagonal and U
+/// on and above it. Returns false if the matrix is singular.
+fn lu_decompose<T>(a: &mut [T], size: usize, permutation: &mut [usize]) -> bool
+    where T: Float
+{
+    let largest = a.iter().fold(T::zero(), |largest, &x| if x.abs() > largest { x.abs() } else { largest });
+    let tolerance = largest * T::epsilon() * T::from_f64(size as f64);
+
+    for (i, p) in permutation.iter_mut().enumerate() {
+        *p = i;
+    }
+
+    for k in 0..size {
+        let pivot = (k..size).fold(k, |best, row| {
+            if a[row * size + k].abs() > a[best * size + k].abs() { row } else { best }
+        });
+        if a[pivot * size + k].abs() <= tolerance {
+            return false;
+        }
+
+        if pivot != k {
+            for column in 0..size {
+                a.swap(k * size + column, pivot * size + column);
+            }
+            permutation.swap(k, pivot);
+        }
+
+        for row in k + 1..size {
+            let factor = a[row * size + k] / a[k * size + k];
+            a[row * size + k] = factor;
+            for column in k + 1..size {
+                a[row * size + column] = a[row * size + column] - factor * a[k * size + column];
+            }
+        }
+    }
+
+    true
+}
+
+fn lu_split<T>(lu: &[T], size: usize, l: &mut [T], u: &mut [T])
+    where T: Copy
+{
+    for row in 0..size {
+        for column in 0..size {
+            if column < row {
+                l[row * size + column] = lu[row * size + column];
+            } else {
+                u[row * size + column] = lu[row * size + column];
+            }
+        }
+    }
+}
+
+fn lu_solve<T>(l: &[T], u: &[T], permutation: &[usize], b: &[T], x: &mut [T])
+    where T: Float
+{
+    let size = x.len();
+    for row in 0..size {
+        let mut sum = b[permutation[row]];
+        for column in 0..row {
+            sum = sum - l[row * size + column] * x[column];
+        }
+        x[row] = sum;
+    }
+
+    for row in (0..size).rev() {
+        let mut sum = x[row];
+        for column in row + 1..size {
+            sum = sum - u[row * size + column] * x[column];
+        }
+        x[row] = sum / u[row * size + row];
+    }
+}
+
+fn one_norm<T>(m: &[T], size: usize) -> T
+    where T: Float
+{
+    (0..size)
+        .map(|column| (0..size).fold(T::zero(), |acc, row| acc + m[row * size + column].abs()))
+        .fold(T::zero(), |acc, sum| if sum > acc { sum } else { acc })
+}
+
+/// Tests whether a square matrix of size at most 4 is singular with the pivot criterion of
+/// `lu_decompose`.
+fn is_nearly_singular<T>(m: &[T], size: usize) -> bool
+    where T: Float
+{
+    let mut lu = [T::zero(); 16];
+    let mut permutation = [0; 4];
+    lu[..size * size].copy_from_slice(m);
+    !lu_decompose(&mut lu[..size * size], size, &mut permutation[..size])
+}
+
+fn frobenius_norm<T>(m: &[T]) -> T
+    where T: Float
+{
+    m.iter().fold(T::zero(), |acc, &x| acc + x * x).sqrt()
+}
+
+/// Computes the exponential of a skew-symmetric 3x3 matrix
+///
+/// The skew-symmetric matrix of the vector w = [x, y, z] is
+/// [0, -z, y, z, 0, -x, -y, x, 0], its exponential is the rotation of |w| radians around
+/// the axis w, computed using the Rodrigues formula.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_exp, mat3_identity};
+///
+/// assert_eq!(mat3_exp([0.0; 9]), mat3_identity::<f64>());
+///
+/// // Rotation of 90° around Z
+/// let angle = std::f64::consts::FRAC_PI_2;
+/// let w = [0.0, -angle, 0.0,
+///          angle, 0.0, 0.0,
+///          0.0, 0.0, 0.0];
+/// let expected = [0.0, -1.0, 0.0,
+///                 1.0, 0.0, 0.0,
+///                 0.0, 0.0, 1.0];
+/// let r = mat3_exp(w);
+/// for i in 0..9 {
+///     assert!((r[i] - expected[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat3_exp<T>(skew_symmetric: Matrix3<T>) -> Matrix3<T>
+    where T: Float
+{
+    let w = skew_symmetric;
+    let theta_squared = w[7] * w[7] + w[2] * w[2] + w[3] * w[3];
+    let (a, b, _) = rotation_coefficients(theta_squared);
+    mat3_add(mat3_identity(), mat3_add(mat3_scale(w, a), mat3_scale(mat3_mul(w, w), b)))
+}
+
+/// Computes the logarithm of a 3x3 rotation matrix
+///
+/// Returns the skew-symmetric matrix of the vector w whose direction is the rotation axis
+/// and whose length is the rotation angle, in [0, π]. The inverse of `mat3_exp`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_exp, mat3_log, mat3_identity};
+///
+/// assert_eq!(mat3_log(mat3_identity::<f64>()), [0.0; 9]);
+///
+/// for &angle in &[0.3, 2.0, std::f64::consts::PI - 1e-6, std::f64::consts::PI] {
+///     let axis = [2.0 / 3.0, -1.0 / 3.0, 2.0 / 3.0];
+///     let w = [0.0, -axis[2] * angle, axis[1] * angle,
+///              axis[2] * angle, 0.0, -axis[0] * angle,
+///              -axis[1] * angle, axis[0] * angle, 0.0];
+///
+///     let r = mat3_exp(w);
+///     let log = mat3_log(r);
+///     let round_trip = mat3_exp(log);
+///     for i in 0..9 {
+///         assert!((round_trip[i] - r[i]).abs() < 1e-9);
+///     }
+///
+///     if angle < std::f64::consts::PI {
+///         for i in 0..9 {
+///             assert!((log[i] - w[i]).abs() < 1e-6);
+///         }
+///     }
+/// }
+/// ```
+pub fn mat3_log<T>(rotation: Matrix3<T>) -> Matrix3<T>
+    where T: Float
 {
+    let r = rotation;
+    let one = T::one();
+    let half = T::from_f64(0.5);
+
+    let cos = (r[0] + r[4] + r[8] - one) * half;
+    let cos = if cos > one { one } else if cos < -one { -one } else { cos };
+
+    // Antisymmetric part, equal to sin(theta) * axis
+    let antisymmetric = [(r[7] - r[5]) * half, (r[2] - r[6]) * half, (r[3] - r[1]) * half];
+    let sin = (antisymmetric[0] * antisymmetric[0]
+        + antisymmetric[1] * antisymmetric[1]
+        + antisymmetric[2] * antisymmetric[2]).sqrt();
+    let theta = sin.atan2(cos);
+
+    let w = if cos >= T::zero() {
+        let theta_squared = theta * theta;
+        let a = if theta_squared < T::epsilon().sqrt() {
+            one - theta_squared / T::from_f64(6.0) + theta_squared * theta_squared / T::from_f64(120.0)
+        } else {
+            sin / theta
+        };
+        [antisymmetric[0] / a, antisymmetric[1] / a, antisymmetric[2] / a]
+    } else {
+        // Close to π, the axis is extracted from the symmetric part R = cos * I + (1 - cos) * u * u^T + sin * [u]
+        let one_minus_cos = one - cos;
+        let diagonal = [r[0], r[4], r[8]];
+        let i = if diagonal[0] >= diagonal[1] && diagonal[0] >= diagonal[2] {
+            0
+        } else if diagonal[1] >= diagonal[2] {
+            1
+        } else {
+            2
+        };
+
+        let ui = ((diagonal[i] - cos) / one_minus_cos).sqrt();
+        let ui = if antisymmetric[i] < T::zero() { -ui } else { ui };
+        let mut axis = [T::zero(); 3];
+        for (j, a) in axis.iter_mut().enumerate() {
+            *a = if i == j {
+                ui
+            } else {
+                (r[i * 3 + j] + r[j * 3 + i]) * half / (one_minus_cos * ui)
+            };
+        }
+        [axis[0] * theta, axis[1] * theta, axis[2] * theta]
+    };
+
     [
-        lhs[0] * rhs[0] + lhs[1] * rhs[1] + lhs[2] * rhs[2] + lhs[3] * rhs[3],
-        lhs[4] * rhs[0] + lhs[5] * rhs[1] + lhs[6] * rhs[2] + lhs[7] * rhs[3],
-        lhs[8] * rhs[0] + lhs[9] * rhs[1] + lhs[10] * rhs[2] + lhs[11] * rhs[3],
-        lhs[12] * rhs[0] + lhs[13] * rhs[1] + lhs[14] * rhs[2] + lhs[15] * rhs[3]
+        T::zero(), -w[2], w[1],
+        w[2], T::zero(), -w[0],
+        -w[1], w[0], T::zero()
+    ]
+}
+
+/// Computes the exponential of a 4x4 twist matrix
+///
+/// The twist matrix has the skew-symmetric matrix of the angular velocity in its upper-left
+/// 3x3 block, the linear velocity in its last column and a zero last row. Its exponential is
+/// the corresponding rigid transformation, a rotation along with a translation.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_exp_se3, mat4_identity};
+///
+/// assert_eq!(mat4_exp_se3([0.0; 16]), mat4_identity::<f64>());
+///
+/// // Pure translation
+/// let twist = [0.0, 0.0, 0.0, 1.0,
+///              0.0, 0.0, 0.0, 2.0,
+///              0.0, 0.0, 0.0, 3.0,
+///              0.0, 0.0, 0.0, 0.0];
+/// assert_eq!(mat4_exp_se3(twist), [1.0, 0.0, 0.0, 1.0,
+///                                  0.0, 1.0, 0.0, 2.0,
+///                                  0.0, 0.0, 1.0, 3.0,
+///                                  0.0, 0.0, 0.0, 1.0]);
+/// ```
+pub fn mat4_exp_se3<T>(twist: Matrix4<T>) -> Matrix4<T>
+    where T: Float
+{
+    let w = [
+        twist[0], twist[1], twist[2],
+        twist[4], twist[5], twist[6],
+        twist[8], twist[9], twist[10]
+    ];
+    let theta_squared = w[7] * w[7] + w[2] * w[2] + w[3] * w[3];
+    let (a, b, c) = rotation_coefficients(theta_squared);
+
+    let w2 = mat3_mul(w, w);
+    let r = mat3_add(mat3_identity(), mat3_add(mat3_scale(w, a), mat3_scale(w2, b)));
+    let v = mat3_add(mat3_identity(), mat3_add(mat3_scale(w, b), mat3_scale(w2, c)));
+    let t = [twist[3], twist[7], twist[11]];
+
+    [
+        r[0], r[1], r[2], v[0] * t[0] + v[1] * t[1] + v[2] * t[2],
+        r[3], r[4], r[5], v[3] * t[0] + v[4] * t[1] + v[5] * t[2],
+        r[6], r[7], r[8], v[6] * t[0] + v[7] * t[1] + v[8] * t[2],
+        T::zero(), T::zero(), T::zero(), T::one()
+    ]
+}
+
+/// Computes the logarithm of a 4x4 rigid transformation matrix
+///
+/// Returns the twist matrix whose exponential is the transformation, the inverse of
+/// `mat4_exp_se3`. This allows interpolating rigid transformations along a screw motion as
+/// `exp(t * log(B * inverse(A))) * A`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_exp_se3, mat4_log_se3, mat4_mul, mat4_scale};
+///
+/// // A is a translation, B is A followed by a screw motion of 90° around the Z axis passing
+/// // through [1, 0, 0], with a translation of 2 along the axis
+/// let screw = |angle: f64, shift: f64| {
+///     let (sin, cos) = angle.sin_cos();
+///     [cos, -sin, 0.0, 1.0 - cos,
+///      sin, cos, 0.0, -sin,
+///      0.0, 0.0, 1.0, shift,
+///      0.0, 0.0, 0.0, 1.0]
+/// };
+/// let a = [1.0, 0.0, 0.0, 3.0,
+///          0.0, 1.0, 0.0, 0.0,
+///          0.0, 0.0, 1.0, 0.0,
+///          0.0, 0.0, 0.0, 1.0];
+/// let a_inverse = [1.0, 0.0, 0.0, -3.0,
+///                  0.0, 1.0, 0.0, 0.0,
+///                  0.0, 0.0, 1.0, 0.0,
+///                  0.0, 0.0, 0.0, 1.0];
+/// let b = mat4_mul(screw(std::f64::consts::FRAC_PI_2, 2.0), a);
+///
+/// let log = mat4_log_se3(mat4_mul(b, a_inverse));
+/// let halfway = mat4_mul(mat4_exp_se3(mat4_scale(log, 0.5)), a);
+/// let expected = mat4_mul(screw(std::f64::consts::FRAC_PI_4, 1.0), a);
+/// for i in 0..16 {
+///     assert!((halfway[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// let round_trip = mat4_exp_se3(mat4_log_se3(b));
+/// for i in 0..16 {
+///     assert!((round_trip[i] - b[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat4_log_se3<T>(m: Matrix4<T>) -> Matrix4<T>
+    where T: Float
+{
+    let w = mat3_log(mat4_to_mat3(m));
+    let theta_squared = w[7] * w[7] + w[2] * w[2] + w[3] * w[3];
+
+    let half = T::from_f64(0.5);
+    let c = if theta_squared < T::epsilon().sqrt() {
+        T::one() / T::from_f64(12.0) + theta_squared / T::from_f64(720.0)
+    } else {
+        let theta = theta_squared.sqrt();
+        (T::one() - theta * theta.sin() * half / (T::one() - theta.cos())) / theta_squared
+    };
+
+    let v_inverse = mat3_add(mat3_identity(), mat3_add(mat3_scale(w, -half), mat3_scale(mat3_mul(w, w), c)));
+    let t = [m[3], m[7], m[11]];
+
+    [
+        w[0], w[1], w[2], v_inverse[0] * t[0] + v_inverse[1] * t[1] + v_inverse[2] * t[2],
+        w[3], w[4], w[5], v_inverse[3] * t[0] + v_inverse[4] * t[1] + v_inverse[5] * t[2],
+        w[6], w[7], w[8], v_inverse[6] * t[0] + v_inverse[7] * t[1] + v_inverse[8] * t[2],
+        T::zero(), T::zero(), T::zero(), T::zero()
     ]
 }
+
+/// Returns sin(θ)/θ, (1 - cos(θ))/θ² and (θ - sin(θ))/θ³ from θ², using their Taylor
+/// expansions for small angles
+fn rotation_coefficients<T>(theta_squared: T) -> (T, T, T)
+    where T: Float
+{
+    if theta_squared < T::epsilon().sqrt() {
+        let theta_fourth = theta_squared * theta_squared;
+        (
+            T::one() - theta_squared / T::from_f64(6.0) + theta_fourth / T::from_f64(120.0),
+            T::from_f64(0.5) - theta_squared / T::from_f64(24.0) + theta_fourth / T::from_f64(720.0),
+            T::one() / T::from_f64(6.0) - theta_squared / T::from_f64(120.0) + theta_fourth / T::from_f64(5040.0)
+        )
+    } else {
+        let theta = theta_squared.sqrt();
+        let sin = theta.sin();
+        (
+            sin / theta,
+            (T::one() - theta.cos()) / theta_squared,
+            (theta - sin) / (theta_squared * theta)
+        )
+    }
+}
+
+/// Computes the singular value decomposition of a 3x3 matrix
+///
+/// Returns U, the singular values and V^T such that m = U * diag(singular values) * V^T.
+/// U and V are rotation matrices, reflections are handled by making the last singular value
+/// negative, so the singular values are sorted in descending order of absolute value.
+///
+/// The decomposition is computed using Jacobi rotations on m^T * m followed by a QR
+/// decomposition, as described by McAdams et al.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_svd, mat3_mul, mat3_transpose, mat3_determinant};
+///
+/// let diagonal = |s: [f64; 3]| [s[0], 0.0, 0.0, 0.0, s[1], 0.0, 0.0, 0.0, s[2]];
+///
+/// for &m in &[[1.0, 2.0, 3.0, -1.0, 0.5, 2.0, 4.0, 1.0, -2.0],
+///             [2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0],
+///             [0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 3.0]] {
+///     let (u, s, vt) = mat3_svd(m);
+///
+///     let reconstructed = mat3_mul(mat3_mul(u, diagonal(s)), vt);
+///     for i in 0..9 {
+///         assert!((reconstructed[i] - m[i]).abs() < 1e-12);
+///     }
+///
+///     let (uut, vvt) = (mat3_mul(u, mat3_transpose(u)), mat3_mul(mat3_transpose(vt), vt));
+///     for i in 0..9 {
+///         let expected = if i % 4 == 0 { 1.0 } else { 0.0 };
+///         assert!((uut[i] - expected).abs() < 1e-12);
+///         assert!((vvt[i] - expected).abs() < 1e-12);
+///     }
+///
+///     assert!((mat3_determinant(u) - 1.0).abs() < 1e-12);
+///     assert!((mat3_determinant(vt) - 1.0).abs() < 1e-12);
+///     assert!(s[0] >= s[1] && s[1] >= s[2].abs());
+///     assert_eq!(s[2] < 0.0, mat3_determinant(m) < 0.0);
+/// }
+/// ```
+///
+/// Symmetric positive definite matrices, the singular values are the eigenvalues
+/// ```
+/// use stones::matrix::{mat3_svd, mat3_eigen_symmetric};
+///
+/// let m: [f64; 9] = [4.0, 1.0, 0.5,
+///                     1.0, 3.0, 0.2,
+///                     0.5, 0.2, 2.0];
+/// let (_, s, _) = mat3_svd(m);
+/// let (values, _) = mat3_eigen_symmetric(m);
+/// for i in 0..3 {
+///     assert!((s[i] - values[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat3_svd<T>(m: Matrix3<T>) -> (Matrix3<T>, Vector3<T>, Matrix3<T>)
+    where T: Float
+{
+    let (_, v) = mat3_eigen_symmetric(mat3_mul(mat3_transpose(m), m));
+
+    let v0 = [v[0], v[3], v[6]];
+    let v1 = [v[1], v[4], v[7]];
+    let v = [v0, v1, vec3_cross(v0, v1)];
+
+    // The columns of B = m * V are orthogonal, the QR decomposition of B gives U and the
+    // singular values
+    let b = [mat3_transform_vec(m, v[0]), mat3_transform_vec(m, v[1]), mat3_transform_vec(m, v[2])];
+
+    let b0_length = vec3_dot(b[0], b[0]).sqrt();
+    let u0 = if b0_length > T::zero() {
+        vec3_mul(b[0], T::one() / b0_length)
+    } else {
+        [T::one(), T::zero(), T::zero()]
+    };
+
+    let u1 = vec3_sub(b[1], vec3_mul(u0, vec3_dot(u0, b[1])));
+    let u1_length = vec3_dot(u1, u1).sqrt();
+    let u1 = if u1_length > b0_length * T::epsilon() {
+        vec3_mul(u1, T::one() / u1_length)
+    } else {
+        let axis = if u0[0].abs() < u0[1].abs() && u0[0].abs() < u0[2].abs() {
+            [T::one(), T::zero(), T::zero()]
+        } else if u0[1].abs() < u0[2].abs() {
+            [T::zero(), T::one(), T::zero()]
+        } else {
+            [T::zero(), T::zero(), T::one()]
+        };
+        let u1 = vec3_cross(u0, axis);
+        vec3_mul(u1, T::one() / vec3_dot(u1, u1).sqrt())
+    };
+    let u = [u0, u1, vec3_cross(u0, u1)];
+
+    let singular_values = [vec3_dot(u[0], b[0]), vec3_dot(u[1], b[1]), vec3_dot(u[2], b[2])];
+
+    (
+        [
+            u[0][0], u[1][0], u[2][0],
+            u[0][1], u[1][1], u[2][1],
+            u[0][2], u[1][2], u[2][2]
+        ],
+        singular_values,
+        [
+            v[0][0], v[0][1], v[0][2],
+            v[1][0], v[1][1], v[1][2],
+            v[2][0], v[2][1], v[2][2]
+        ]
+    )
+}
+
+/// Computes the polar decomposition of a 3x3 matrix
+///
+/// Returns the rotation R and the symmetric stretch matrix S such that m = R * S. As for
+/// `mat3_svd`, R is always a rotation, so if m contains a reflection, S has a negative
+/// eigenvalue.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_polar_decompose, mat3_mul};
+///
+/// let (sin, cos) = 0.4f64.sin_cos();
+/// let rotation = [cos, -sin, 0.0,
+///                 sin, cos, 0.0,
+///                 0.0, 0.0, 1.0];
+/// let stretch = [2.0, 0.5, 0.0,
+///                0.5, 1.0, 0.0,
+///                0.0, 0.0, 3.0];
+///
+/// let (r, s) = mat3_polar_decompose(mat3_mul(rotation, stretch));
+/// for i in 0..9 {
+///     assert!((r[i] - rotation[i]).abs() < 1e-12);
+///     assert!((s[i] - stretch[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn mat3_polar_decompose<T>(m: Matrix3<T>) -> (Matrix3<T>, Matrix3<T>)
+    where T: Float
+{
+    let (u, singular_values, vt) = mat3_svd(m);
+    let v = [
+        vt[0], vt[3], vt[6],
+        vt[1], vt[4], vt[7],
+        vt[2], vt[5], vt[8]
+    ];
+    let stretch = [
+        singular_values[0], T::zero(), T::zero(),
+        T::zero(), singular_values[1], T::zero(),
+        T::zero(), T::zero(), singular_values[2]
+    ];
+
+    (mat3_mul(u, vt), mat3_mul(mat3_mul(v, stretch), vt))
+}
+
+/// Computes the polar decomposition of a 3x3 matrix by iterative averaging
+///
+/// Returns the orthogonal matrix R and the symmetric positive semi-definite matrix S such that
+/// m = R * S, R being the orthogonal matrix the closest to m. The iteration replaces R by the
+/// average of R and its inverse transpose, starting from m, until it converges. Unlike
+/// `mat3_polar_decompose`, if m contains a reflection, R contains it too. Returns None if m is
+/// singular or nearly singular, i.e. if the absolute value of its determinant is below the
+/// machine epsilon times the cube of its Frobenius norm, or if the iteration doesn't converge.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_polar_decompose_iterative, mat3_mul, mat3_identity, mat4_to_mat3, mat4_rotation_axis};
+///
+/// let rotation = mat4_to_mat3(mat4_rotation_axis([1.0f32, 2.0, -0.5], 0.7));
+/// let (r, s) = mat3_polar_decompose_iterative(rotation).unwrap();
+/// let identity = mat3_identity::<f32>();
+/// for i in 0..9 {
+///     assert!((r[i] - rotation[i]).abs() < 1e-4);
+///     assert!((s[i] - identity[i]).abs() < 1e-4);
+/// }
+///
+/// // Rotation and scale
+/// let scale = [2.0, 0.0, 0.0,
+///              0.0, 0.5, 0.0,
+///              0.0, 0.0, 3.0];
+/// let (r, s) = mat3_polar_decompose_iterative(mat3_mul(rotation, scale)).unwrap();
+/// for i in 0..9 {
+///     assert!((r[i] - rotation[i]).abs() < 1e-4);
+///     assert!((s[i] - scale[i]).abs() < 1e-4);
+/// }
+///
+/// // Mirrored, the reflection goes to R
+/// let mirror = [1.0, 0.0, 0.0,
+///               0.0, 1.0, 0.0,
+///               0.0, 0.0, -1.0];
+/// let (r, s) = mat3_polar_decompose_iterative(mat3_mul(mat3_mul(rotation, mirror), scale)).unwrap();
+/// let expected = mat3_mul(rotation, mirror);
+/// for i in 0..9 {
+///     assert!((r[i] - expected[i]).abs() < 1e-4);
+///     assert!((s[i] - scale[i]).abs() < 1e-4);
+/// }
+/// ```
+///
+/// Singular matrices
+/// ```
+/// use stones::matrix::mat3_polar_decompose_iterative;
+///
+/// let flat = [1.0f64, 0.0, 0.0,
+///             0.0, 1.0, 0.0,
+///             0.0, 0.0, 1e-20];
+/// assert_eq!(mat3_polar_decompose_iterative(flat), None);
+/// assert_eq!(mat3_polar_decompose_iterative([0.0f64; 9]), None);
+/// ```
+pub fn mat3_polar_decompose_iterative<T>(m: Matrix3<T>) -> Option<(Matrix3<T>, Matrix3<T>)>
+    where T: Float
+{
+    const MAX_ITERATIONS: usize = 64;
+
+    let norm = frobenius_norm(&m);
+    if mat3_determinant(m).abs() <= T::epsilon() * norm * norm * norm {
+        return None;
+    }
+
+    let half = T::one() / (T::one() + T::one());
+    let tolerance = T::epsilon() * T::from_f64(8.0);
+    let mut r = m;
+    let mut converged = false;
+    for _ in 0..MAX_ITERATIONS {
+        let inverse_transpose = mat3_transpose(mat3_inverse(r)?);
+        let next = mat3_scale(mat3_add(r, inverse_transpose), half);
+        let difference = frobenius_norm(&mat3_sub(next, r));
+        r = next;
+        if difference <= tolerance * frobenius_norm(&r) {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return None;
+    }
+
+    let s = mat3_mul(mat3_transpose(r), m);
+    Some((r, mat3_scale(mat3_add(s, mat3_transpose(s)), half)))
+}
+
+/// Orthonormalizes the columns of a 3x3 matrix with the Gram-Schmidt process
+///
+/// The first column keeps its direction, the second one is made orthogonal to the first one
+/// and the third one to the first two, so the handedness of the basis is preserved. The
+/// columns must be linearly independent.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_orthonormalize, mat3_mul, mat3_transpose, mat3_determinant, mat3_identity, mat4_to_mat3, mat4_rotation_axis};
+///
+/// // Drifted rotation
+/// let mut m = mat4_to_mat3(mat4_rotation_axis([1.0f32, 2.0, -0.5], 0.7));
+/// for i in 0..9 {
+///     m[i] += if i % 2 == 0 { 1e-3 } else { -1e-3 };
+/// }
+///
+/// let r = mat3_orthonormalize(m);
+/// let product = mat3_mul(r, mat3_transpose(r));
+/// let identity = mat3_identity::<f32>();
+/// for i in 0..9 {
+///     assert!((product[i] - identity[i]).abs() < 1e-5);
+/// }
+/// assert!(mat3_determinant(r) > 0.0);
+///
+/// // The first column keeps its direction
+/// let length = (m[0] * m[0] + m[3] * m[3] + m[6] * m[6]).sqrt();
+/// for &i in &[0, 3, 6] {
+///     assert!((r[i] - m[i] / length).abs() < 1e-6);
+/// }
+/// ```
+pub fn mat3_orthonormalize<T>(m: Matrix3<T>) -> Matrix3<T>
+    where T: Float
+{
+    let normalize = |v: Vector3<T>| vec3_mul(v, T::one() / vec3_dot(v, v).sqrt());
+
+    let c0 = normalize(mat3_col(m, 0));
+    let c1 = mat3_col(m, 1);
+    let c1 = normalize(vec3_sub(c1, vec3_mul(c0, vec3_dot(c0, c1))));
+    let c2 = mat3_col(m, 2);
+    let c2 = vec3_sub(c2, vec3_mul(c0, vec3_dot(c0, c2)));
+    let c2 = normalize(vec3_sub(c2, vec3_mul(c1, vec3_dot(c1, c2))));
+
+    mat3_from_cols([c0, c1, c2])
+}
+
+/// Computes the QR decomposition of a 3x3 matrix with Householder reflections
+///
+/// Returns (Q, R) where Q is orthogonal and R is upper triangular, with m = Q * R. The
+/// components of R below the diagonal are exactly zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat3_qr, mat3_mul, mat3_transpose, mat3_identity, mat3_determinant};
+///
+/// let matrices = [
+///     [12.0f32, -51.0, 4.0,
+///      6.0, 167.0, -68.0,
+///      -4.0, 24.0, -41.0],
+///     [0.5, 0.2, -1.3,
+///      -0.7, 2.0, 0.1,
+///      1.1, -0.4, 0.9],
+///     // Negative determinant
+///     [0.0, 1.0, 0.0,
+///      1.0, 0.0, 0.0,
+///      0.0, 0.0, 2.0],
+///     // Singular
+///     [1.0, 2.0, 3.0,
+///      2.0, 4.0, 6.0,
+///      1.0, 0.0, 1.0],
+/// ];
+/// assert!(mat3_determinant(matrices[2]) < 0.0);
+///
+/// for &m in &matrices {
+///     let (q, r) = mat3_qr(m);
+///
+///     let qtq = mat3_mul(mat3_transpose(q), q);
+///     let identity = mat3_identity::<f32>();
+///     for i in 0..9 {
+///         assert!((qtq[i] - identity[i]).abs() < 1e-5);
+///     }
+///
+///     assert_eq!([r[3], r[6], r[7]], [0.0, 0.0, 0.0]);
+///
+///     let qr = mat3_mul(q, r);
+///     let scale = m.iter().fold(1.0f32, |scale, x| scale.max(x.abs()));
+///     for i in 0..9 {
+///         assert!((qr[i] - m[i]).abs() < 1e-5 * scale);
+///     }
+/// }
+/// ```
+pub fn mat3_qr<T>(m: Matrix3<T>) -> (Matrix3<T>, Matrix3<T>)
+    where T: Float
+{
+    let mut q = mat3_identity();
+    let mut r = m;
+
+    for k in 0..2 {
+        let mut v = [T::zero(); 3];
+        for row in k..3 {
+            v[row] = r[row * 3 + k];
+        }
+        let length = vec3_dot(v, v).sqrt();
+        if length == T::zero() {
+            continue;
+        }
+
+        // Reflect towards the axis on the side avoiding cancellation
+        v[k] = if v[k] < T::zero() { v[k] - length } else { v[k] + length };
+        let v_squared = vec3_dot(v, v);
+        let outer = vec3_outer(v, v);
+        let two = T::one() + T::one();
+        let householder = mat3_sub(mat3_identity(), mat3_scale(outer, two / v_squared));
+
+        r = mat3_mul(householder, r);
+        q = mat3_mul(q, householder);
+    }
+
+    r[3] = T::zero();
+    r[6] = T::zero();
+    r[7] = T::zero();
+    (q, r)
+}
+
+/// Converts a single precision 4x4 matrix to double precision
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_to_f64, mat4_translation};
+///
+/// assert_eq!(mat4_to_f64(mat4_translation([1.0, 2.5, -3.0])), mat4_translation([1.0, 2.5, -3.0]));
+/// ```
+pub fn mat4_to_f64(m: Matrix4f) -> Matrix4d {
+    mat4_map(m, f64::from)
+}
+
+/// Converts a double precision 4x4 matrix to single precision relatively to an origin
+///
+/// The matrix is premultiplied by a translation of -origin before being narrowed, so a
+/// matrix placing an object far away from the world origin places it relatively to the
+/// origin without losing the precision of its translation.
+///
+/// # Examples
+///
+/// ```
+/// use stones::matrix::{mat4_to_f32_relative_to, mat4_mul, mat4_rotation_y, mat4_translation, mat4_transform_vec};
+///
+/// let origin = [1e9, 2.0, -1e9];
+/// let world = mat4_mul(mat4_translation([1e9 + 20.123, 2.0, -1e9 - 4.567]), mat4_rotation_y(0.5f64));
+/// let local = mat4_to_f32_relative_to(world, origin);
+///
+/// let expected = mat4_transform_vec(world, [1.0, 2.0, 3.0, 1.0]);
+/// let v = mat4_transform_vec(local, [1.0, 2.0, 3.0, 1.0]);
+/// for i in 0..3 {
+///     assert!((v[i] as f64 + origin[i] - expected[i]).abs() < 0.01);
+/// }
+///
+/// // Narrowing directly loses several units
+/// assert!(((world[3] as f32) as f64 - world[3]).abs() > 1.0);
+/// ```
+pub fn mat4_to_f32_relative_to(m: Matrix4d, origin: Vector3d) -> Matrix4f {
+    let mut result = [0.0; 16];
+    for row in 0..4 {
+        for column in 0..4 {
+            let offset = if row < 3 { origin[row] * m[12 + column] } else { 0.0 };
+            result[row * 4 + column] = (m[row * 4 + column] - offset) as f32;
+        }
+    }
+    result
+}
\ No newline at end of file
@@ -22,6 +22,8 @@
 * SOFTWARE.
 */
 
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
 /// Trait for getting the 0 value of the type implementing the trait
 pub trait Zero {
     fn zero() -> Self;
@@ -79,4 +81,118 @@ impl One for f64 {
     fn one() -> Self {
         1f64
     }
-}
\ No newline at end of file
+}
+
+/// Trait for getting the absolute value of the type implementing the trait
+///
+/// For integers, the absolute value saturates: the absolute value of the minimum value is the
+/// maximum value, so the result is never negative. `Float` requires `Abs`, so floating point
+/// types get their absolute value from this trait as well.
+///
+/// # Examples
+///
+/// ```
+/// use stones::number_traits::{Abs, Float};
+///
+/// assert_eq!(Abs::abs(-5i32), 5);
+/// assert_eq!(Abs::abs(i32::MIN), i32::MAX);
+/// assert_eq!(Abs::abs(i64::MIN), i64::MAX);
+/// assert_eq!(Abs::abs(-2.5f64), 2.5);
+///
+/// fn largest_abs<T: Float>(a: T, b: T) -> T {
+///     if a.abs() > b.abs() { a.abs() } else { b.abs() }
+/// }
+/// assert_eq!(largest_abs(-3.0f32, 2.0), 3.0);
+/// ```
+pub trait Abs {
+    fn abs(self) -> Self;
+}
+
+impl Abs for i32 {
+    fn abs(self) -> Self {
+        self.saturating_abs()
+    }
+}
+
+impl Abs for i64 {
+    fn abs(self) -> Self {
+        self.saturating_abs()
+    }
+}
+
+impl Abs for f32 {
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+}
+
+impl Abs for f64 {
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+}
+
+/// Trait for the floating point operations required by the geometric functions
+pub trait Float: Copy + PartialOrd + Zero + One + Abs
+    + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self> + Neg<Output=Self>
+{
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn floor(self) -> Self;
+    fn epsilon() -> Self;
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_float {
+    ($t:ident) => {
+        impl Float for $t {
+            fn sqrt(self) -> Self {
+                $t::sqrt(self)
+            }
+
+            fn sin(self) -> Self {
+                $t::sin(self)
+            }
+
+            fn cos(self) -> Self {
+                $t::cos(self)
+            }
+
+            fn tan(self) -> Self {
+                $t::tan(self)
+            }
+
+            fn acos(self) -> Self {
+                $t::acos(self)
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                $t::atan2(self, other)
+            }
+
+            fn floor(self) -> Self {
+                $t::floor(self)
+            }
+
+            fn epsilon() -> Self {
+                $t::EPSILON
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
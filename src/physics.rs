@@ -0,0 +1,362 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::{Vector2, Vector3, vec3_dot, vec3_mul};
+use crate::matrix::{Matrix3, mat3_add, mat3_mul, mat3_transpose};
+use crate::quaternion::{Quaternion, quat_conjugate, quat_integrate, quat_mul, quat_to_axis_angle};
+use crate::number_traits::Float;
+
+/// Computes the inertia tensor of a solid box centered on the origin
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::inertia_tensor_box;
+///
+/// // Unit cube
+/// let tensor = inertia_tensor_box([0.5, 0.5, 0.5], 1.0f64);
+/// let expected = 1.0 / 6.0;
+/// for i in 0..9 {
+///     let expected = if i % 4 == 0 { expected } else { 0.0 };
+///     assert!((tensor[i] - expected).abs() < 1e-15);
+/// }
+///
+/// assert_eq!(inertia_tensor_box([1.0, 2.0, 3.0], 3.0), [13.0, 0.0, 0.0,
+///                                                      0.0, 10.0, 0.0,
+///                                                      0.0, 0.0, 5.0]);
+/// ```
+pub fn inertia_tensor_box<T>(half_extents: Vector3<T>, mass: T) -> Matrix3<T>
+    where T: Float
+{
+    let factor = mass / T::from_f64(3.0);
+    let [x, y, z] = [half_extents[0] * half_extents[0], half_extents[1] * half_extents[1], half_extents[2] * half_extents[2]];
+    diagonal([factor * (y + z), factor * (x + z), factor * (x + y)])
+}
+
+/// Computes the inertia tensor of a solid sphere centered on the origin
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::inertia_tensor_sphere;
+///
+/// // Unit sphere
+/// assert_eq!(inertia_tensor_sphere(1.0, 1.0), [0.4, 0.0, 0.0,
+///                                              0.0, 0.4, 0.0,
+///                                              0.0, 0.0, 0.4]);
+/// assert_eq!(inertia_tensor_sphere(2.0, 5.0)[4], 8.0);
+/// ```
+pub fn inertia_tensor_sphere<T>(radius: T, mass: T) -> Matrix3<T>
+    where T: Float
+{
+    let moment = T::from_f64(0.4) * mass * radius * radius;
+    diagonal([moment, moment, moment])
+}
+
+/// Computes the inertia tensor of a solid cylinder centered on the origin
+///
+/// The axis of the cylinder is expected to be normalized.
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::inertia_tensor_cylinder;
+///
+/// assert_eq!(inertia_tensor_cylinder(2.0, 6.0, 3.0, [0.0, 1.0, 0.0]), [12.0, 0.0, 0.0,
+///                                                                     0.0, 6.0, 0.0,
+///                                                                     0.0, 0.0, 12.0]);
+///
+/// // Tilted axis, the tensor is symmetric and the moment around the axis is still m * r^2 / 2
+/// let axis = [0.6, 0.0, 0.8f64];
+/// let tensor = inertia_tensor_cylinder(2.0, 6.0, 3.0, axis);
+/// for row in 0..3 {
+///     for column in 0..3 {
+///         assert_eq!(tensor[row * 3 + column], tensor[column * 3 + row]);
+///     }
+/// }
+/// let moment: f64 = (0..3).map(|r| (0..3).map(|c| axis[r] * tensor[r * 3 + c] * axis[c]).sum::<f64>()).sum();
+/// assert!((moment - 6.0).abs() < 1e-12);
+/// ```
+pub fn inertia_tensor_cylinder<T>(radius: T, height: T, mass: T, axis: Vector3<T>) -> Matrix3<T>
+    where T: Float
+{
+    let radius_squared = radius * radius;
+    let axial = mass * radius_squared / T::from_f64(2.0);
+    let perpendicular = mass * (T::from_f64(3.0) * radius_squared + height * height) / T::from_f64(12.0);
+
+    let difference = axial - perpendicular;
+    let mut tensor = diagonal([perpendicular, perpendicular, perpendicular]);
+    for row in 0..3 {
+        for column in 0..3 {
+            tensor[row * 3 + column] = tensor[row * 3 + column] + difference * (axis[row] * axis[column]);
+        }
+    }
+
+    tensor
+}
+
+/// Moves an inertia tensor from the center of mass to a point at `offset` from it
+///
+/// Applies the parallel axis theorem: I + mass * (|offset|^2 * Id - offset * offset^T).
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::{inertia_tensor_sphere, inertia_tensor_translate};
+///
+/// let tensor = inertia_tensor_translate(inertia_tensor_sphere(1.0, 1.0), 1.0, [0.0, 0.0, 2.0]);
+/// assert_eq!(tensor, [4.4, 0.0, 0.0,
+///                     0.0, 4.4, 0.0,
+///                     0.0, 0.0, 0.4]);
+///
+/// let tensor = inertia_tensor_translate([0.0; 9], 2.0, [1.0, 2.0, 3.0]);
+/// assert_eq!(tensor, [26.0, -4.0, -6.0,
+///                     -4.0, 20.0, -12.0,
+///                     -6.0, -12.0, 10.0]);
+/// ```
+pub fn inertia_tensor_translate<T>(tensor: Matrix3<T>, mass: T, offset: Vector3<T>) -> Matrix3<T>
+    where T: Float
+{
+    let length_squared = vec3_dot(offset, offset);
+    let mut shift = [T::zero(); 9];
+    for row in 0..3 {
+        for column in 0..3 {
+            let identity = if row == column { length_squared } else { T::zero() };
+            shift[row * 3 + column] = mass * (identity - offset[row] * offset[column]);
+        }
+    }
+
+    mat3_add(tensor, shift)
+}
+
+/// Rotates an inertia tensor, computing R * I * R^T
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::{inertia_tensor_box, inertia_tensor_rotate};
+/// use stones::matrix::mat3_eigen_symmetric;
+///
+/// // Rotation of 90° around Z swaps the X and Y moments
+/// let tensor = inertia_tensor_box([1.0, 2.0, 3.0], 3.0);
+/// let rotation = [0.0, -1.0, 0.0,
+///                 1.0, 0.0, 0.0,
+///                 0.0, 0.0, 1.0];
+/// assert_eq!(inertia_tensor_rotate(tensor, rotation), [10.0, 0.0, 0.0,
+///                                                      0.0, 13.0, 0.0,
+///                                                      0.0, 0.0, 5.0]);
+///
+/// // The principal moments are preserved by any rotation
+/// let (sin, cos) = 0.7f64.sin_cos();
+/// let rotation = [cos, 0.0, sin,
+///                 0.0, 1.0, 0.0,
+///                 -sin, 0.0, cos];
+/// let rotated = inertia_tensor_rotate(tensor, rotation);
+/// for row in 0..3 {
+///     for column in 0..3 {
+///         assert!((rotated[row * 3 + column] - rotated[column * 3 + row]).abs() < 1e-12);
+///     }
+/// }
+/// let (moments, _) = mat3_eigen_symmetric(rotated);
+/// for (moment, expected) in moments.iter().zip(&[13.0, 10.0, 5.0]) {
+///     assert!((moment - expected).abs() < 1e-12);
+/// }
+/// ```
+pub fn inertia_tensor_rotate<T>(tensor: Matrix3<T>, rotation: Matrix3<T>) -> Matrix3<T>
+    where T: Float
+{
+    mat3_mul(mat3_mul(rotation, tensor), mat3_transpose(rotation))
+}
+
+/// Moves a value towards a target with a damped spring
+///
+/// Updates the velocity and returns the new value. The velocity is integrated implicitly and
+/// the value uses the updated velocity, which keeps the spring stable for any dt. Setting
+/// damping to 2 * sqrt(stiffness) makes the spring critically damped.
+///
+/// # Examples
+///
+/// Critically damped, converging without overshoot
+/// ```
+/// use stones::physics::spring_damper;
+///
+/// let (stiffness, dt) = (100.0f64, 1.0 / 60.0);
+/// let damping = 2.0 * stiffness.sqrt();
+/// let (mut value, mut velocity) = (0.0, 0.0);
+/// for _ in 0..120 {
+///     let next = spring_damper(value, 1.0, &mut velocity, stiffness, damping, dt);
+///     assert!(next >= value && next <= 1.0);
+///     value = next;
+/// }
+/// assert!((value - 1.0).abs() < 1e-3);
+/// ```
+///
+/// Underdamped, oscillating with a decaying amplitude
+/// ```
+/// use stones::physics::spring_damper;
+///
+/// let (mut value, mut velocity) = (0.0f64, 0.0);
+/// let mut peaks = Vec::new();
+/// for _ in 0..600 {
+///     let previous_velocity = velocity;
+///     value = spring_damper(value, 1.0, &mut velocity, 100.0, 2.0, 1.0 / 60.0);
+///     if previous_velocity > 0.0 && velocity <= 0.0 {
+///         peaks.push(value - 1.0);
+///     }
+/// }
+/// assert!(peaks.len() > 3);
+/// assert!(peaks[0] > 0.0);
+/// assert!(peaks.windows(2).all(|w| w[1] < w[0]));
+/// ```
+///
+/// Stiff spring with a large time step
+/// ```
+/// use stones::physics::spring_damper;
+///
+/// let (mut value, mut velocity) = (0.0f64, 0.0);
+/// for _ in 0..100 {
+///     value = spring_damper(value, 1.0, &mut velocity, 10000.0, 1.0, 0.1);
+///     assert!(value.abs() < 2.0);
+/// }
+/// assert!((value - 1.0).abs() < 1e-3);
+///
+/// // Zero dt leaves the value unchanged
+/// assert_eq!(spring_damper(0.5, 1.0, &mut velocity, 10.0, 1.0, 0.0), 0.5);
+/// ```
+pub fn spring_damper<T>(current: T, target: T, velocity: &mut T, stiffness: T, damping: T, dt: T) -> T
+    where T: Float
+{
+    let denominator = spring_denominator(stiffness, damping, dt);
+    *velocity = (*velocity + dt * stiffness * (target - current)) / denominator;
+    current + *velocity * dt
+}
+
+/// Moves a Vector2<T> towards a target with a damped spring
+///
+/// See `spring_damper`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::vec2_spring_damper;
+///
+/// let (mut value, mut velocity) = ([0.0f64, 4.0], [0.0, 0.0]);
+/// for _ in 0..200 {
+///     value = vec2_spring_damper(value, [2.0, -1.0], &mut velocity, 50.0, 2.0 * 50.0f64.sqrt(), 0.05);
+/// }
+/// assert!((value[0] - 2.0).abs() < 1e-6 && (value[1] + 1.0).abs() < 1e-6);
+/// ```
+pub fn vec2_spring_damper<T>(current: Vector2<T>, target: Vector2<T>, velocity: &mut Vector2<T>, stiffness: T, damping: T, dt: T) -> Vector2<T>
+    where T: Float
+{
+    [
+        spring_damper(current[0], target[0], &mut velocity[0], stiffness, damping, dt),
+        spring_damper(current[1], target[1], &mut velocity[1], stiffness, damping, dt)
+    ]
+}
+
+/// Moves a Vector3<T> towards a target with a damped spring
+///
+/// See `spring_damper`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::vec3_spring_damper;
+///
+/// let (mut value, mut velocity) = ([0.0f64, 4.0, 1.0], [0.0, 0.0, 0.0]);
+/// for _ in 0..200 {
+///     value = vec3_spring_damper(value, [2.0, -1.0, 1.0], &mut velocity, 50.0, 2.0 * 50.0f64.sqrt(), 0.05);
+/// }
+/// assert!((value[0] - 2.0).abs() < 1e-6 && (value[1] + 1.0).abs() < 1e-6);
+/// assert_eq!(value[2], 1.0);
+/// ```
+pub fn vec3_spring_damper<T>(current: Vector3<T>, target: Vector3<T>, velocity: &mut Vector3<T>, stiffness: T, damping: T, dt: T) -> Vector3<T>
+    where T: Float
+{
+    [
+        spring_damper(current[0], target[0], &mut velocity[0], stiffness, damping, dt),
+        spring_damper(current[1], target[1], &mut velocity[1], stiffness, damping, dt),
+        spring_damper(current[2], target[2], &mut velocity[2], stiffness, damping, dt)
+    ]
+}
+
+/// Rotates an orientation towards a target with a proportional-derivative controller
+///
+/// The error is the rotation going from current to target by the shortest path, expressed
+/// as the rotation axis scaled by the angle. Like `spring_damper`, the angular velocity is
+/// updated implicitly then used to integrate the orientation, see `quat_integrate`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::physics::quat_pd_control;
+/// use stones::quaternion::{quat_dot, quat_from_axis_angle, quat_identity};
+///
+/// // Starting 180° away from the target
+/// let target = quat_from_axis_angle([0.0, 1.0, 0.0], std::f64::consts::PI);
+/// let (mut q, mut angular_velocity) = (quat_identity(), [0.0; 3]);
+/// for _ in 0..300 {
+///     q = quat_pd_control(q, target, &mut angular_velocity, 40.0, 2.0 * 40.0f64.sqrt(), 1.0 / 60.0);
+/// }
+/// assert!((quat_dot(q, target).abs() - 1.0).abs() < 1e-6);
+/// assert!(angular_velocity.iter().all(|w| w.abs() < 1e-3));
+///
+/// let (mut angular_velocity, q) = ([0.0; 3], quat_from_axis_angle([1.0, 0.0, 0.0], 0.5f64));
+/// let next = quat_pd_control(q, q, &mut angular_velocity, 40.0, 1.0, 1.0 / 60.0);
+/// assert_eq!(angular_velocity, [0.0; 3]);
+/// assert!((quat_dot(next, q) - 1.0).abs() < 1e-12);
+/// ```
+pub fn quat_pd_control<T>(current: Quaternion<T>, target: Quaternion<T>, angular_velocity: &mut Vector3<T>, stiffness: T, damping: T, dt: T) -> Quaternion<T>
+    where T: Float
+{
+    let delta = quat_mul(target, quat_conjugate(current));
+    let delta = if delta[3] < T::zero() { [-delta[0], -delta[1], -delta[2], -delta[3]] } else { delta };
+    let (axis, angle) = quat_to_axis_angle(delta);
+    let error = vec3_mul(axis, angle);
+
+    let denominator = spring_denominator(stiffness, damping, dt);
+    for i in 0..3 {
+        angular_velocity[i] = (angular_velocity[i] + dt * stiffness * error[i]) / denominator;
+    }
+
+    quat_integrate(current, *angular_velocity, dt)
+}
+
+/// Denominator of the implicit velocity update of a damped spring
+fn spring_denominator<T>(stiffness: T, damping: T, dt: T) -> T
+    where T: Float
+{
+    T::one() + dt * damping + dt * dt * stiffness
+}
+
+fn diagonal<T>(d: Vector3<T>) -> Matrix3<T>
+    where T: Float
+{
+    [
+        d[0], T::zero(), T::zero(),
+        T::zero(), d[1], T::zero(),
+        T::zero(), T::zero(), d[2]
+    ]
+}
@@ -0,0 +1,656 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::Vector3;
+use crate::matrix::mat4_transform_vec;
+use crate::number_traits::{Float, One, Zero};
+use std::ops::{Add, Sub, Mul, Neg};
+
+/// Quaternion stored as [x, y, z, w], w being the real part
+pub type Quaternion<T> = [T; 4];
+pub type Quaternionf = Quaternion<f32>;
+pub type Quaterniond = Quaternion<f64>;
+
+/// Returns the identity quaternion
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::quat_identity;
+///
+/// assert_eq!(quat_identity::<f32>(), [0.0, 0.0, 0.0, 1.0]);
+/// ```
+pub fn quat_identity<T>() -> Quaternion<T>
+    where T: Zero + One
+{
+    [T::zero(), T::zero(), T::zero(), T::one()]
+}
+
+/// Returns the unit quaternion of the rotation of `angle` radians around `axis`
+///
+/// The axis is expected to be normalized.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::quat_from_axis_angle;
+///
+/// let q = quat_from_axis_angle([0.0, 0.0, 1.0], std::f64::consts::PI);
+/// assert!(q[0] == 0.0 && q[1] == 0.0 && q[2] == 1.0);
+/// assert!(q[3].abs() < 1e-15);
+/// ```
+pub fn quat_from_axis_angle<T>(axis: Vector3<T>, angle: T) -> Quaternion<T>
+    where T: Float
+{
+    let half_angle = angle * T::from_f64(0.5);
+    let sin = half_angle.sin();
+    [axis[0] * sin, axis[1] * sin, axis[2] * sin, half_angle.cos()]
+}
+
+/// Returns the rotation axis and angle of a unit quaternion
+///
+/// The angle is in [0, 2π), the axis is normalized. For the identity rotation, the axis is
+/// arbitrarily [1, 0, 0].
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_to_axis_angle, quat_from_axis_angle, quat_identity};
+///
+/// let (axis, angle) = quat_to_axis_angle(quat_from_axis_angle([0.0, 0.6, 0.8], 1.5f64));
+/// assert!((axis[0] - 0.0).abs() < 1e-12);
+/// assert!((axis[1] - 0.6).abs() < 1e-12);
+/// assert!((axis[2] - 0.8).abs() < 1e-12);
+/// assert!((angle - 1.5).abs() < 1e-12);
+///
+/// assert_eq!(quat_to_axis_angle(quat_identity::<f64>()), ([1.0, 0.0, 0.0], 0.0));
+/// ```
+pub fn quat_to_axis_angle<T>(q: Quaternion<T>) -> (Vector3<T>, T)
+    where T: Float
+{
+    let sin = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2]).sqrt();
+    if sin <= T::zero() {
+        return ([T::one(), T::zero(), T::zero()], T::zero());
+    }
+
+    let angle = sin.atan2(q[3]);
+    ([q[0] / sin, q[1] / sin, q[2] / sin], angle + angle)
+}
+
+/// Multiplies two quaternions together
+///
+/// The product of two rotation quaternions is the rotation applying rhs, then lhs.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::quat_mul;
+///
+/// let i = [1, 0, 0, 0];
+/// let j = [0, 1, 0, 0];
+/// assert_eq!(quat_mul(i, j), [0, 0, 1, 0]);
+/// assert_eq!(quat_mul(j, i), [0, 0, -1, 0]);
+/// assert_eq!(quat_mul(i, i), [0, 0, 0, -1]);
+/// ```
+pub fn quat_mul<T>(lhs: Quaternion<T>, rhs: Quaternion<T>) -> Quaternion<T>
+    where T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T>
+{
+    [
+        lhs[3] * rhs[0] + lhs[0] * rhs[3] + lhs[1] * rhs[2] - lhs[2] * rhs[1],
+        lhs[3] * rhs[1] - lhs[0] * rhs[2] + lhs[1] * rhs[3] + lhs[2] * rhs[0],
+        lhs[3] * rhs[2] + lhs[0] * rhs[1] - lhs[1] * rhs[0] + lhs[2] * rhs[3],
+        lhs[3] * rhs[3] - lhs[0] * rhs[0] - lhs[1] * rhs[1] - lhs[2] * rhs[2]
+    ]
+}
+
+/// Returns the conjugate of a quaternion, the inverse rotation for unit quaternions
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::quat_conjugate;
+///
+/// assert_eq!(quat_conjugate([1, -2, 3, 4]), [-1, 2, -3, 4]);
+/// ```
+pub fn quat_conjugate<T>(q: Quaternion<T>) -> Quaternion<T>
+    where T: Copy + Neg<Output=T>
+{
+    [-q[0], -q[1], -q[2], q[3]]
+}
+
+/// Calculates the dot product of two quaternions
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::quat_dot;
+///
+/// assert_eq!(quat_dot([1, 2, 3, 4], [5, 6, 7, 8]), 70);
+/// ```
+pub fn quat_dot<T>(lhs: Quaternion<T>, rhs: Quaternion<T>) -> T
+    where T: Copy + Add<Output=T> + Mul<Output=T>
+{
+    lhs[0] * rhs[0] + lhs[1] * rhs[1] + lhs[2] * rhs[2] + lhs[3] * rhs[3]
+}
+
+/// Normalizes a quaternion
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::quat_normalize;
+///
+/// assert_eq!(quat_normalize([0.0, 3.0, 0.0, 4.0]), [0.0, 0.6, 0.0, 0.8]);
+/// ```
+pub fn quat_normalize<T>(q: Quaternion<T>) -> Quaternion<T>
+    where T: Float
+{
+    let length = quat_dot(q, q).sqrt();
+    [q[0] / length, q[1] / length, q[2] / length, q[3] / length]
+}
+
+/// Spherically interpolates between two unit quaternions
+///
+/// The interpolation follows the shortest path between the two rotations. t is usually in
+/// [0, 1], values outside of this range extrapolate along the same great arc.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_slerp, quat_from_axis_angle, quat_identity};
+///
+/// let q = quat_from_axis_angle([0.0, 0.0, 1.0], 2.0f64);
+/// let halfway = quat_slerp(quat_identity(), q, 0.5);
+/// let expected = quat_from_axis_angle([0.0, 0.0, 1.0], 1.0);
+/// for i in 0..4 {
+///     assert!((halfway[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // -q is the same rotation as q
+/// let negated = [-q[0], -q[1], -q[2], -q[3]];
+/// let halfway = quat_slerp(quat_identity(), negated, 0.5);
+/// for i in 0..4 {
+///     assert!((halfway[i] - expected[i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn quat_slerp<T>(from: Quaternion<T>, to: Quaternion<T>, t: T) -> Quaternion<T>
+    where T: Float
+{
+    let cos = quat_dot(from, to);
+    let (to, cos) = if cos < T::zero() { ([-to[0], -to[1], -to[2], -to[3]], -cos) } else { (to, cos) };
+    slerp_unaligned(from, to, cos, t)
+}
+
+/// Interpolates between q1 and q2 using a Catmull-Rom spline through four unit quaternions
+///
+/// The spline goes through q1 at t = 0 and q2 at t = 1, q0 and q3 being the previous and
+/// next keyframes. It is evaluated using the Barry-Goldman pyramidal formulation with
+/// spherical interpolations, so consecutive segments join with a continuous angular
+/// velocity.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_catmull_rom, quat_from_axis_angle, quat_mul, quat_conjugate, quat_dot};
+///
+/// let keys: [[f64; 4]; 5] = [
+///     quat_from_axis_angle([0.0, 0.0, 1.0], 0.0),
+///     quat_from_axis_angle([0.0, 0.0, 1.0], 0.8),
+///     quat_from_axis_angle([0.6, 0.0, 0.8], 1.5),
+///     quat_from_axis_angle([0.0, 1.0, 0.0], 2.0),
+///     quat_from_axis_angle([1.0, 0.0, 0.0], 1.0),
+/// ];
+/// let first = |t| quat_catmull_rom(keys[0], keys[1], keys[2], keys[3], t);
+/// let second = |t| quat_catmull_rom(keys[1], keys[2], keys[3], keys[4], t);
+///
+/// // Goes through the keys
+/// for i in 0..4 {
+///     assert!((first(0.0)[i] - keys[1][i]).abs() < 1e-12);
+///     assert!((first(1.0)[i] - keys[2][i]).abs() < 1e-12);
+/// }
+///
+/// // Unit length
+/// for i in 0..=10 {
+///     let q = first(i as f64 / 10.0);
+///     assert!((quat_dot(q, q) - 1.0).abs() < 1e-12);
+/// }
+///
+/// // Continuous angular velocity across keys[2]
+/// let h = 1e-5;
+/// let left = quat_mul(quat_conjugate(first(1.0 - h)), first(1.0));
+/// let right = quat_mul(quat_conjugate(second(0.0)), second(h));
+/// for i in 0..3 {
+///     assert!((left[i] / h - right[i] / h).abs() < 1e-3);
+/// }
+///
+/// // Repeated keys
+/// let q = quat_catmull_rom(keys[1], keys[1], keys[1], keys[1], 0.5);
+/// for i in 0..4 {
+///     assert!((q[i] - keys[1][i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn quat_catmull_rom<T>(q0: Quaternion<T>, q1: Quaternion<T>, q2: Quaternion<T>, q3: Quaternion<T>, t: T) -> Quaternion<T>
+    where T: Float
+{
+    let one = T::one();
+    let half = T::from_f64(0.5);
+
+    let a1 = quat_slerp(q0, q1, t + one);
+    let a2 = quat_slerp(q1, q2, t);
+    let a3 = quat_slerp(q2, q3, t - one);
+    let b1 = quat_slerp(a1, a2, (t + one) * half);
+    let b2 = quat_slerp(a2, a3, t * half);
+    quat_normalize(quat_slerp(b1, b2, t))
+}
+
+/// Interpolates between q1 and q2 using spherical quadrangle interpolation
+///
+/// a and b are the intermediate control quaternions of q1 and q2, obtained using
+/// `quat_squad_intermediate`. The interpolation goes through q1 at t = 0 and q2 at t = 1.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_squad, quat_squad_intermediate, quat_from_axis_angle, quat_mul, quat_conjugate, quat_dot};
+///
+/// let keys: [[f64; 4]; 5] = [
+///     quat_from_axis_angle([0.0, 0.0, 1.0], 0.0),
+///     quat_from_axis_angle([0.0, 0.0, 1.0], 0.8),
+///     quat_from_axis_angle([0.6, 0.0, 0.8], 1.5),
+///     quat_from_axis_angle([0.0, 1.0, 0.0], 2.0),
+///     quat_from_axis_angle([1.0, 0.0, 0.0], 1.0),
+/// ];
+/// let intermediates = [
+///     quat_squad_intermediate(keys[0], keys[1], keys[2]),
+///     quat_squad_intermediate(keys[1], keys[2], keys[3]),
+///     quat_squad_intermediate(keys[2], keys[3], keys[4]),
+/// ];
+/// let first = |t| quat_squad(keys[1], intermediates[0], intermediates[1], keys[2], t);
+/// let second = |t| quat_squad(keys[2], intermediates[1], intermediates[2], keys[3], t);
+///
+/// // Goes through the keys
+/// for i in 0..4 {
+///     assert!((first(0.0)[i] - keys[1][i]).abs() < 1e-12);
+///     assert!((first(1.0)[i] - keys[2][i]).abs() < 1e-12);
+/// }
+///
+/// // Unit length
+/// for i in 0..=10 {
+///     let q = first(i as f64 / 10.0);
+///     assert!((quat_dot(q, q) - 1.0).abs() < 1e-12);
+/// }
+///
+/// // Continuous angular velocity across keys[2]
+/// let h = 1e-5;
+/// let left = quat_mul(quat_conjugate(first(1.0 - h)), first(1.0));
+/// let right = quat_mul(quat_conjugate(second(0.0)), second(h));
+/// for i in 0..3 {
+///     assert!((left[i] / h - right[i] / h).abs() < 1e-3);
+/// }
+///
+/// // Repeated keys
+/// let a = quat_squad_intermediate(keys[1], keys[1], keys[1]);
+/// let q = quat_squad(keys[1], a, a, keys[1], 0.5);
+/// for i in 0..4 {
+///     assert!((q[i] - keys[1][i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn quat_squad<T>(q1: Quaternion<T>, a: Quaternion<T>, b: Quaternion<T>, q2: Quaternion<T>, t: T) -> Quaternion<T>
+    where T: Float
+{
+    let q2 = if quat_dot(q1, q2) < T::zero() { [-q2[0], -q2[1], -q2[2], -q2[3]] } else { q2 };
+    let outer = slerp_unaligned(q1, q2, quat_dot(q1, q2), t);
+    let inner = slerp_unaligned(a, b, quat_dot(a, b), t);
+    let s = (t + t) * (T::one() - t);
+    quat_normalize(slerp_unaligned(outer, inner, quat_dot(outer, inner), s))
+}
+
+/// Computes the intermediate control quaternion of q1 for `quat_squad`
+///
+/// q0 and q2 are the keyframes surrounding q1.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_squad_intermediate, quat_from_axis_angle};
+///
+/// // Constant rotation speed around the same axis, the intermediate is the key itself
+/// let keys: [[f64; 4]; 3] = [
+///     quat_from_axis_angle([0.0, 1.0, 0.0], 0.2),
+///     quat_from_axis_angle([0.0, 1.0, 0.0], 0.7),
+///     quat_from_axis_angle([0.0, 1.0, 0.0], 1.2),
+/// ];
+/// let a = quat_squad_intermediate(keys[0], keys[1], keys[2]);
+/// for i in 0..4 {
+///     assert!((a[i] - keys[1][i]).abs() < 1e-12);
+/// }
+/// ```
+pub fn quat_squad_intermediate<T>(q0: Quaternion<T>, q1: Quaternion<T>, q2: Quaternion<T>) -> Quaternion<T>
+    where T: Float
+{
+    let q0 = if quat_dot(q0, q1) < T::zero() { [-q0[0], -q0[1], -q0[2], -q0[3]] } else { q0 };
+    let q2 = if quat_dot(q2, q1) < T::zero() { [-q2[0], -q2[1], -q2[2], -q2[3]] } else { q2 };
+
+    let inverse = quat_conjugate(q1);
+    let log_next = quat_log(quat_mul(inverse, q2));
+    let log_previous = quat_log(quat_mul(inverse, q0));
+    let quarter = T::from_f64(-0.25);
+    let tangent = [
+        (log_next[0] + log_previous[0]) * quarter,
+        (log_next[1] + log_previous[1]) * quarter,
+        (log_next[2] + log_previous[2]) * quarter
+    ];
+
+    quat_normalize(quat_mul(q1, quat_exp(tangent)))
+}
+
+/// Computes the weighted average of unit quaternions
+///
+/// The average is the eigenvector of the largest eigenvalue of the weighted sum of the outer
+/// products q * q^T, which is found by power iteration starting from the normalized weighted
+/// sum. Since q and -q represent the same rotation, the quaternions are first aligned with
+/// the first one, and the result is in the same hemisphere as the first quaternion. The
+/// weights are expected to be non-negative, all the quaternions having the same weight if
+/// there are none. Returns None if there are no quaternions, if the number of weights
+/// doesn't match or if the weights sum to zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_average, quat_from_axis_angle, quat_dot, quat_slerp};
+///
+/// let q = quat_from_axis_angle([0.0, 0.6, 0.8], 1.2f64);
+/// let average = quat_average(&[q, q, q], None).unwrap();
+/// assert!((quat_dot(average, q) - 1.0).abs() < 1e-12);
+///
+/// // q and -q are the same rotation
+/// let average = quat_average(&[q, [-q[0], -q[1], -q[2], -q[3]]], None).unwrap();
+/// assert!((quat_dot(average, q) - 1.0).abs() < 1e-12);
+///
+/// // Rotations 90° apart average to the 45° rotation
+/// let (from, to) = (quat_from_axis_angle([0.0, 0.0, 1.0], 0.0f64), quat_from_axis_angle([0.0, 0.0, 1.0], std::f64::consts::FRAC_PI_2));
+/// let average = quat_average(&[from, to], None).unwrap();
+/// let expected = quat_from_axis_angle([0.0, 0.0, 1.0], std::f64::consts::FRAC_PI_4);
+/// assert!((quat_dot(average, expected) - 1.0).abs() < 1e-12);
+///
+/// // Weights move the average towards the heavier rotation
+/// let average = quat_average(&[from, to], Some(&[1.0, 3.0])).unwrap();
+/// let (_, angle) = stones::quaternion::quat_to_axis_angle(average);
+/// assert!(angle > std::f64::consts::FRAC_PI_4 && angle < std::f64::consts::FRAC_PI_2);
+/// assert!((quat_dot(average, quat_average(&[from, to, to, to], None).unwrap()) - 1.0).abs() < 1e-12);
+///
+/// // Spread rotations
+/// let quats: Vec<[f64; 4]> = (0..5).map(|i| quat_from_axis_angle([1.0, 0.0, 0.0], 0.1 * i as f64)).collect();
+/// let average = quat_average(&quats, None).unwrap();
+/// assert!((quat_dot(average, quat_from_axis_angle([1.0, 0.0, 0.0], 0.2)) - 1.0).abs() < 1e-12);
+///
+/// assert_eq!(quat_average::<f64>(&[], None), None);
+/// assert_eq!(quat_average(&[from, to], Some(&[1.0])), None);
+/// assert_eq!(quat_average(&[from, to], Some(&[0.0, 0.0])), None);
+/// ```
+pub fn quat_average<T>(quats: &[Quaternion<T>], weights: Option<&[T]>) -> Option<Quaternion<T>>
+    where T: Float
+{
+    let first = *quats.first()?;
+    if weights.is_some_and(|weights| weights.len() != quats.len()) {
+        return None;
+    }
+
+    let mut sum = [T::zero(); 4];
+    let mut outer_products = [T::zero(); 16];
+    for (i, q) in quats.iter().enumerate() {
+        let weight = weights.map_or(T::one(), |weights| weights[i]);
+        let weight = if quat_dot(*q, first) < T::zero() { -weight } else { weight };
+        for row in 0..4 {
+            sum[row] = sum[row] + weight * q[row];
+            for column in 0..4 {
+                outer_products[row * 4 + column] = outer_products[row * 4 + column] + weight.abs() * (q[row] * q[column]);
+            }
+        }
+    }
+
+    let length = quat_dot(sum, sum).sqrt();
+    if length <= T::zero() {
+        return None;
+    }
+
+    let mut average = quat_normalize(sum);
+    for _ in 0..QUAT_AVERAGE_ITERATIONS {
+        let next = quat_normalize(mat4_transform_vec(outer_products, average));
+        let converged = T::one() - quat_dot(next, average) <= T::epsilon();
+        average = next;
+        if converged {
+            break;
+        }
+    }
+
+    if quat_dot(average, first) < T::zero() {
+        average = [-average[0], -average[1], -average[2], -average[3]];
+    }
+    Some(average)
+}
+
+/// Maximum number of power iterations used by `quat_average`
+const QUAT_AVERAGE_ITERATIONS: usize = 64;
+
+/// Integrates a constant angular velocity over a time step
+///
+/// The angular velocity is expressed in world space as the rotation axis scaled by the
+/// rotation speed in radians per unit of time, as returned by `transform_delta`. The result
+/// is normalized to prevent drift when integrating repeatedly.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_integrate, quat_from_axis_angle, quat_identity};
+///
+/// let q = quat_integrate(quat_identity(), [0.0, 0.0, 2.0f64], 0.25);
+/// let expected = quat_from_axis_angle([0.0, 0.0, 1.0], 0.5);
+/// for i in 0..4 {
+///     assert!((q[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // The rotation is applied after the initial one
+/// let initial = quat_from_axis_angle([1.0, 0.0, 0.0], 0.3f64);
+/// let q = quat_integrate(initial, [0.0, -1.0, 0.0], 0.4);
+/// let expected = stones::quaternion::quat_mul(quat_from_axis_angle([0.0, -1.0, 0.0], 0.4), initial);
+/// for i in 0..4 {
+///     assert!((q[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// assert_eq!(quat_integrate(initial, [0.0, -1.0, 0.0], 0.0), initial);
+/// ```
+pub fn quat_integrate<T>(q: Quaternion<T>, angular_velocity: Vector3<T>, dt: T) -> Quaternion<T>
+    where T: Float
+{
+    if dt == T::zero() {
+        return q;
+    }
+
+    let half_dt = dt * T::from_f64(0.5);
+    let delta = quat_exp([angular_velocity[0] * half_dt, angular_velocity[1] * half_dt, angular_velocity[2] * half_dt]);
+    quat_normalize(quat_mul(delta, q))
+}
+
+/// Compresses a unit quaternion to 32 bits using the smallest-three encoding
+///
+/// The largest component is dropped and its index stored in the two highest bits. The
+/// quaternion is negated if needed so that the dropped component is positive, which
+/// represents the same rotation. The three remaining components, in [-1/√2, 1/√2], are
+/// quantized to 10 bits each, from the highest bits to the lowest. The decompressed rotation
+/// differs from the original one by less than 0.3 degrees.
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_compress_smallest_three, quat_decompress_smallest_three, quat_identity};
+///
+/// // The identity drops w and stores three zeros
+/// let compressed = quat_compress_smallest_three(quat_identity());
+/// assert_eq!(compressed >> 30, 3);
+/// assert_eq!(quat_decompress_smallest_three(compressed), [0.0, 0.0, 0.0, 1.0]);
+///
+/// // Rotations of 180 degrees around the axes are reconstructed exactly
+/// for i in 0..3 {
+///     let mut q = [0.0; 4];
+///     q[i] = 1.0;
+///     assert_eq!(quat_decompress_smallest_three(quat_compress_smallest_three(q)), q);
+///
+///     // The negated quaternion represents the same rotation
+///     q[i] = -1.0;
+///     let decompressed = quat_decompress_smallest_three(quat_compress_smallest_three(q));
+///     q[i] = 1.0;
+///     assert_eq!(decompressed, q);
+/// }
+///
+/// // Rotations of 90 degrees
+/// let half = std::f32::consts::FRAC_1_SQRT_2;
+/// let q = [0.0, half, 0.0, -half];
+/// let decompressed = quat_decompress_smallest_three(quat_compress_smallest_three(q));
+/// for i in 0..4 {
+///     assert!((decompressed[i] - q[i]).abs() < 1e-6);
+/// }
+/// ```
+///
+/// Angular error
+/// ```
+/// use stones::quaternion::{quat_compress_smallest_three, quat_decompress_smallest_three};
+///
+/// let mut seed = 7u64;
+/// let mut random = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 11) as f64 / (1u64 << 53) as f64
+/// };
+///
+/// let tau = 2.0 * std::f64::consts::PI;
+/// let mut max_error = 0.0f64;
+/// for _ in 0..500 {
+///     // Uniformly distributed rotation
+///     let (u1, u2, u3) = (random(), random(), random());
+///     let q = [((1.0 - u1).sqrt() * (tau * u2).sin()) as f32,
+///              ((1.0 - u1).sqrt() * (tau * u2).cos()) as f32,
+///              (u1.sqrt() * (tau * u3).sin()) as f32,
+///              (u1.sqrt() * (tau * u3).cos()) as f32];
+///
+///     let decompressed = quat_decompress_smallest_three(quat_compress_smallest_three(q));
+///     let dot = (0..4).map(|i| q[i] as f64 * decompressed[i] as f64).sum::<f64>().abs().min(1.0);
+///     max_error = max_error.max(2.0 * dot.acos());
+/// }
+/// assert!(max_error.to_degrees() < 0.3);
+/// ```
+pub fn quat_compress_smallest_three(q: Quaternion<f32>) -> u32 {
+    let mut largest = 0;
+    for i in 1..4 {
+        if q[i].abs() > q[largest].abs() {
+            largest = i;
+        }
+    }
+
+    let sign = if q[largest] < 0.0 { -1.0 } else { 1.0 };
+    let mut compressed = largest as u32;
+    for (i, component) in q.iter().enumerate() {
+        if i != largest {
+            let scaled = (sign * component * std::f32::consts::SQRT_2).clamp(-1.0, 1.0);
+            let quantized = (scaled * SMALLEST_THREE_STEPS).round() + SMALLEST_THREE_STEPS;
+            compressed = (compressed << 10) | quantized as u32;
+        }
+    }
+
+    compressed
+}
+
+/// Decompresses a quaternion compressed by `quat_compress_smallest_three`
+///
+/// # Examples
+///
+/// ```
+/// use stones::quaternion::{quat_compress_smallest_three, quat_decompress_smallest_three};
+///
+/// let q = [0.5, -0.5, 0.5, 0.5];
+/// let decompressed = quat_decompress_smallest_three(quat_compress_smallest_three(q));
+/// for i in 0..4 {
+///     assert!((decompressed[i] - q[i]).abs() < 2e-3);
+/// }
+/// ```
+pub fn quat_decompress_smallest_three(compressed: u32) -> Quaternion<f32> {
+    let largest = (compressed >> 30) as usize;
+    let mut q = [0.0; 4];
+    let mut sum_squares = 0.0;
+    let mut shift = 20;
+    for (i, component) in q.iter_mut().enumerate() {
+        if i != largest {
+            let quantized = ((compressed >> shift) & 0x3ff) as f32 - SMALLEST_THREE_STEPS;
+            *component = quantized / SMALLEST_THREE_STEPS * std::f32::consts::FRAC_1_SQRT_2;
+            sum_squares += *component * *component;
+            shift -= 10;
+        }
+    }
+
+    q[largest] = (1.0 - sum_squares).max(0.0).sqrt();
+    q
+}
+
+/// Number of quantization steps on each side of zero for the smallest-three encoding
+const SMALLEST_THREE_STEPS: f32 = 511.0;
+
+/// Spherically interpolates without choosing the shortest path, cos being dot(from, to)
+fn slerp_unaligned<T>(from: Quaternion<T>, to: Quaternion<T>, cos: T, t: T) -> Quaternion<T>
+    where T: Float
+{
+    let one = T::one();
+    let (from_weight, to_weight) = if cos > one - T::epsilon().sqrt() {
+        (one - t, t)
+    } else {
+        let cos = if cos < -one { -one } else { cos };
+        let angle = (one - cos * cos).sqrt().atan2(cos);
+        let sin = angle.sin();
+        (((one - t) * angle).sin() / sin, (t * angle).sin() / sin)
+    };
+
+    quat_normalize([
+        from[0] * from_weight + to[0] * to_weight,
+        from[1] * from_weight + to[1] * to_weight,
+        from[2] * from_weight + to[2] * to_weight,
+        from[3] * from_weight + to[3] * to_weight
+    ])
+}
+
+/// Logarithm of a unit quaternion, the rotation axis scaled by half the rotation angle
+fn quat_log<T>(q: Quaternion<T>) -> Vector3<T>
+    where T: Float
+{
+    let sin = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2]).sqrt();
+    let angle = sin.atan2(q[3]);
+    let scale = if sin > T::epsilon() { angle / sin } else { T::one() };
+    [q[0] * scale, q[1] * scale, q[2] * scale]
+}
+
+/// Exponential of a pure quaternion, the inverse of `quat_log`
+fn quat_exp<T>(v: Vector3<T>) -> Quaternion<T>
+    where T: Float
+{
+    let angle = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    let scale = if angle > T::epsilon() { angle.sin() / angle } else { T::one() };
+    [v[0] * scale, v[1] * scale, v[2] * scale, angle.cos()]
+}
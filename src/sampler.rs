@@ -0,0 +1,497 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::{Vector2, Vector3, vec2_zip_with, vec3_zip_with};
+use crate::number_traits::Float;
+
+/// Address mode used to sample a grid outside of its bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressMode {
+    /// The grid is repeated, see `wrap_repeat`
+    Repeat,
+    /// The grid is repeated and mirrored every other time, see `wrap_mirror`
+    Mirror,
+    /// The cells on the edges are extended, see `wrap_clamp`
+    Clamp,
+}
+
+/// Wraps a coordinate into [0, size) by repeating, like the repeat sampler address mode
+///
+/// Negative coordinates wrap around from size, i.e. the result is the floored modulo.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::wrap_repeat;
+///
+/// assert_eq!(wrap_repeat(2.5, 4.0), 2.5);
+/// assert_eq!(wrap_repeat(4.0, 4.0), 0.0);
+/// assert_eq!(wrap_repeat(9.5, 4.0), 1.5);
+/// assert_eq!(wrap_repeat(-1.0, 4.0), 3.0);
+/// assert_eq!(wrap_repeat(-8.0, 4.0), 0.0);
+/// assert_eq!(wrap_repeat(-1e-20, 4.0), 0.0);
+///
+/// // Agrees with the reference modulo formulation
+/// for i in -200..200 {
+///     let v = i as f64 * 0.37;
+///     let reference = ((v % 3.0) + 3.0) % 3.0;
+///     assert!((wrap_repeat(v, 3.0) - reference).abs() < 1e-12);
+/// }
+/// ```
+pub fn wrap_repeat<T>(v: T, size: T) -> T
+    where T: Float
+{
+    let wrapped = v - size * (v / size).floor();
+    if wrapped >= size || wrapped < T::zero() {
+        T::zero()
+    } else {
+        wrapped
+    }
+}
+
+/// Wraps a coordinate into [0, size] by mirroring, like the mirrored repeat address mode
+///
+/// The coordinate is reflected at each boundary: it goes from 0 to size during even periods
+/// and from size back to 0 during odd periods.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::wrap_mirror;
+///
+/// assert_eq!(wrap_mirror(2.5, 4.0), 2.5);
+/// assert_eq!(wrap_mirror(4.0, 4.0), 4.0);
+/// assert_eq!(wrap_mirror(8.0, 4.0), 0.0);
+///
+/// // Odd period
+/// assert_eq!(wrap_mirror(5.0, 4.0), 3.0);
+/// assert_eq!(wrap_mirror(-1.0, 4.0), 1.0);
+///
+/// // Even period
+/// assert_eq!(wrap_mirror(9.0, 4.0), 1.0);
+/// assert_eq!(wrap_mirror(-7.0, 4.0), 1.0);
+///
+/// // Agrees with the reference modulo formulation
+/// for i in -200..200 {
+///     let v = i as f64 * 0.37;
+///     let reference = (((v + 3.0) % 6.0 + 6.0) % 6.0 - 3.0).abs();
+///     assert!((wrap_mirror(v, 3.0) - reference).abs() < 1e-12);
+/// }
+/// ```
+pub fn wrap_mirror<T>(v: T, size: T) -> T
+    where T: Float
+{
+    let period = size + size;
+    let wrapped = wrap_repeat(v, period);
+    if wrapped > size {
+        period - wrapped
+    } else {
+        wrapped
+    }
+}
+
+/// Clamps a coordinate to [0, size], like the clamp to edge address mode
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::wrap_clamp;
+///
+/// assert_eq!(wrap_clamp(2.5, 4.0), 2.5);
+/// assert_eq!(wrap_clamp(4.0, 4.0), 4.0);
+/// assert_eq!(wrap_clamp(9.5, 4.0), 4.0);
+/// assert_eq!(wrap_clamp(-1.0, 4.0), 0.0);
+/// ```
+pub fn wrap_clamp<T>(v: T, size: T) -> T
+    where T: Float
+{
+    if v < T::zero() {
+        T::zero()
+    } else if v > size {
+        size
+    } else {
+        v
+    }
+}
+
+/// Wraps each component of a Vector2<T> by repeating, see `wrap_repeat`
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::vec2_wrap_repeat;
+///
+/// assert_eq!(vec2_wrap_repeat([1.25, -0.25], [1.0, 1.0]), [0.25, 0.75]);
+/// assert_eq!(vec2_wrap_repeat([33.0, -5.0], [16.0, 8.0]), [1.0, 3.0]);
+/// ```
+pub fn vec2_wrap_repeat<T>(v: Vector2<T>, size: Vector2<T>) -> Vector2<T>
+    where T: Float
+{
+    vec2_zip_with(v, size, wrap_repeat)
+}
+
+/// Wraps each component of a Vector2<T> by mirroring, see `wrap_mirror`
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::vec2_wrap_mirror;
+///
+/// assert_eq!(vec2_wrap_mirror([1.25, -0.25], [1.0, 1.0]), [0.75, 0.25]);
+/// assert_eq!(vec2_wrap_mirror([33.0, -5.0], [16.0, 8.0]), [1.0, 5.0]);
+/// ```
+pub fn vec2_wrap_mirror<T>(v: Vector2<T>, size: Vector2<T>) -> Vector2<T>
+    where T: Float
+{
+    vec2_zip_with(v, size, wrap_mirror)
+}
+
+/// Clamps each component of a Vector2<T>, see `wrap_clamp`
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::vec2_wrap_clamp;
+///
+/// assert_eq!(vec2_wrap_clamp([1.25, -0.25], [1.0, 1.0]), [1.0, 0.0]);
+/// assert_eq!(vec2_wrap_clamp([3.0, 5.0], [16.0, 8.0]), [3.0, 5.0]);
+/// ```
+pub fn vec2_wrap_clamp<T>(v: Vector2<T>, size: Vector2<T>) -> Vector2<T>
+    where T: Float
+{
+    vec2_zip_with(v, size, wrap_clamp)
+}
+
+/// Wraps each component of a Vector3<T> by repeating, see `wrap_repeat`
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::vec3_wrap_repeat;
+///
+/// assert_eq!(vec3_wrap_repeat([1.25, -0.25, 2.0], [1.0, 1.0, 2.0]), [0.25, 0.75, 0.0]);
+/// ```
+pub fn vec3_wrap_repeat<T>(v: Vector3<T>, size: Vector3<T>) -> Vector3<T>
+    where T: Float
+{
+    vec3_zip_with(v, size, wrap_repeat)
+}
+
+/// Wraps each component of a Vector3<T> by mirroring, see `wrap_mirror`
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::vec3_wrap_mirror;
+///
+/// assert_eq!(vec3_wrap_mirror([1.25, -0.25, 2.0], [1.0, 1.0, 2.0]), [0.75, 0.25, 2.0]);
+/// ```
+pub fn vec3_wrap_mirror<T>(v: Vector3<T>, size: Vector3<T>) -> Vector3<T>
+    where T: Float
+{
+    vec3_zip_with(v, size, wrap_mirror)
+}
+
+/// Clamps each component of a Vector3<T>, see `wrap_clamp`
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::vec3_wrap_clamp;
+///
+/// assert_eq!(vec3_wrap_clamp([1.25, -0.25, 2.0], [1.0, 1.0, 2.0]), [1.0, 0.0, 2.0]);
+/// ```
+pub fn vec3_wrap_clamp<T>(v: Vector3<T>, size: Vector3<T>) -> Vector3<T>
+    where T: Float
+{
+    vec3_zip_with(v, size, wrap_clamp)
+}
+
+/// Bilinearly interpolates between four values
+///
+/// cXY is the value at tx = X and ty = Y.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::bilerp;
+///
+/// let (c00, c10, c01, c11) = (1.0, 3.0, 5.0, 11.0);
+///
+/// // Corners
+/// assert_eq!(bilerp(1e20, 1.0, 0.0, 0.0, 1.0, 0.0), 1.0);
+/// assert_eq!(bilerp(c00, c10, c01, c11, 0.0, 0.0), c00);
+/// assert_eq!(bilerp(c00, c10, c01, c11, 1.0, 0.0), c10);
+/// assert_eq!(bilerp(c00, c10, c01, c11, 0.0, 1.0), c01);
+/// assert_eq!(bilerp(c00, c10, c01, c11, 1.0, 1.0), c11);
+///
+/// // Center
+/// assert_eq!(bilerp(c00, c10, c01, c11, 0.5, 0.5), 5.0);
+///
+/// // Linear interpolation along the edges
+/// assert_eq!(bilerp(c00, c10, c01, c11, 0.25, 0.0), 1.5);
+/// assert_eq!(bilerp(c00, c10, c01, c11, 1.0, 0.75), 9.0);
+/// ```
+pub fn bilerp<T>(c00: T, c10: T, c01: T, c11: T, tx: T, ty: T) -> T
+    where T: Float
+{
+    lerp(lerp(c00, c10, tx), lerp(c01, c11, tx), ty)
+}
+
+/// Trilinearly interpolates between the eight corners of a cube
+///
+/// The value at t = [x, y, z], each component being 0 or 1, is corners[x + 2 * y + 4 * z].
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::{bilerp, trilerp};
+///
+/// let corners = [1.0, 3.0, 5.0, 11.0, -2.0, 0.0, 4.0, 6.0];
+///
+/// // Corners
+/// for i in 0..8 {
+///     let t = [(i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64];
+///     assert_eq!(trilerp(corners, t), corners[i]);
+/// }
+///
+/// // Center
+/// assert_eq!(trilerp(corners, [0.5, 0.5, 0.5]), 3.5);
+///
+/// // Bilinear interpolation on the faces
+/// assert_eq!(trilerp(corners, [0.25, 0.5, 0.0]), bilerp(1.0, 3.0, 5.0, 11.0, 0.25, 0.5));
+/// assert_eq!(trilerp(corners, [0.25, 0.5, 1.0]), bilerp(-2.0, 0.0, 4.0, 6.0, 0.25, 0.5));
+/// ```
+pub fn trilerp<T>(corners: [T; 8], t: Vector3<T>) -> T
+    where T: Float
+{
+    let near = bilerp(corners[0], corners[1], corners[2], corners[3], t[0], t[1]);
+    let far = bilerp(corners[4], corners[5], corners[6], corners[7], t[0], t[1]);
+    lerp(near, far, t[2])
+}
+
+/// Bilinearly interpolates between four Vector3<T>, see `bilerp`
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::vec3_bilerp;
+///
+/// let (c00, c10, c01, c11) = ([0.0, 0.0, 1.0], [2.0, 0.0, 1.0], [0.0, 4.0, 1.0], [2.0, 4.0, 3.0]);
+/// assert_eq!(vec3_bilerp(c00, c10, c01, c11, 0.0, 0.0), c00);
+/// assert_eq!(vec3_bilerp(c00, c10, c01, c11, 1.0, 1.0), c11);
+/// assert_eq!(vec3_bilerp(c00, c10, c01, c11, 0.5, 0.5), [1.0, 2.0, 1.5]);
+/// assert_eq!(vec3_bilerp(c00, c10, c01, c11, 0.5, 0.0), [1.0, 0.0, 1.0]);
+/// ```
+pub fn vec3_bilerp<T>(c00: Vector3<T>, c10: Vector3<T>, c01: Vector3<T>, c11: Vector3<T>, tx: T, ty: T) -> Vector3<T>
+    where T: Float
+{
+    [
+        bilerp(c00[0], c10[0], c01[0], c11[0], tx, ty),
+        bilerp(c00[1], c10[1], c01[1], c11[1], tx, ty),
+        bilerp(c00[2], c10[2], c01[2], c11[2], tx, ty)
+    ]
+}
+
+/// Samples a grid of values stored row by row with bilinear filtering
+///
+/// The texture coordinates are normalized: [0, 0] is the top-left corner of the first cell
+/// and [1, 1] the bottom-right corner of the last one, the value of a cell being at its
+/// center. Coordinates reaching outside of the grid are handled by the address mode.
+///
+/// # Panics
+///
+/// Panics if width or height is zero or if values contains less than width * height
+/// values.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::{AddressMode, sample_grid_bilinear};
+///
+/// let values = [0.0, 1.0, 2.0,
+///               3.0, 4.0, 5.0];
+///
+/// // Cell centers
+/// for y in 0..2 {
+///     for x in 0..3 {
+///         let uv = [(x as f64 + 0.5) / 3.0, (y as f64 + 0.5) / 2.0];
+///         assert_eq!(sample_grid_bilinear(&values, 3, 2, uv, AddressMode::Repeat), values[y * 3 + x]);
+///     }
+/// }
+///
+/// // Between cell centers
+/// assert_eq!(sample_grid_bilinear(&values, 3, 2, [1.0 / 3.0, 0.5], AddressMode::Clamp), 2.0);
+///
+/// // Outside of the grid
+/// assert_eq!(sample_grid_bilinear(&values, 3, 2, [0.0, 0.25], AddressMode::Clamp), 0.0);
+/// assert_eq!(sample_grid_bilinear(&values, 3, 2, [0.0, 0.25], AddressMode::Mirror), 0.0);
+/// assert_eq!(sample_grid_bilinear(&values, 3, 2, [0.0, 0.25], AddressMode::Repeat), 1.0);
+/// assert_eq!(sample_grid_bilinear(&values, 3, 2, [7.0 / 6.0, 0.25], AddressMode::Repeat), 0.0);
+/// assert_eq!(sample_grid_bilinear(&values, 3, 2, [7.0 / 6.0, 0.25], AddressMode::Mirror), 2.0);
+/// ```
+pub fn sample_grid_bilinear<T>(values: &[T], width: usize, height: usize, uv: Vector2<T>, address_mode: AddressMode) -> T
+    where T: Float
+{
+    assert!(width > 0 && height > 0, "the grid must not be empty");
+    assert!(values.len() >= width * height, "the grid must contain width * height values");
+
+    let half = T::from_f64(0.5);
+    let x = uv[0] * T::from_f64(width as f64) - half;
+    let y = uv[1] * T::from_f64(height as f64) - half;
+    let (x0, y0) = (x.floor(), y.floor());
+
+    let column = |offset: f64| address_cell(x0.to_f64() + offset, width, address_mode);
+    let row = |offset: f64| address_cell(y0.to_f64() + offset, height, address_mode) * width;
+    let (c0, c1, r0, r1) = (column(0.0), column(1.0), row(0.0), row(1.0));
+
+    bilerp(values[r0 + c0], values[r0 + c1], values[r1 + c0], values[r1 + c1], x - x0, y - y0)
+}
+
+/// Computes the gradient of a heightmap at a cell using central differences
+///
+/// The heightmap is stored row by row and cell_size is the distance between two adjacent
+/// cells. Cells on the borders use one-sided differences. Returns [dh/dx, dh/dy].
+///
+/// # Panics
+///
+/// Panics if the cell is outside of the heightmap or if values contains less than
+/// width * height values.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::heightmap_gradient;
+///
+/// // Planar ramp h = 2x - y with cells 0.5 apart
+/// let mut values = Vec::new();
+/// for y in 0..4 {
+///     for x in 0..5 {
+///         values.push(2.0 * x as f64 * 0.5 - y as f64 * 0.5);
+///     }
+/// }
+/// for y in 0..4 {
+///     for x in 0..5 {
+///         assert_eq!(heightmap_gradient(&values, 5, 4, x, y, 0.5), [2.0, -1.0]);
+///     }
+/// }
+///
+/// // Borders use one-sided differences
+/// let values = [0.0, 1.0, 4.0,
+///               0.0, 1.0, 4.0];
+/// assert_eq!(heightmap_gradient(&values, 3, 2, 0, 0, 1.0), [1.0, 0.0]);
+/// assert_eq!(heightmap_gradient(&values, 3, 2, 1, 0, 1.0), [2.0, 0.0]);
+/// assert_eq!(heightmap_gradient(&values, 3, 2, 2, 1, 1.0), [3.0, 0.0]);
+/// ```
+pub fn heightmap_gradient<T>(values: &[T], width: usize, height: usize, x: usize, y: usize, cell_size: T) -> Vector2<T>
+    where T: Float
+{
+    assert!(x < width && y < height, "the cell must be inside of the heightmap");
+    assert!(values.len() >= width * height, "the heightmap must contain width * height values");
+
+    let difference = |first: usize, second: usize, steps: usize| {
+        if steps == 0 {
+            T::zero()
+        } else {
+            (values[second] - values[first]) / (T::from_f64(steps as f64) * cell_size)
+        }
+    };
+
+    let (x0, x1) = (x.saturating_sub(1), (x + 1).min(width - 1));
+    let (y0, y1) = (y.saturating_sub(1), (y + 1).min(height - 1));
+    [
+        difference(y * width + x0, y * width + x1, x1 - x0),
+        difference(y0 * width + x, y1 * width + x, y1 - y0)
+    ]
+}
+
+/// Computes the unit surface normal of a heightmap at a cell
+///
+/// The heights are along the Y axis while the grid's x and y axes are along the X and Z
+/// axes. See `heightmap_gradient`.
+///
+/// # Panics
+///
+/// Panics if the cell is outside of the heightmap or if values contains less than
+/// width * height values.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampler::heightmap_normal;
+///
+/// // Flat
+/// let values = [3.0; 12];
+/// for y in 0..3 {
+///     for x in 0..4 {
+///         assert_eq!(heightmap_normal(&values, 4, 3, x, y, 1.0), [0.0, 1.0, 0.0]);
+///     }
+/// }
+///
+/// // Ramp rising by 1 for each step along x
+/// let values = [0.0, 1.0, 2.0,
+///               0.0, 1.0, 2.0];
+/// let normal = heightmap_normal(&values, 3, 2, 1, 1, 1.0);
+/// let expected = [-std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2, 0.0];
+/// for i in 0..3 {
+///     assert!((normal[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // Unit length everywhere
+/// let values: Vec<f64> = (0..64).map(|i| ((i * 37 % 11) as f64).sin() * 3.0).collect();
+/// for y in 0..8 {
+///     for x in 0..8 {
+///         let n = heightmap_normal(&values, 8, 8, x, y, 0.25);
+///         assert!(((n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() - 1.0).abs() < 1e-12);
+///         assert!(n[1] > 0.0);
+///     }
+/// }
+/// ```
+pub fn heightmap_normal<T>(values: &[T], width: usize, height: usize, x: usize, y: usize, cell_size: T) -> Vector3<T>
+    where T: Float
+{
+    let gradient = heightmap_gradient(values, width, height, x, y, cell_size);
+    let length = (gradient[0] * gradient[0] + gradient[1] * gradient[1] + T::one()).sqrt();
+    [-gradient[0] / length, T::one() / length, -gradient[1] / length]
+}
+
+/// Returns the index of the cell to sample for a possibly out of bounds cell index
+fn address_cell(index: f64, size: usize, address_mode: AddressMode) -> usize {
+    let size = size as f64;
+    let center = index + 0.5;
+    let wrapped = match address_mode {
+        AddressMode::Repeat => wrap_repeat(center, size),
+        AddressMode::Mirror => wrap_mirror(center, size),
+        AddressMode::Clamp => wrap_clamp(center, size),
+    };
+
+    (wrapped.floor() as usize).min(size as usize - 1)
+}
+
+/// Linear interpolation returning exactly from for t = 0 and to for t = 1
+fn lerp<T>(from: T, to: T, t: T) -> T
+    where T: Float
+{
+    from * (T::one() - t) + to * t
+}
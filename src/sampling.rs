@@ -0,0 +1,432 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::{Vector2, Vector2f, Vector3};
+use crate::number_traits::Float;
+
+/// Samples a point uniformly distributed on a triangle
+///
+/// u1 and u2 are uniformly distributed in [0, 1).
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::sample_triangle_uniform;
+///
+/// let (a, b, c) = ([0.0, 0.0, 1.0], [2.0, 0.0, 1.0], [0.0, 2.0, 1.0]);
+/// assert_eq!(sample_triangle_uniform(a, b, c, 0.0, 0.3), a);
+/// assert_eq!(sample_triangle_uniform(a, b, c, 1.0, 0.0), b);
+/// assert_eq!(sample_triangle_uniform(a, b, c, 1.0, 1.0), c);
+///
+/// for i in 0..16 {
+///     for j in 0..16 {
+///         let p = sample_triangle_uniform(a, b, c, i as f64 / 16.0, j as f64 / 16.0);
+///         assert!((p[2] - 1.0f64).abs() < 1e-12);
+///         assert!(p[0] >= 0.0 && p[1] >= 0.0 && p[0] + p[1] <= 2.0);
+///     }
+/// }
+/// ```
+pub fn sample_triangle_uniform<T>(a: Vector3<T>, b: Vector3<T>, c: Vector3<T>, u1: T, u2: T) -> Vector3<T>
+    where T: Float
+{
+    let s = u1.sqrt();
+    let (wa, wb, wc) = (T::one() - s, s * (T::one() - u2), s * u2);
+    [
+        wa * a[0] + wb * b[0] + wc * c[0],
+        wa * a[1] + wb * b[1] + wc * c[1],
+        wa * a[2] + wb * b[2] + wc * c[2]
+    ]
+}
+
+/// Samples a point uniformly distributed on the unit disk
+///
+/// Uses the concentric mapping of Shirley and Chiu, which maps the unit square to the disk
+/// preserving areas and with low distortion. u1 and u2 are uniformly distributed in [0, 1).
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::sample_disk_concentric;
+///
+/// assert_eq!(sample_disk_concentric(0.5, 0.5), [0.0, 0.0]);
+/// assert_eq!(sample_disk_concentric(1.0, 0.5), [1.0, 0.0]);
+///
+/// // Random samples are evenly spread over 16 regions of equal area, 4 rings of 4 sectors
+/// let mut seed: u64 = 42;
+/// let mut uniform = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 11) as f64 / (1u64 << 53) as f64
+/// };
+///
+/// const N: usize = 16000;
+/// let mut counts = [0.0; 16];
+/// for _ in 0..N {
+///     let p = sample_disk_concentric(uniform(), uniform());
+///     let r2: f64 = p[0] * p[0] + p[1] * p[1];
+///     assert!(r2 <= 1.0);
+///
+///     let ring = ((r2 * 4.0) as usize).min(3);
+///     let sector = if p[1] >= 0.0 { 0 } else { 2 } + if p[0] >= 0.0 { 0 } else { 1 };
+///     counts[ring * 4 + sector] += 1.0;
+/// }
+///
+/// // Critical value of the chi-square distribution with 15 degrees of freedom, p = 0.001
+/// let expected = N as f64 / 16.0;
+/// let chi_square: f64 = counts.iter().map(|c| (c - expected) * (c - expected) / expected).sum();
+/// assert!(chi_square < 37.7);
+/// ```
+pub fn sample_disk_concentric<T>(u1: T, u2: T) -> Vector2<T>
+    where T: Float
+{
+    let one = T::one();
+    let (a, b) = (u1 + u1 - one, u2 + u2 - one);
+    if a == T::zero() && b == T::zero() {
+        return [T::zero(), T::zero()];
+    }
+
+    let quarter_pi = T::from_f64(std::f64::consts::FRAC_PI_4);
+    let (r, phi) = if a.abs() > b.abs() {
+        (a, quarter_pi * (b / a))
+    } else {
+        (b, quarter_pi + quarter_pi - quarter_pi * (a / b))
+    };
+
+    [r * phi.cos(), r * phi.sin()]
+}
+
+/// Samples a direction of the +Z unit hemisphere with a cosine-weighted distribution
+///
+/// Returns the direction along with its probability density, cos(θ) / π. u1 and u2 are
+/// uniformly distributed in [0, 1).
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::sample_hemisphere_cosine;
+///
+/// assert_eq!(sample_hemisphere_cosine(0.5, 0.5), ([0.0, 0.0, 1.0], std::f64::consts::FRAC_1_PI));
+///
+/// // The mean of cos(θ) for a cosine-weighted distribution is 2/3
+/// const N: usize = 64;
+/// let mut sum = 0.0;
+/// for i in 0..N {
+///     for j in 0..N {
+///         let (d, pdf) = sample_hemisphere_cosine((i as f64 + 0.5) / N as f64, (j as f64 + 0.5) / N as f64);
+///         let length: f64 = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+///         assert!((length - 1.0).abs() < 1e-12);
+///         assert!(d[2] >= 0.0);
+///         assert!((pdf - d[2] / std::f64::consts::PI).abs() < 1e-12);
+///         sum += d[2];
+///     }
+/// }
+/// assert!((sum / (N * N) as f64 - 2.0 / 3.0).abs() < 2e-3);
+/// ```
+pub fn sample_hemisphere_cosine<T>(u1: T, u2: T) -> (Vector3<T>, T)
+    where T: Float
+{
+    let p = sample_disk_concentric(u1, u2);
+    let z_squared = T::one() - p[0] * p[0] - p[1] * p[1];
+    let z = if z_squared > T::zero() { z_squared.sqrt() } else { T::zero() };
+    ([p[0], p[1], z], z / T::from_f64(std::f64::consts::PI))
+}
+
+/// Samples a point uniformly distributed on the unit sphere
+///
+/// u1 and u2 are uniformly distributed in [0, 1).
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::sample_sphere_uniform;
+///
+/// assert_eq!(sample_sphere_uniform(0.0, 0.0), [0.0, 0.0, 1.0]);
+/// assert_eq!(sample_sphere_uniform(0.5, 0.0), [1.0, 0.0, 0.0]);
+///
+/// const N: usize = 32;
+/// let mut sum = [0.0; 3];
+/// for i in 0..N {
+///     for j in 0..N {
+///         let p = sample_sphere_uniform((i as f64 + 0.5) / N as f64, (j as f64 + 0.5) / N as f64);
+///         let length: f64 = p[0] * p[0] + p[1] * p[1] + p[2] * p[2];
+///         assert!((length - 1.0).abs() < 1e-12);
+///         for k in 0..3 {
+///             sum[k] += p[k];
+///         }
+///     }
+/// }
+/// for k in 0..3 {
+///     assert!(sum[k].abs() < 1e-9);
+/// }
+/// ```
+pub fn sample_sphere_uniform<T>(u1: T, u2: T) -> Vector3<T>
+    where T: Float
+{
+    let z = T::one() - (u1 + u1);
+    let r_squared = T::one() - z * z;
+    let r = if r_squared > T::zero() { r_squared.sqrt() } else { T::zero() };
+    let phi = T::from_f64(2.0 * std::f64::consts::PI) * u2;
+    [r * phi.cos(), r * phi.sin(), z]
+}
+
+/// Generates points approximately uniformly distributed on a disk using a golden-angle spiral
+///
+/// The disk is centered on the origin. The i-th point is at the distance
+/// radius * sqrt((i + 0.5) / n) from the center, each point being rotated by the golden
+/// angle from the previous one. The output is deterministic.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::golden_spiral_disk;
+///
+/// let points = golden_spiral_disk(200, 2.0f64);
+/// assert_eq!(points.len(), 200);
+/// assert!(points.iter().all(|p| (p[0] * p[0] + p[1] * p[1]).sqrt() <= 2.0));
+///
+/// // Points are well separated, the spacing of a regular packing being about 0.25
+/// for i in 0..points.len() {
+///     for j in 0..i {
+///         let (dx, dy) = (points[i][0] - points[j][0], points[i][1] - points[j][1]);
+///         assert!((dx * dx + dy * dy).sqrt() > 0.1);
+///     }
+/// }
+///
+/// assert_eq!(golden_spiral_disk(200, 2.0f64), points);
+/// assert!(golden_spiral_disk::<f64>(0, 1.0).is_empty());
+/// assert_eq!(golden_spiral_disk(1, 2.0f64), vec![[2.0f64.sqrt(), 0.0]]);
+/// ```
+pub fn golden_spiral_disk<T>(n: usize, radius: T) -> Vec<Vector2<T>>
+    where T: Float
+{
+    let golden_angle = T::from_f64(GOLDEN_ANGLE);
+    let count = T::from_f64(n as f64);
+    (0..n).map(|i| {
+        let index = T::from_f64(i as f64);
+        let r = radius * ((index + T::from_f64(0.5)) / count).sqrt();
+        let theta = index * golden_angle;
+        [r * theta.cos(), r * theta.sin()]
+    }).collect()
+}
+
+/// Generates points approximately uniformly distributed on the unit sphere using a Fibonacci
+/// lattice
+///
+/// The points are evenly spaced along the Z axis, from the north pole to the south pole, each
+/// point being rotated by the golden angle around the Z axis from the previous one. The
+/// output is deterministic.
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::golden_spiral_sphere;
+///
+/// let points = golden_spiral_sphere::<f64>(200);
+/// assert_eq!(points.len(), 200);
+/// assert!(points.iter().all(|p| ((p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt() - 1.0).abs() < 1e-12));
+///
+/// // Points are well separated, the spacing of a regular packing being about 0.27
+/// for i in 0..points.len() {
+///     for j in 0..i {
+///         let d = [points[i][0] - points[j][0], points[i][1] - points[j][1], points[i][2] - points[j][2]];
+///         assert!((d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt() > 0.15);
+///     }
+/// }
+///
+/// // The centroid is close to the center of the sphere
+/// let centroid: Vec<f64> = (0..3).map(|i| points.iter().map(|p| p[i]).sum::<f64>() / 200.0).collect();
+/// assert!(centroid.iter().all(|c| c.abs() < 1e-2));
+///
+/// assert_eq!(golden_spiral_sphere::<f64>(200), points);
+/// assert!(golden_spiral_sphere::<f64>(0).is_empty());
+/// assert_eq!(golden_spiral_sphere::<f64>(1), vec![[1.0, 0.0, 0.0]]);
+/// ```
+pub fn golden_spiral_sphere<T>(n: usize) -> Vec<Vector3<T>>
+    where T: Float
+{
+    let golden_angle = T::from_f64(GOLDEN_ANGLE);
+    let count = T::from_f64(n as f64);
+    (0..n).map(|i| {
+        let index = T::from_f64(i as f64);
+        let z = T::one() - (index + index + T::one()) / count;
+        let r = (T::one() - z * z).sqrt();
+        let theta = index * golden_angle;
+        [r * theta.cos(), r * theta.sin(), z]
+    }).collect()
+}
+
+/// Angle between two consecutive points of a golden spiral, π * (3 - sqrt(5))
+const GOLDEN_ANGLE: f64 = 2.399_963_229_728_653;
+
+/// Returns the element of the Halton sequence of the given base at the given index
+///
+/// This is the radical inverse of the index in the given base, which must be at least 2.
+/// The values are in [0, 1).
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::halton;
+///
+/// let base_2: Vec<f32> = (0..8).map(|i| halton(i, 2)).collect();
+/// assert_eq!(base_2, [0.0, 0.5, 0.25, 0.75, 0.125, 0.625, 0.375, 0.875]);
+///
+/// let base_3: Vec<f32> = (0..6).map(|i| halton(i, 3)).collect();
+/// assert_eq!(base_3, [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0, 4.0 / 9.0, 7.0 / 9.0]);
+///
+/// assert!((0..10000).all(|i| halton(i, 5) < 1.0));
+/// assert!(halton(u32::MAX, 2) < 1.0);
+/// ```
+pub fn halton(index: u32, base: u32) -> f32 {
+    debug_assert!(base >= 2, "the base of the Halton sequence must be at least 2");
+
+    let inverse_base = 1.0 / f64::from(base);
+    let (mut i, mut f, mut result) = (index, 1.0, 0.0);
+    while i > 0 {
+        f *= inverse_base;
+        result += f * f64::from(i % base);
+        i /= base;
+    }
+
+    unit_interval(result)
+}
+
+/// Returns the element of the 2D Halton sequence of bases 2 and 3 at the given index
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::halton_2d;
+///
+/// assert_eq!(halton_2d(0), [0.0, 0.0]);
+/// assert_eq!(halton_2d(1), [0.5, 1.0 / 3.0]);
+/// assert_eq!(halton_2d(2), [0.25, 2.0 / 3.0]);
+/// assert_eq!(halton_2d(3), [0.75, 1.0 / 9.0]);
+/// ```
+///
+/// The first points have a lower discrepancy than pseudo-random ones
+/// ```
+/// use stones::sampling::halton_2d;
+///
+/// // Maximum difference between the proportion of points and the area of the boxes
+/// // [0, x) * [0, y) on a 32x32 grid
+/// let discrepancy = |points: &[[f32; 2]]| {
+///     let mut max: f32 = 0.0;
+///     for i in 1..=32 {
+///         for j in 1..=32 {
+///             let (x, y) = (i as f32 / 32.0, j as f32 / 32.0);
+///             let inside = points.iter().filter(|p| p[0] < x && p[1] < y).count();
+///             max = max.max((inside as f32 / points.len() as f32 - x * y).abs());
+///         }
+///     }
+///     max
+/// };
+///
+/// let mut seed: u64 = 7;
+/// let mut uniform = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 40) as f32 / (1u64 << 24) as f32
+/// };
+/// let random: Vec<[f32; 2]> = (0..256).map(|_| [uniform(), uniform()]).collect();
+/// let halton: Vec<[f32; 2]> = (0..256).map(halton_2d).collect();
+///
+/// assert!(discrepancy(&halton) < 0.5 * discrepancy(&random));
+/// ```
+pub fn halton_2d(index: u32) -> Vector2f {
+    [halton(index, 2), halton(index, 3)]
+}
+
+/// Returns the element of the R2 sequence at the given index
+///
+/// The R2 sequence is the 2D additive recurrence based on the plastic number described by
+/// Martin Roberts, the values are in [0, 1).
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::r2_sequence;
+///
+/// assert_eq!(r2_sequence(0), [0.5, 0.5]);
+///
+/// let p = r2_sequence(1);
+/// assert!((p[0] - 0.2548776662).abs() < 1e-6);
+/// assert!((p[1] - 0.0698402495).abs() < 1e-6);
+///
+/// assert!((0..10000).map(r2_sequence).all(|p| p[0] >= 0.0 && p[0] < 1.0 && p[1] >= 0.0 && p[1] < 1.0));
+/// ```
+pub fn r2_sequence(index: u32) -> Vector2f {
+    const PLASTIC_NUMBER: f64 = 1.324_717_957_244_746;
+    const A1: f64 = 1.0 / PLASTIC_NUMBER;
+    const A2: f64 = 1.0 / (PLASTIC_NUMBER * PLASTIC_NUMBER);
+
+    let n = f64::from(index);
+    [
+        unit_interval((0.5 + A1 * n).fract()),
+        unit_interval((0.5 + A2 * n).fract())
+    ]
+}
+
+/// Iterator over the points of a 2D low-discrepancy sequence, starting at index 0
+///
+/// # Examples
+///
+/// ```
+/// use stones::sampling::{LowDiscrepancy2D, halton_2d, r2_sequence};
+///
+/// let jitter: Vec<[f32; 2]> = LowDiscrepancy2D::new(halton_2d).skip(1).take(8).collect();
+/// assert_eq!(jitter, (1..9).map(halton_2d).collect::<Vec<_>>());
+///
+/// let points: Vec<[f32; 2]> = LowDiscrepancy2D::new(r2_sequence).take(16).collect();
+/// assert_eq!(points, (0..16).map(r2_sequence).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LowDiscrepancy2D {
+    sequence: fn(u32) -> Vector2f,
+    index: Option<u32>,
+}
+
+impl LowDiscrepancy2D {
+    /// Creates an iterator over the given sequence, such as `halton_2d` or `r2_sequence`
+    pub fn new(sequence: fn(u32) -> Vector2f) -> Self {
+        LowDiscrepancy2D {
+            sequence,
+            index: Some(0),
+        }
+    }
+}
+
+impl Iterator for LowDiscrepancy2D {
+    type Item = Vector2f;
+
+    fn next(&mut self) -> Option<Vector2f> {
+        let index = self.index?;
+        self.index = index.checked_add(1);
+        Some((self.sequence)(index))
+    }
+}
+
+/// Narrows a value of [0, 1) to f32, keeping it below 1
+fn unit_interval(value: f64) -> f32 {
+    let value = value as f32;
+    if value < 1.0 { value } else { 1.0 - f32::EPSILON / 2.0 }
+}
@@ -0,0 +1,284 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::Vector3f;
+use crate::matrix::Matrix4f;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Absolute tolerance used when comparing against golden files
+pub const SNAPSHOT_TOLERANCE: f32 = 1e-5;
+
+/// Number of decimals written for each component in the canonical text form
+const SNAPSHOT_DECIMALS: usize = 6;
+
+/// Error returned when a value doesn't match its snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The golden file couldn't be read or written
+    Io { name: String, error: io::Error },
+    /// The golden text isn't the canonical form of the snapshotted type
+    Malformed { name: String, reason: String },
+    /// A component differs from the golden value by more than the tolerance, only the first
+    /// one is reported
+    Mismatch { name: String, component: String, expected: f32, actual: f32, tolerance: f32 },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io { name, error } => write!(f, "snapshot '{}': {}", name, error),
+            SnapshotError::Malformed { name, reason } => write!(f, "snapshot '{}' is malformed: {}", name, reason),
+            SnapshotError::Mismatch { name, component, expected, actual, tolerance } => write!(
+                f,
+                "snapshot '{}' differs at {}: expected {}, got {} (difference {}, tolerance {})",
+                name, component, expected, actual, (actual - expected).abs(), tolerance
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Returns the canonical text form of a 4x4 matrix
+///
+/// The form is a `mat4` header followed by one line per row, each component written with a
+/// fixed number of decimals. The formatting doesn't depend on the locale and negative zeros
+/// are written as zeros.
+///
+/// # Examples
+///
+/// ```
+/// use stones::testing::mat4_snapshot_text;
+/// use stones::matrix::mat4_translation;
+///
+/// assert_eq!(mat4_snapshot_text(mat4_translation([1.5, -0.0, -2.25])),
+///            "mat4\n\
+///             1.000000 0.000000 0.000000 1.500000\n\
+///             0.000000 1.000000 0.000000 0.000000\n\
+///             0.000000 0.000000 1.000000 -2.250000\n\
+///             0.000000 0.000000 0.000000 1.000000\n");
+/// ```
+pub fn mat4_snapshot_text(m: Matrix4f) -> String {
+    snapshot_text("mat4", &m, 4)
+}
+
+/// Returns the canonical text form of a 3D vector
+///
+/// See `mat4_snapshot_text` for the details of the form.
+///
+/// # Examples
+///
+/// ```
+/// use stones::testing::vec3_snapshot_text;
+///
+/// assert_eq!(vec3_snapshot_text([1.0, -1e-9, 1234.5]), "vec3\n1.000000 0.000000 1234.500000\n");
+/// ```
+pub fn vec3_snapshot_text(v: Vector3f) -> String {
+    snapshot_text("vec3", &v, 3)
+}
+
+/// Compares a 4x4 matrix against its golden canonical text form
+///
+/// Leading and trailing whitespace in the golden text is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use stones::testing::{SnapshotError, compare_mat4_snapshot, mat4_snapshot_text};
+/// use stones::matrix::{mat4_identity, mat4_scaling};
+///
+/// let golden = "
+///     mat4
+///     2.000000 0.000000 0.000000 0.000000
+///     0.000000 3.000000 0.000000 0.000000
+///     0.000000 0.000000 4.000000 0.000000
+///     0.000000 0.000000 0.000000 1.000000
+/// ";
+/// compare_mat4_snapshot("scaling", golden, mat4_scaling([2.0, 3.0, 4.0]), 1e-5).unwrap();
+///
+/// // The tolerance is honored
+/// compare_mat4_snapshot("scaling", golden, mat4_scaling([2.0, 3.000009, 4.0]), 1e-5).unwrap();
+/// match compare_mat4_snapshot("scaling", golden, mat4_scaling([2.0, 3.0, 4.01]), 1e-5) {
+///     Err(SnapshotError::Mismatch { name, component, expected, actual, .. }) => {
+///         assert_eq!(name, "scaling");
+///         assert_eq!(component, "row 2, column 2");
+///         assert_eq!((expected, actual), (4.0, 4.01));
+///     }
+///     _ => panic!("expected a mismatch"),
+/// }
+///
+/// let error = compare_mat4_snapshot("scaling", golden, mat4_identity(), 1e-5).unwrap_err();
+/// assert_eq!(error.to_string(),
+///            "snapshot 'scaling' differs at row 0, column 0: expected 2, got 1 (difference 1, tolerance 0.00001)");
+///
+/// // Golden texts of another type are rejected
+/// assert!(matches!(compare_mat4_snapshot("scaling", "vec3\n1 2 3", mat4_identity(), 1e-5),
+///                  Err(SnapshotError::Malformed { .. })));
+///
+/// // The canonical form round trips
+/// let m = [0.1, -0.2, 0.3, 10.0, 1e-7, 0.0, -0.0, 5.0, 1.0, 2.0, 3.0, 4.0, 0.0, 0.0, 0.0, 1.0];
+/// compare_mat4_snapshot("round trip", &mat4_snapshot_text(m), m, 1e-6).unwrap();
+/// ```
+pub fn compare_mat4_snapshot(name: &str, golden: &str, m: Matrix4f, tolerance: f32) -> Result<(), SnapshotError> {
+    compare_snapshot(name, golden, "mat4", &m, 4, tolerance)
+}
+
+/// Compares a 3D vector against its golden canonical text form
+///
+/// # Examples
+///
+/// ```
+/// use stones::testing::{SnapshotError, compare_vec3_snapshot};
+///
+/// compare_vec3_snapshot("position", "vec3\n1.000000 2.000000 3.000000", [1.0, 2.0, 3.0], 1e-5).unwrap();
+///
+/// let error = compare_vec3_snapshot("position", "vec3\n1.000000 2.000000 3.000000", [1.0, 2.5, 3.0], 1e-5);
+/// assert!(matches!(error, Err(SnapshotError::Mismatch { ref component, .. }) if component == "y"));
+///
+/// assert!(matches!(compare_vec3_snapshot("position", "vec3\n1.0 2.0", [1.0, 2.0, 3.0], 1e-5),
+///                  Err(SnapshotError::Malformed { .. })));
+/// ```
+pub fn compare_vec3_snapshot(name: &str, golden: &str, v: Vector3f, tolerance: f32) -> Result<(), SnapshotError> {
+    compare_snapshot(name, golden, "vec3", &v, 3, tolerance)
+}
+
+/// Compares a 4x4 matrix against the golden file of the snapshot `name`
+///
+/// The golden files are stored as `<name>.snap` in the directory given by the
+/// `STONES_SNAPSHOT_DIR` environment variable, or in `snapshots` relatively to the current
+/// directory. A missing golden file is created from the matrix, and every golden file is
+/// rewritten when `STONES_UPDATE_SNAPSHOTS` is set. The comparison uses
+/// `SNAPSHOT_TOLERANCE`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stones::testing::snapshot_mat4;
+/// use stones::matrix::{mat4_mul, mat4_rotation_z, mat4_translation};
+///
+/// let m = mat4_mul(mat4_translation([1.0, 2.0, 3.0]), mat4_rotation_z(0.5));
+/// if let Err(error) = snapshot_mat4("translated_rotation", m) {
+///     panic!("{}", error);
+/// }
+/// ```
+pub fn snapshot_mat4(name: &str, m: Matrix4f) -> Result<(), SnapshotError> {
+    snapshot(name, &mat4_snapshot_text(m), |golden| compare_mat4_snapshot(name, golden, m, SNAPSHOT_TOLERANCE))
+}
+
+/// Compares a 3D vector against the golden file of the snapshot `name`
+///
+/// See `snapshot_mat4` for the location of the golden files.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stones::testing::snapshot_vec3;
+///
+/// if let Err(error) = snapshot_vec3("origin", [0.0, 0.0, 0.0]) {
+///     panic!("{}", error);
+/// }
+/// ```
+pub fn snapshot_vec3(name: &str, v: Vector3f) -> Result<(), SnapshotError> {
+    snapshot(name, &vec3_snapshot_text(v), |golden| compare_vec3_snapshot(name, golden, v, SNAPSHOT_TOLERANCE))
+}
+
+fn format_component(value: f32) -> String {
+    let text = format!("{:.*}", SNAPSHOT_DECIMALS, value);
+    let unsigned = text.trim_start_matches('-');
+    if unsigned.chars().all(|c| c == '0' || c == '.') {
+        unsigned.to_string()
+    } else {
+        text
+    }
+}
+
+fn snapshot_text(kind: &str, components: &[f32], columns: usize) -> String {
+    let mut text = format!("{}\n", kind);
+    for row in components.chunks(columns) {
+        let row: Vec<String> = row.iter().map(|&c| format_component(c)).collect();
+        text.push_str(&row.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+fn component_name(index: usize, count: usize, columns: usize) -> String {
+    if columns < count {
+        format!("row {}, column {}", index / columns, index % columns)
+    } else {
+        ["x", "y", "z", "w"][index].to_string()
+    }
+}
+
+fn compare_snapshot(name: &str, golden: &str, kind: &str, components: &[f32], columns: usize, tolerance: f32)
+    -> Result<(), SnapshotError>
+{
+    let malformed = |reason: String| SnapshotError::Malformed { name: name.to_string(), reason };
+
+    let mut tokens = golden.split_whitespace();
+    match tokens.next() {
+        Some(header) if header == kind => {}
+        header => return Err(malformed(format!("expected the header '{}', found {:?}", kind, header))),
+    }
+
+    let expected = tokens
+        .map(|token| token.parse::<f32>().map_err(|_| malformed(format!("'{}' is not a number", token))))
+        .collect::<Result<Vec<f32>, SnapshotError>>()?;
+    if expected.len() != components.len() {
+        return Err(malformed(format!("expected {} components, found {}", components.len(), expected.len())));
+    }
+
+    for (index, (&expected, &actual)) in expected.iter().zip(components).enumerate() {
+        let difference = (actual - expected).abs();
+        if difference > tolerance || difference.is_nan() {
+            return Err(SnapshotError::Mismatch {
+                name: name.to_string(),
+                component: component_name(index, components.len(), columns),
+                expected,
+                actual,
+                tolerance,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn snapshot<F>(name: &str, text: &str, compare: F) -> Result<(), SnapshotError>
+    where F: Fn(&str) -> Result<(), SnapshotError>
+{
+    let io_error = |error| SnapshotError::Io { name: name.to_string(), error };
+
+    let directory = std::env::var_os("STONES_SNAPSHOT_DIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("snapshots"));
+    let path = directory.join(format!("{}.snap", name));
+    if std::env::var_os("STONES_UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        fs::create_dir_all(&directory).map_err(io_error)?;
+        return fs::write(&path, text).map_err(io_error);
+    }
+
+    compare(&fs::read_to_string(&path).map_err(io_error)?)
+}
@@ -0,0 +1,251 @@
+/*
+* MIT License
+*
+* Copyright (c) 2018 Clément SIBILLE
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*/
+
+use crate::vector::{Vector3, vec3_add, vec3_sub, vec3_mul};
+use crate::quaternion::{Quaternion, quat_mul, quat_conjugate, quat_to_axis_angle, quat_integrate, quat_slerp};
+use crate::number_traits::Float;
+
+/// Transformation made of a scale, followed by a rotation, followed by a translation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform<T> {
+    pub translation: Vector3<T>,
+    pub rotation: Quaternion<T>,
+    pub scale: Vector3<T>,
+}
+
+/// Transform along with its linear and angular velocities, as received in a network snapshot
+///
+/// The angular velocity is the rotation axis scaled by the rotation speed in radians per unit
+/// of time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformSnapshot<T> {
+    pub transform: Transform<T>,
+    pub linear_vel: Vector3<T>,
+    pub angular_vel: Vector3<T>,
+}
+
+/// Computes the linear and angular velocities between two transforms
+///
+/// Returns the linear velocity of the translation and the angular velocity, as the rotation
+/// axis scaled by the rotation speed in radians per unit of time, of the rotation going from
+/// prev to curr by the shortest path. If dt is zero, both velocities are zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::transform::{Transform, transform_delta};
+/// use stones::quaternion::{quat_from_axis_angle, quat_identity};
+///
+/// let prev = Transform {
+///     translation: [1.0, 2.0, 3.0f64],
+///     rotation: quat_from_axis_angle([0.0, 0.0, 1.0], 0.5),
+///     scale: [1.0, 1.0, 1.0],
+/// };
+/// let curr = Transform {
+///     translation: [1.5, 2.0, 2.0],
+///     rotation: quat_from_axis_angle([0.0, 0.0, 1.0], 0.7),
+///     ..prev
+/// };
+///
+/// let (linear, angular) = transform_delta(&prev, &curr, 0.5);
+/// assert_eq!(linear, [1.0, 0.0, -2.0]);
+/// assert!(angular[0].abs() < 1e-12 && angular[1].abs() < 1e-12);
+/// assert!((angular[2] - 0.4f64).abs() < 1e-12);
+///
+/// // Rotating backwards
+/// let (_, angular) = transform_delta(&curr, &prev, 0.5);
+/// assert!((angular[2] + 0.4f64).abs() < 1e-12);
+///
+/// assert_eq!(transform_delta(&prev, &prev, 0.5), ([0.0; 3], [0.0; 3]));
+/// assert_eq!(transform_delta(&prev, &curr, 0.0), ([0.0; 3], [0.0; 3]));
+/// ```
+pub fn transform_delta<T>(prev: &Transform<T>, curr: &Transform<T>, dt: T) -> (Vector3<T>, Vector3<T>)
+    where T: Float
+{
+    if dt == T::zero() {
+        return ([T::zero(); 3], [T::zero(); 3]);
+    }
+
+    let inverse_dt = T::one() / dt;
+    let linear = vec3_mul(vec3_sub(curr.translation, prev.translation), inverse_dt);
+
+    let delta = quat_mul(curr.rotation, quat_conjugate(prev.rotation));
+    let delta = if delta[3] < T::zero() { [-delta[0], -delta[1], -delta[2], -delta[3]] } else { delta };
+    let (axis, angle) = quat_to_axis_angle(delta);
+
+    (linear, vec3_mul(axis, angle * inverse_dt))
+}
+
+/// Extrapolates a position assuming a constant acceleration
+///
+/// # Examples
+///
+/// ```
+/// use stones::transform::extrapolate_position;
+///
+/// assert_eq!(extrapolate_position([1.0, 2.0, 3.0], [2.0, 0.0, -1.0], [0.0, 0.0, 0.0], 0.5), [2.0, 2.0, 2.5]);
+/// assert_eq!(extrapolate_position([0.0, 10.0, 0.0], [1.0, 0.0, 0.0], [0.0, -10.0, 0.0], 1.0), [1.0, 5.0, 0.0]);
+/// assert_eq!(extrapolate_position([1.0, 2.0, 3.0], [2.0, 0.0, -1.0], [0.0, -10.0, 0.0], 0.0), [1.0, 2.0, 3.0]);
+/// ```
+pub fn extrapolate_position<T>(pos: Vector3<T>, velocity: Vector3<T>, acceleration: Vector3<T>, dt: T) -> Vector3<T>
+    where T: Float
+{
+    let half_dt_squared = dt * dt * T::from_f64(0.5);
+    vec3_add(pos, vec3_add(vec3_mul(velocity, dt), vec3_mul(acceleration, half_dt_squared)))
+}
+
+/// Extrapolates an orientation assuming a constant angular velocity
+///
+/// See `quat_integrate`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::transform::extrapolate_orientation;
+/// use stones::quaternion::{quat_from_axis_angle, quat_dot};
+///
+/// let q = quat_from_axis_angle([0.0, 1.0, 0.0], 0.5f64);
+/// let extrapolated = extrapolate_orientation(q, [0.0, 0.2, 0.0], 2.0);
+/// assert!((quat_dot(extrapolated, quat_from_axis_angle([0.0, 1.0, 0.0], 0.9)) - 1.0).abs() < 1e-12);
+///
+/// assert_eq!(extrapolate_orientation(q, [0.0, 0.2, 0.0], 0.0), q);
+/// ```
+pub fn extrapolate_orientation<T>(q: Quaternion<T>, angular_velocity: Vector3<T>, dt: T) -> Quaternion<T>
+    where T: Float
+{
+    quat_integrate(q, angular_velocity, dt)
+}
+
+impl<T> TransformSnapshot<T>
+    where T: Float
+{
+    /// Predicts the transform dt after the snapshot assuming constant velocities
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stones::transform::{Transform, TransformSnapshot};
+    /// use stones::quaternion::{quat_identity, quat_dot};
+    ///
+    /// let snapshot = TransformSnapshot {
+    ///     transform: Transform {
+    ///         translation: [1.0, 0.0, -2.0f64],
+    ///         rotation: quat_identity(),
+    ///         scale: [1.0, 1.0, 1.0],
+    ///     },
+    ///     linear_vel: [0.5, 0.25, 0.0],
+    ///     angular_vel: [0.0, 0.0, 3.0],
+    /// };
+    ///
+    /// // Constant velocity
+    /// let transform = snapshot.extrapolate(2.0);
+    /// assert_eq!(transform.translation, [2.0, 0.5, -2.0]);
+    /// assert_eq!(transform.scale, [1.0, 1.0, 1.0]);
+    ///
+    /// assert_eq!(snapshot.extrapolate(0.0), snapshot.transform);
+    ///
+    /// // Long extrapolations stay finite and the rotation stays a unit quaternion
+    /// let transform = snapshot.extrapolate(1e6);
+    /// assert!(transform.translation.iter().all(|c| c.is_finite()));
+    /// assert!(transform.rotation.iter().all(|c| c.is_finite()));
+    /// assert!((quat_dot(transform.rotation, transform.rotation) - 1.0).abs() < 1e-12);
+    /// ```
+    pub fn extrapolate(&self, dt: T) -> Transform<T> {
+        Transform {
+            translation: extrapolate_position(self.transform.translation, self.linear_vel, [T::zero(); 3], dt),
+            rotation: extrapolate_orientation(self.transform.rotation, self.angular_vel, dt),
+            scale: self.transform.scale,
+        }
+    }
+
+    /// Blends the snapshot towards another one for projective velocity blending
+    ///
+    /// The transforms and the velocities are interpolated, alpha = 0 giving self and
+    /// alpha = 1 giving other. Blending repeatedly towards an authoritative snapshot while
+    /// extrapolating both smoothly corrects a prediction error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stones::transform::{Transform, TransformSnapshot};
+    /// use stones::quaternion::{quat_from_axis_angle, quat_identity};
+    ///
+    /// let predicted = TransformSnapshot {
+    ///     transform: Transform {
+    ///         translation: [0.0, 0.0, 0.0f64],
+    ///         rotation: quat_identity(),
+    ///         scale: [1.0, 1.0, 1.0],
+    ///     },
+    ///     linear_vel: [1.0, 0.0, 0.0],
+    ///     angular_vel: [0.0, 0.0, 0.0],
+    /// };
+    /// let authoritative = TransformSnapshot {
+    ///     transform: Transform {
+    ///         translation: [2.0, 1.0, 0.0],
+    ///         rotation: quat_from_axis_angle([0.0, 1.0, 0.0], 1.0),
+    ///         scale: [1.0, 1.0, 1.0],
+    ///     },
+    ///     linear_vel: [0.0, 1.0, 0.0],
+    ///     angular_vel: [0.0, 0.5, 0.0],
+    /// };
+    ///
+    /// let halfway = predicted.blend_towards(&authoritative, 0.5);
+    /// assert_eq!(halfway.transform.translation, [1.0, 0.5, 0.0]);
+    /// assert_eq!(halfway.linear_vel, [0.5, 0.5, 0.0]);
+    /// assert_eq!(predicted.blend_towards(&authoritative, 0.0), predicted);
+    ///
+    /// // Blending every step converges to the authoritative snapshot
+    /// let (mut local, mut remote) = (predicted, authoritative);
+    /// for _ in 0..60 {
+    ///     let dt = 1.0 / 60.0;
+    ///     local = TransformSnapshot { transform: local.extrapolate(dt), ..local };
+    ///     remote = TransformSnapshot { transform: remote.extrapolate(dt), ..remote };
+    ///     local = local.blend_towards(&remote, 0.2);
+    /// }
+    /// for i in 0..3 {
+    ///     assert!((local.transform.translation[i] - remote.transform.translation[i]).abs() < 1e-4);
+    ///     assert!((local.linear_vel[i] - remote.linear_vel[i]).abs() < 1e-4);
+    /// }
+    /// for i in 0..4 {
+    ///     assert!((local.transform.rotation[i] - remote.transform.rotation[i]).abs() < 1e-4);
+    /// }
+    /// ```
+    pub fn blend_towards(&self, other: &TransformSnapshot<T>, alpha: T) -> TransformSnapshot<T> {
+        TransformSnapshot {
+            transform: Transform {
+                translation: lerp(self.transform.translation, other.transform.translation, alpha),
+                rotation: quat_slerp(self.transform.rotation, other.transform.rotation, alpha),
+                scale: lerp(self.transform.scale, other.transform.scale, alpha),
+            },
+            linear_vel: lerp(self.linear_vel, other.linear_vel, alpha),
+            angular_vel: lerp(self.angular_vel, other.angular_vel, alpha),
+        }
+    }
+}
+
+fn lerp<T>(from: Vector3<T>, to: Vector3<T>, t: T) -> Vector3<T>
+    where T: Float
+{
+    vec3_add(from, vec3_mul(vec3_sub(to, from), t))
+}
@@ -22,20 +22,24 @@
 * SOFTWARE.
 */
 
-use crate::number_traits::Zero;
-use std::ops::{Add, Sub, Mul};
+use crate::matrix::{Matrix2, Matrix3, Matrix4};
+use crate::number_traits::{Abs, Zero, Float};
+use std::ops::{Add, Sub, Mul, Div, Neg};
 
 pub type Vector2<T> = [T; 2];
 pub type Vector2i = Vector2<i32>;
 pub type Vector2f = Vector2<f32>;
+pub type Vector2d = Vector2<f64>;
 
 pub type Vector3<T> = [T; 3];
 pub type Vector3i = Vector3<i32>;
 pub type Vector3f = Vector3<f32>;
+pub type Vector3d = Vector3<f64>;
 
 pub type Vector4<T> = [T; 4];
 pub type Vector4i = Vector4<i32>;
 pub type Vector4f = Vector4<f32>;
+pub type Vector4d = Vector4<f64>;
 
 /// Adds two Vector2<T> together
 ///
@@ -228,6 +232,330 @@ pub fn vec4_mul<T>(lhs: Vector4<T>, rhs: T) -> Vector4<T>
     ]
 }
 
+/// Multiplies the corresponding components of two Vector2<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_hadamard;
+///
+/// assert_eq!(vec2_hadamard([5, 3], [2, -4]), [10, -12]);
+/// ```
+pub fn vec2_hadamard<T>(lhs: Vector2<T>, rhs: Vector2<T>) -> Vector2<T>
+    where T: Copy + Mul<Output=T>
+{
+    vec2_zip_with(lhs, rhs, |a, b| a * b)
+}
+
+/// Multiplies the corresponding components of two Vector3<T>
+///
+/// This applies a non-uniform scale to a position, or modulates a color by another one.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_hadamard, vec3_dot};
+///
+/// assert_eq!(vec3_hadamard([5, 3, 7], [2, -4, 0]), [10, -12, 0]);
+///
+/// // The dot product is the sum of the components of the Hadamard product
+/// let (a, b) = ([0.6, -0.8, 2.0], [0.0, 1.0, 1.0]);
+/// let product = vec3_hadamard(a, b);
+/// assert_eq!(vec3_dot(a, b), product[0] + product[1] + product[2]);
+/// ```
+pub fn vec3_hadamard<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> Vector3<T>
+    where T: Copy + Mul<Output=T>
+{
+    vec3_zip_with(lhs, rhs, |a, b| a * b)
+}
+
+/// Multiplies the corresponding components of two Vector4<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_hadamard;
+///
+/// // Modulating a color with alpha
+/// assert_eq!(vec4_hadamard([1.0, 0.5, 0.25, 1.0], [0.5, 0.5, 1.0, 0.5]), [0.5, 0.25, 0.25, 0.5]);
+/// assert_eq!(vec4_hadamard([5, 3, 7, 2], [2, -4, 0, 1]), [10, -12, 0, 2]);
+/// ```
+pub fn vec4_hadamard<T>(lhs: Vector4<T>, rhs: Vector4<T>) -> Vector4<T>
+    where T: Copy + Mul<Output=T>
+{
+    vec4_zip_with(lhs, rhs, |a, b| a * b)
+}
+
+/// Divides a Vector2<T> by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_div;
+///
+/// assert_eq!(vec2_div([10, 7], 2), [5, 3]);
+/// assert_eq!(vec2_div([1.0, -3.0], 4.0), [0.25, -0.75]);
+/// ```
+pub fn vec2_div<T>(lhs: Vector2<T>, rhs: T) -> Vector2<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs,
+        lhs[1] / rhs
+    ]
+}
+
+/// Divides a Vector3<T> by a scalar
+///
+/// Integer components are truncated towards zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_div, vec3_mul};
+///
+/// assert_eq!(vec3_div([10, 7, -7], 2), [5, 3, -3]);
+///
+/// let v = [0.1f64, -2.5, 1e10];
+/// for &s in &[2.0, 0.5, -8.0] {
+///     assert_eq!(vec3_div(vec3_mul(v, s), s), v);
+/// }
+/// ```
+pub fn vec3_div<T>(lhs: Vector3<T>, rhs: T) -> Vector3<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs,
+        lhs[1] / rhs,
+        lhs[2] / rhs
+    ]
+}
+
+/// Divides a Vector4<T> by a scalar
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_div;
+///
+/// assert_eq!(vec4_div([10, 7, -7, 0], 2), [5, 3, -3, 0]);
+/// assert_eq!(vec4_div([1.0, -3.0, 2.0, 8.0], 4.0), [0.25, -0.75, 0.5, 2.0]);
+/// ```
+pub fn vec4_div<T>(lhs: Vector4<T>, rhs: T) -> Vector4<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs,
+        lhs[1] / rhs,
+        lhs[2] / rhs,
+        lhs[3] / rhs
+    ]
+}
+
+/// Divides the corresponding components of two Vector2<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_div_componentwise;
+///
+/// assert_eq!(vec2_div_componentwise([10, 7], [2, -3]), [5, -2]);
+/// ```
+pub fn vec2_div_componentwise<T>(lhs: Vector2<T>, rhs: Vector2<T>) -> Vector2<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs[0],
+        lhs[1] / rhs[1]
+    ]
+}
+
+/// Divides the corresponding components of two Vector3<T>
+///
+/// Dividing a float by zero yields an infinity or NaN, see `vec3_div_checked` to detect the
+/// zero divisors.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_div_componentwise;
+///
+/// assert_eq!(vec3_div_componentwise([10, 7, -7], [2, 3, 2]), [5, 2, -3]);
+/// assert_eq!(vec3_div_componentwise([1.0, 3.0, 2.0], [4.0, -2.0, 0.0]), [0.25, -1.5, f64::INFINITY]);
+/// ```
+pub fn vec3_div_componentwise<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> Vector3<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs[0],
+        lhs[1] / rhs[1],
+        lhs[2] / rhs[2]
+    ]
+}
+
+/// Divides the corresponding components of two Vector4<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_div_componentwise;
+///
+/// assert_eq!(vec4_div_componentwise([10, 7, -7, 0], [2, 3, 2, 5]), [5, 2, -3, 0]);
+/// ```
+pub fn vec4_div_componentwise<T>(lhs: Vector4<T>, rhs: Vector4<T>) -> Vector4<T>
+    where T: Copy + Div<Output=T>
+{
+    [
+        lhs[0] / rhs[0],
+        lhs[1] / rhs[1],
+        lhs[2] / rhs[2],
+        lhs[3] / rhs[3]
+    ]
+}
+
+/// Divides the corresponding components of two Vector3<T>, returns None if a component of
+/// the divisor is zero
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_div_checked;
+///
+/// assert_eq!(vec3_div_checked([1.0, 3.0, 2.0], [4.0, -2.0, 0.5]), Some([0.25, -1.5, 4.0]));
+/// assert_eq!(vec3_div_checked([1.0, 3.0, 2.0], [4.0, -0.0, 0.5]), None);
+/// assert_eq!(vec3_div_checked([10, 7, -7], [2, 0, 2]), None);
+/// ```
+pub fn vec3_div_checked<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> Option<Vector3<T>>
+    where T: Copy + PartialEq + Zero + Div<Output=T>
+{
+    if rhs.iter().any(|&divisor| divisor == T::zero()) {
+        return None;
+    }
+
+    Some(vec3_div_componentwise(lhs, rhs))
+}
+
+/// Negates every component of a Vector2<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_neg;
+///
+/// assert_eq!(vec2_neg([5, -3]), [-5, 3]);
+/// assert_eq!(vec2_neg([0.5, -1.0]), [-0.5, 1.0]);
+/// ```
+pub fn vec2_neg<T>(v: Vector2<T>) -> Vector2<T>
+    where T: Copy + Neg<Output=T>
+{
+    [
+        -v[0],
+        -v[1]
+    ]
+}
+
+/// Negates every component of a Vector3<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_neg, vec3_add};
+///
+/// let v = [5, -3, 7];
+/// assert_eq!(vec3_neg(v), [-5, 3, -7]);
+/// assert_eq!(vec3_add(v, vec3_neg(v)), [0, 0, 0]);
+///
+/// let v = [0.25f32, -1.5, 3.0];
+/// assert_eq!(vec3_neg(v), [-0.25, 1.5, -3.0]);
+/// assert_eq!(vec3_add(v, vec3_neg(v)), [0.0, 0.0, 0.0]);
+/// ```
+pub fn vec3_neg<T>(v: Vector3<T>) -> Vector3<T>
+    where T: Copy + Neg<Output=T>
+{
+    [
+        -v[0],
+        -v[1],
+        -v[2]
+    ]
+}
+
+/// Negates every component of a Vector4<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_neg;
+///
+/// assert_eq!(vec4_neg([5, -3, 7, 0]), [-5, 3, -7, 0]);
+/// assert_eq!(vec4_neg([0.5, -1.0, 2.0, -0.0]), [-0.5, 1.0, -2.0, 0.0]);
+/// ```
+pub fn vec4_neg<T>(v: Vector4<T>) -> Vector4<T>
+    where T: Copy + Neg<Output=T>
+{
+    [
+        -v[0],
+        -v[1],
+        -v[2],
+        -v[3]
+    ]
+}
+
+/// Computes the absolute value of every component of a Vector2<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_abs;
+///
+/// assert_eq!(vec2_abs([-5, 3]), [5, 3]);
+/// assert_eq!(vec2_abs([0.5, -1.0]), [0.5, 1.0]);
+/// ```
+pub fn vec2_abs<T>(v: Vector2<T>) -> Vector2<T>
+    where T: Copy + Abs
+{
+    vec2_map(v, Abs::abs)
+}
+
+/// Computes the absolute value of every component of a Vector3<T>
+///
+/// Integer components saturate, see `Abs`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_abs;
+///
+/// assert_eq!(vec3_abs([-5, -3, -7]), [5, 3, 7]);
+/// assert_eq!(vec3_abs([5, 3, 7]), [5, 3, 7]);
+/// assert_eq!(vec3_abs([-5, 0, 7]), [5, 0, 7]);
+/// assert_eq!(vec3_abs([i32::MIN, i32::MAX, -1]), [i32::MAX, i32::MAX, 1]);
+///
+/// let v = vec3_abs([-0.0f32, -2.5, 1.0]);
+/// assert_eq!(v, [0.0, 2.5, 1.0]);
+/// assert!(v[0].is_sign_positive());
+/// ```
+pub fn vec3_abs<T>(v: Vector3<T>) -> Vector3<T>
+    where T: Copy + Abs
+{
+    vec3_map(v, Abs::abs)
+}
+
+/// Computes the absolute value of every component of a Vector4<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_abs;
+///
+/// assert_eq!(vec4_abs([-5i64, 3, -7, i64::MIN]), [5, 3, 7, i64::MAX]);
+/// assert_eq!(vec4_abs([-0.5, 1.0, -0.0, 2.0]), [0.5, 1.0, 0.0, 2.0]);
+/// ```
+pub fn vec4_abs<T>(v: Vector4<T>) -> Vector4<T>
+    where T: Copy + Abs
+{
+    vec4_map(v, Abs::abs)
+}
+
 /// Calculates the dot product of two Vector2<T>
 ///
 /// # Examples
@@ -300,10 +628,1343 @@ pub fn vec3_cross<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> Vector3<T>
     ]
 }
 
+/// Calculates the squared length of a Vector2<T>, the dot product of the vector with itself
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec2_length_squared, vec2_dot};
+///
+/// assert_eq!(vec2_length_squared([3, 4]), 25);
+/// assert_eq!(vec2_length_squared([0, 0]), 0);
+/// assert_eq!(vec2_length_squared([-1.5, 2.0]), vec2_dot([-1.5, 2.0], [-1.5, 2.0]));
+/// ```
+pub fn vec2_length_squared<T>(v: Vector2<T>) -> T
+    where T: Zero + Copy + Mul<Output=T> + Add<Output=T>
+{
+    vec2_dot(v, v)
+}
 
-fn dot_product<T>(lhs: std::slice::Iter<T>, rhs: std::slice::Iter<T>) -> T 
+/// Calculates the squared length of a Vector3<T>, the dot product of the vector with itself
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_length_squared, vec3_dot};
+///
+/// assert_eq!(vec3_length_squared([3, 0, -4]), 25);
+/// assert_eq!(vec3_length_squared([0, 0, 0]), 0);
+/// assert_eq!(vec3_length_squared([1.0, -2.0, 0.5]), vec3_dot([1.0, -2.0, 0.5], [1.0, -2.0, 0.5]));
+/// ```
+pub fn vec3_length_squared<T>(v: Vector3<T>) -> T
     where T: Zero + Copy + Mul<Output=T> + Add<Output=T>
 {
-    lhs.zip(rhs)
-        .fold(T::zero(), |acc, (&a, &b)| acc + a * b)
+    vec3_dot(v, v)
+}
+
+/// Calculates the squared length of a Vector4<T>, the dot product of the vector with itself
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec4_length_squared, vec4_dot};
+///
+/// assert_eq!(vec4_length_squared([1, 2, 3, 4]), 30);
+/// assert_eq!(vec4_length_squared([0, 0, 0, 0]), 0);
+/// assert_eq!(vec4_length_squared([1.0, -2.0, 0.5, 3.0]), vec4_dot([1.0, -2.0, 0.5, 3.0], [1.0, -2.0, 0.5, 3.0]));
+/// ```
+pub fn vec4_length_squared<T>(v: Vector4<T>) -> T
+    where T: Zero + Copy + Mul<Output=T> + Add<Output=T>
+{
+    vec4_dot(v, v)
+}
+
+/// Calculates the length of a Vector2<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_length;
+///
+/// assert_eq!(vec2_length([3.0, 4.0]), 5.0);
+/// assert_eq!(vec2_length([0.0f32, 0.0]), 0.0);
+/// ```
+pub fn vec2_length<T>(v: Vector2<T>) -> T
+    where T: Float
+{
+    vec2_length_squared(v).sqrt()
+}
+
+/// Calculates the length of a Vector3<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_length;
+///
+/// assert_eq!(vec3_length([3.0, 0.0, -4.0]), 5.0);
+/// assert_eq!(vec3_length([2.0f32, 3.0, 6.0]), 7.0);
+/// assert_eq!(vec3_length([0.0f64, 0.0, 0.0]), 0.0);
+/// ```
+pub fn vec3_length<T>(v: Vector3<T>) -> T
+    where T: Float
+{
+    vec3_length_squared(v).sqrt()
+}
+
+/// Calculates the length of a Vector4<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_length;
+///
+/// assert_eq!(vec4_length([3.0, 0.0, 4.0, 0.0]), 5.0);
+/// assert_eq!(vec4_length([1.0f32, 1.0, 1.0, 1.0]), 2.0);
+/// assert_eq!(vec4_length([0.0f64; 4]), 0.0);
+/// ```
+pub fn vec4_length<T>(v: Vector4<T>) -> T
+    where T: Float
+{
+    vec4_length_squared(v).sqrt()
+}
+
+/// Computes the direction of a ray refracted through a surface, following Snell's law
+///
+/// `incident` is the direction of the incoming ray and `normal` the normal of the surface
+/// facing the incoming ray, both must be normalized. `eta` is the ratio of the refractive
+/// index of the incoming medium over the one of the outgoing medium. Returns None on total
+/// internal reflection, i.e. when the ray doesn't leave the incoming medium.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_refract;
+///
+/// let incident = [0.5f64.sqrt(), -0.5f64.sqrt(), 0.0];
+/// let normal = [0.0, 1.0, 0.0];
+///
+/// // Same medium on both sides
+/// let refracted = vec3_refract(incident, normal, 1.0).unwrap();
+/// for i in 0..3 {
+///     assert!((refracted[i] - incident[i]).abs() < 1e-12);
+/// }
+///
+/// // From air to glass, the sine of the angle is divided by 1.5
+/// let refracted = vec3_refract(incident, normal, 1.0 / 1.5).unwrap();
+/// let expected = [2f64.sqrt() / 3.0, -7f64.sqrt() / 3.0, 0.0];
+/// for i in 0..3 {
+///     assert!((refracted[i] - expected[i]).abs() < 1e-12);
+/// }
+///
+/// // From glass to air beyond the critical angle of about 41.8°
+/// let (sin, cos) = 60f64.to_radians().sin_cos();
+/// assert_eq!(vec3_refract([sin, -cos, 0.0], normal, 1.5), None);
+/// let (sin, cos) = 40f64.to_radians().sin_cos();
+/// assert!(vec3_refract([sin, -cos, 0.0], normal, 1.5).is_some());
+/// ```
+pub fn vec3_refract<T>(incident: Vector3<T>, normal: Vector3<T>, eta: T) -> Option<Vector3<T>>
+    where T: Float
+{
+    debug_assert!((vec3_length_squared(incident) - T::one()).abs() <= T::epsilon().sqrt(),
+                  "the incident direction must be normalized");
+    debug_assert!((vec3_length_squared(normal) - T::one()).abs() <= T::epsilon().sqrt(),
+                  "the normal must be normalized");
+
+    let cos_incident = vec3_dot(normal, incident);
+    let k = T::one() - eta * eta * (T::one() - cos_incident * cos_incident);
+    if k < T::zero() {
+        return None;
+    }
+
+    Some(vec3_sub(vec3_mul(incident, eta), vec3_mul(normal, eta * cos_incident + k.sqrt())))
+}
+
+/// Projects a Vector2<T> onto another one, returning the component of `a` along `b`
+///
+/// The result is b * dot(a, b) / dot(b, b), which isn't finite if `b` is zero, see
+/// `vec2_project_onto_checked`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_project_onto;
+///
+/// assert_eq!(vec2_project_onto([3.0, -4.0], [0.0, 1.0]), [0.0, -4.0]);
+/// assert_eq!(vec2_project_onto([3.0, 1.0], [2.0, 2.0]), [2.0, 2.0]);
+/// ```
+pub fn vec2_project_onto<T>(a: Vector2<T>, b: Vector2<T>) -> Vector2<T>
+    where T: Float
+{
+    vec2_mul(b, vec2_dot(a, b) / vec2_dot(b, b))
+}
+
+/// Projects a Vector3<T> onto another one, returning the component of `a` along `b`
+///
+/// The result is b * dot(a, b) / dot(b, b), which isn't finite if `b` is zero, see
+/// `vec3_project_onto_checked`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_project_onto, vec3_cross};
+///
+/// // Onto an axis
+/// assert_eq!(vec3_project_onto([3.0, -4.0, 5.0], [1.0, 0.0, 0.0]), [3.0, 0.0, 0.0]);
+/// assert_eq!(vec3_project_onto([3.0, -4.0, 5.0], [0.0, -2.0, 0.0]), [0.0, -4.0, 0.0]);
+///
+/// // The projection is parallel to b
+/// let b = [1.0f64, 2.0, -0.5];
+/// let projection = vec3_project_onto([-3.0, 0.5, 4.0], b);
+/// assert!(vec3_cross(projection, b).iter().all(|x| x.abs() < 1e-12));
+/// ```
+pub fn vec3_project_onto<T>(a: Vector3<T>, b: Vector3<T>) -> Vector3<T>
+    where T: Float
+{
+    vec3_mul(b, vec3_dot(a, b) / vec3_dot(b, b))
+}
+
+/// Projects a Vector4<T> onto another one, returning the component of `a` along `b`
+///
+/// The result is b * dot(a, b) / dot(b, b), which isn't finite if `b` is zero, see
+/// `vec4_project_onto_checked`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_project_onto;
+///
+/// assert_eq!(vec4_project_onto([3.0, -4.0, 5.0, 6.0], [0.0, 0.0, 0.0, 1.0]), [0.0, 0.0, 0.0, 6.0]);
+/// assert_eq!(vec4_project_onto([1.0, 2.0, 3.0, 4.0], [1.0, 1.0, 1.0, 1.0]), [2.5, 2.5, 2.5, 2.5]);
+/// ```
+pub fn vec4_project_onto<T>(a: Vector4<T>, b: Vector4<T>) -> Vector4<T>
+    where T: Float
+{
+    vec4_mul(b, vec4_dot(a, b) / vec4_dot(b, b))
+}
+
+/// Projects a Vector2<T> onto another one, returns None if `b` is zero
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_project_onto_checked;
+///
+/// assert_eq!(vec2_project_onto_checked([3.0, -4.0], [0.0, 2.0]), Some([0.0, -4.0]));
+/// assert_eq!(vec2_project_onto_checked([3.0, -4.0], [0.0, 0.0]), None);
+/// ```
+pub fn vec2_project_onto_checked<T>(a: Vector2<T>, b: Vector2<T>) -> Option<Vector2<T>>
+    where T: Float
+{
+    if vec2_dot(b, b) == T::zero() {
+        return None;
+    }
+
+    Some(vec2_project_onto(a, b))
+}
+
+/// Projects a Vector3<T> onto another one, returns None if `b` is zero
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_project_onto_checked;
+///
+/// assert_eq!(vec3_project_onto_checked([3.0, -4.0, 5.0], [0.0, 0.0, 3.0]), Some([0.0, 0.0, 5.0]));
+/// assert_eq!(vec3_project_onto_checked([3.0, -4.0, 5.0], [0.0, 0.0, 0.0]), None);
+/// ```
+pub fn vec3_project_onto_checked<T>(a: Vector3<T>, b: Vector3<T>) -> Option<Vector3<T>>
+    where T: Float
+{
+    if vec3_dot(b, b) == T::zero() {
+        return None;
+    }
+
+    Some(vec3_project_onto(a, b))
+}
+
+/// Projects a Vector4<T> onto another one, returns None if `b` is zero
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_project_onto_checked;
+///
+/// assert_eq!(vec4_project_onto_checked([3.0, -4.0, 5.0, 6.0], [-1.0, 0.0, 0.0, 0.0]), Some([3.0, 0.0, 0.0, 0.0]));
+/// assert_eq!(vec4_project_onto_checked([3.0, -4.0, 5.0, 6.0], [0.0; 4]), None);
+/// ```
+pub fn vec4_project_onto_checked<T>(a: Vector4<T>, b: Vector4<T>) -> Option<Vector4<T>>
+    where T: Float
+{
+    if vec4_dot(b, b) == T::zero() {
+        return None;
+    }
+
+    Some(vec4_project_onto(a, b))
+}
+
+/// Computes the rejection of a Vector2<T> from another one, the component of `a`
+/// perpendicular to `b`
+///
+/// The result is a minus its projection onto `b`, which isn't finite if `b` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec2_reject_from, vec2_project_onto, vec2_add};
+///
+/// let (a, b) = ([3.0, 1.0], [2.0, 2.0]);
+/// assert_eq!(vec2_reject_from(a, b), [1.0, -1.0]);
+/// assert_eq!(vec2_add(vec2_project_onto(a, b), vec2_reject_from(a, b)), a);
+/// ```
+pub fn vec2_reject_from<T>(a: Vector2<T>, b: Vector2<T>) -> Vector2<T>
+    where T: Float
+{
+    vec2_sub(a, vec2_project_onto(a, b))
+}
+
+/// Computes the rejection of a Vector3<T> from another one, the component of `a`
+/// perpendicular to `b`
+///
+/// The result is a minus its projection onto `b`, which isn't finite if `b` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_reject_from, vec3_project_onto, vec3_add, vec3_dot};
+///
+/// let (a, b) = ([-3.0f64, 0.5, 4.0], [1.0, 2.0, -0.5]);
+/// let rejection = vec3_reject_from(a, b);
+/// assert!(vec3_dot(rejection, b).abs() < 1e-12);
+/// let reconstructed = vec3_add(vec3_project_onto(a, b), rejection);
+/// for i in 0..3 {
+///     assert!((reconstructed[i] - a[i]).abs() < 1e-12);
+/// }
+///
+/// // Parallel vectors
+/// assert_eq!(vec3_reject_from([2.0, -4.0, 6.0], [-1.0, 2.0, -3.0]), [0.0, 0.0, 0.0]);
+/// ```
+pub fn vec3_reject_from<T>(a: Vector3<T>, b: Vector3<T>) -> Vector3<T>
+    where T: Float
+{
+    vec3_sub(a, vec3_project_onto(a, b))
+}
+
+/// Computes the rejection of a Vector4<T> from another one, the component of `a`
+/// perpendicular to `b`
+///
+/// The result is a minus its projection onto `b`, which isn't finite if `b` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec4_reject_from, vec4_dot};
+///
+/// let b = [1.0, 1.0, 1.0, 1.0];
+/// let rejection = vec4_reject_from([1.0, 2.0, 3.0, 4.0], b);
+/// assert_eq!(rejection, [-1.5, -0.5, 0.5, 1.5]);
+/// assert_eq!(vec4_dot(rejection, b), 0.0);
+/// ```
+pub fn vec4_reject_from<T>(a: Vector4<T>, b: Vector4<T>) -> Vector4<T>
+    where T: Float
+{
+    vec4_sub(a, vec4_project_onto(a, b))
+}
+
+/// Computes the unsigned angle in radians between two Vector2<T>, in [0, π]
+///
+/// The angle is computed from the cross and dot products with atan2, which stays accurate for
+/// nearly parallel and nearly opposite vectors. The angle with a zero vector is 0.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_angle_between;
+/// use std::f64::consts::{PI, FRAC_PI_2};
+///
+/// assert!((vec2_angle_between([2.0, 0.0], [0.0, -3.0]) - FRAC_PI_2).abs() < 1e-12);
+/// assert_eq!(vec2_angle_between([1.0, 1.0], [-2.0, -2.0]), PI);
+///
+/// // Nearly parallel
+/// let angle = vec2_angle_between([1.0, 1e-9], [1.0f64, 0.0]);
+/// assert!((angle - 1e-9).abs() < 1e-15);
+/// ```
+pub fn vec2_angle_between<T>(a: Vector2<T>, b: Vector2<T>) -> T
+    where T: Float
+{
+    let cross = a[0] * b[1] - a[1] * b[0];
+    cross.abs().atan2(vec2_dot(a, b))
+}
+
+/// Computes the unsigned angle in radians between two Vector3<T>, in [0, π]
+///
+/// The angle is computed from the cross and dot products with atan2, which stays accurate for
+/// nearly parallel and nearly opposite vectors. The angle with a zero vector is 0.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_angle_between;
+/// use std::f32::consts::{PI, FRAC_PI_2};
+///
+/// assert!((vec3_angle_between([1.0, 2.0, 0.0], [0.0, 0.0, 5.0]) - FRAC_PI_2).abs() < 1e-6);
+/// assert_eq!(vec3_angle_between([1.0, -2.0, 3.0], [-1.0, 2.0, -3.0]), PI);
+/// assert_eq!(vec3_angle_between([1.0f32, -2.0, 3.0], [2.0, -4.0, 6.0]), 0.0);
+///
+/// // Nearly parallel, where acos of the normalized dot product would return 0 or NaN
+/// let angle = vec3_angle_between([1.0, 1e-4, 0.0], [1.0f32, 0.0, 0.0]);
+/// assert!((angle - 1e-4).abs() < 1e-9);
+/// ```
+pub fn vec3_angle_between<T>(a: Vector3<T>, b: Vector3<T>) -> T
+    where T: Float
+{
+    vec3_length(vec3_cross(a, b)).atan2(vec3_dot(a, b))
+}
+
+/// Computes the signed angle in radians from a Vector2<T> to another one, in (-π, π]
+///
+/// The angle is positive if the rotation from `a` to `b` is counter-clockwise with the Y axis
+/// pointing up. The angle with a zero vector is 0.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_signed_angle;
+/// use std::f64::consts::{PI, FRAC_PI_2};
+///
+/// assert_eq!(vec2_signed_angle([1.0, 0.0], [0.0, 1.0]), FRAC_PI_2);
+/// assert_eq!(vec2_signed_angle([1.0, 0.0], [0.0, -1.0]), -FRAC_PI_2);
+/// assert!((vec2_signed_angle([0.0, 2.0], [-1.0, -1.0]) - 0.75 * PI).abs() < 1e-12);
+///
+/// // Opposite vectors give π rather than -π
+/// assert_eq!(vec2_signed_angle([1.0, 0.0], [-1.0, 0.0]), PI);
+/// assert_eq!(vec2_signed_angle([-1.0, 0.0], [1.0, 0.0]), PI);
+/// ```
+pub fn vec2_signed_angle<T>(a: Vector2<T>, b: Vector2<T>) -> T
+    where T: Float
+{
+    // atan2 returns -π for a negative zero cross product
+    let pi = T::from_f64(std::f64::consts::PI);
+    let angle = (a[0] * b[1] - a[1] * b[0]).atan2(vec2_dot(a, b));
+    if angle <= -pi { pi } else { angle }
+}
+
+/// Clamps every component of a Vector2<T> between the matching components of `min` and `max`
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_clamp;
+///
+/// assert_eq!(vec2_clamp([5, -3], [0, 0], [4, 4]), [4, 0]);
+/// assert_eq!(vec2_clamp([0.5, 0.25], [0.0, 0.0], [1.0, 1.0]), [0.5, 0.25]);
+/// ```
+pub fn vec2_clamp<T>(v: Vector2<T>, min: Vector2<T>, max: Vector2<T>) -> Vector2<T>
+    where T: Copy + PartialOrd
+{
+    [
+        clamp(v[0], min[0], max[0]),
+        clamp(v[1], min[1], max[1])
+    ]
+}
+
+/// Clamps every component of a Vector3<T> between the matching components of `min` and `max`
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_clamp;
+///
+/// let (min, max) = ([-1.0, 0.0, 2.0], [1.0, 10.0, 3.0]);
+/// assert_eq!(vec3_clamp([0.5, 5.0, 2.5], min, max), [0.5, 5.0, 2.5]);
+/// assert_eq!(vec3_clamp([-4.0, 5.0, 7.0], min, max), [-1.0, 5.0, 3.0]);
+/// assert_eq!(vec3_clamp([300, -20, 128], [0; 3], [255; 3]), [255, 0, 128]);
+/// ```
+pub fn vec3_clamp<T>(v: Vector3<T>, min: Vector3<T>, max: Vector3<T>) -> Vector3<T>
+    where T: Copy + PartialOrd
+{
+    [
+        clamp(v[0], min[0], max[0]),
+        clamp(v[1], min[1], max[1]),
+        clamp(v[2], min[2], max[2])
+    ]
+}
+
+/// Clamps every component of a Vector4<T> between the matching components of `min` and `max`
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_clamp;
+///
+/// assert_eq!(vec4_clamp([1.5, -0.5, 0.5, 1.0], [0.0; 4], [1.0; 4]), [1.0, 0.0, 0.5, 1.0]);
+/// ```
+pub fn vec4_clamp<T>(v: Vector4<T>, min: Vector4<T>, max: Vector4<T>) -> Vector4<T>
+    where T: Copy + PartialOrd
+{
+    [
+        clamp(v[0], min[0], max[0]),
+        clamp(v[1], min[1], max[1]),
+        clamp(v[2], min[2], max[2]),
+        clamp(v[3], min[3], max[3])
+    ]
+}
+
+/// Rescales a Vector3<T> to the length `max_length` if it is longer, shorter vectors are
+/// returned unchanged
+///
+/// The vector is divided by its largest component before computing its length, so huge
+/// vectors don't overflow. `max_length` must not be negative.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_clamp_length, vec3_length};
+///
+/// assert_eq!(vec3_clamp_length([3.0, 0.0, -4.0], 10.0), [3.0, 0.0, -4.0]);
+/// assert_eq!(vec3_clamp_length([3.0, 0.0, -4.0], 5.0), [3.0, 0.0, -4.0]);
+/// assert_eq!(vec3_clamp_length([6.0, 0.0, -8.0], 5.0), [3.0, 0.0, -4.0]);
+/// assert_eq!(vec3_clamp_length([0.0, 0.0, 0.0], 1.0), [0.0, 0.0, 0.0]);
+///
+/// let clamped = vec3_clamp_length([1.0f32, -2.0, 3.5], 0.5);
+/// assert!((vec3_length(clamped) - 0.5).abs() < 1e-6);
+///
+/// // Huge vectors whose squared length overflows
+/// assert_eq!(vec3_clamp_length([1e20f32, 0.0, 0.0], 1.0), [1.0, 0.0, 0.0]);
+/// let clamped = vec3_clamp_length([3e38f32, -3e38, 3e38], 2.0);
+/// assert!((vec3_length(clamped) - 2.0).abs() < 1e-6);
+/// ```
+pub fn vec3_clamp_length<T>(v: Vector3<T>, max_length: T) -> Vector3<T>
+    where T: Float
+{
+    debug_assert!(max_length >= T::zero(), "the maximum length must not be negative");
+
+    let largest = v.iter().fold(T::zero(), |largest, &x| if x.abs() > largest { x.abs() } else { largest });
+    if largest == T::zero() {
+        return v;
+    }
+
+    let scaled = vec3_mul(v, T::one() / largest);
+    let scaled_length = vec3_length(scaled);
+    if scaled_length <= max_length / largest {
+        return v;
+    }
+
+    vec3_mul(scaled, max_length / scaled_length)
+}
+
+/// Computes the componentwise minimum of two Vector2<T>
+///
+/// Like `f32::min`, if one of the components is NaN the other one is returned. For equal
+/// components, the one of `lhs` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_min;
+///
+/// assert_eq!(vec2_min([1, 5], [3, -2]), [1, -2]);
+/// assert_eq!(vec2_min([f64::NAN, 5.0], [3.0, f64::NAN]), [3.0, 5.0]);
+/// ```
+pub fn vec2_min<T>(lhs: Vector2<T>, rhs: Vector2<T>) -> Vector2<T>
+    where T: Copy + PartialOrd
+{
+    vec2_zip_with(lhs, rhs, min)
+}
+
+/// Computes the componentwise minimum of two Vector3<T>
+///
+/// Like `f32::min`, if one of the components is NaN the other one is returned. For equal
+/// components, the one of `lhs` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_min;
+///
+/// assert_eq!(vec3_min([1, 5, 4], [3, -2, 4]), [1, -2, 4]);
+/// assert_eq!(vec3_min([0.5, 2.0, -1.0], [0.25, 3.0, -1.0]), [0.25, 2.0, -1.0]);
+///
+/// // NaN components are ignored
+/// let nan = f32::NAN;
+/// assert_eq!(vec3_min([nan, 2.0, 1.0], [1.0, nan, 0.0]), [1.0, 2.0, 0.0]);
+/// assert!(vec3_min([nan, 0.0, 0.0], [nan, 0.0, 0.0])[0].is_nan());
+/// ```
+pub fn vec3_min<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> Vector3<T>
+    where T: Copy + PartialOrd
+{
+    vec3_zip_with(lhs, rhs, min)
+}
+
+/// Computes the componentwise minimum of two Vector4<T>
+///
+/// Like `f32::min`, if one of the components is NaN the other one is returned. For equal
+/// components, the one of `lhs` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_min;
+///
+/// assert_eq!(vec4_min([1, 5, 4, -7], [3, -2, 4, 0]), [1, -2, 4, -7]);
+/// assert_eq!(vec4_min([f64::NAN, 1.0, 2.0, 3.0], [0.0; 4]), [0.0; 4]);
+/// ```
+pub fn vec4_min<T>(lhs: Vector4<T>, rhs: Vector4<T>) -> Vector4<T>
+    where T: Copy + PartialOrd
+{
+    vec4_zip_with(lhs, rhs, min)
+}
+
+/// Computes the componentwise maximum of two Vector2<T>
+///
+/// Like `f32::max`, if one of the components is NaN the other one is returned. For equal
+/// components, the one of `lhs` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_max;
+///
+/// assert_eq!(vec2_max([1, 5], [3, -2]), [3, 5]);
+/// assert_eq!(vec2_max([f64::NAN, 5.0], [3.0, f64::NAN]), [3.0, 5.0]);
+/// ```
+pub fn vec2_max<T>(lhs: Vector2<T>, rhs: Vector2<T>) -> Vector2<T>
+    where T: Copy + PartialOrd
+{
+    vec2_zip_with(lhs, rhs, max)
+}
+
+/// Computes the componentwise maximum of two Vector3<T>
+///
+/// Like `f32::max`, if one of the components is NaN the other one is returned. For equal
+/// components, the one of `lhs` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_max, vec3_min};
+///
+/// assert_eq!(vec3_max([1, 5, 4], [3, -2, 4]), [3, 5, 4]);
+///
+/// // Growing a bounding box
+/// let (mut min, mut max) = ([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+/// for &point in &[[2.0, 0.5, -1.0], [-0.5, 3.0, 0.5]] {
+///     min = vec3_min(min, point);
+///     max = vec3_max(max, point);
+/// }
+/// assert_eq!((min, max), ([-0.5, 0.0, -1.0], [2.0, 3.0, 1.0]));
+///
+/// // NaN components are ignored
+/// let nan = f32::NAN;
+/// assert_eq!(vec3_max([nan, 2.0, 1.0], [1.0, nan, 0.0]), [1.0, 2.0, 1.0]);
+/// ```
+pub fn vec3_max<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> Vector3<T>
+    where T: Copy + PartialOrd
+{
+    vec3_zip_with(lhs, rhs, max)
+}
+
+/// Computes the componentwise maximum of two Vector4<T>
+///
+/// Like `f32::max`, if one of the components is NaN the other one is returned. For equal
+/// components, the one of `lhs` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_max;
+///
+/// assert_eq!(vec4_max([1, 5, 4, -7], [3, -2, 4, 0]), [3, 5, 4, 0]);
+/// assert_eq!(vec4_max([f64::NAN, 1.0, 2.0, 3.0], [0.0; 4]), [0.0, 1.0, 2.0, 3.0]);
+/// ```
+pub fn vec4_max<T>(lhs: Vector4<T>, rhs: Vector4<T>) -> Vector4<T>
+    where T: Copy + PartialOrd
+{
+    vec4_zip_with(lhs, rhs, max)
+}
+
+/// Computes the outer product of two Vector2<T>, the matrix whose component (i, j) is
+/// lhs[i] * rhs[j]
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec2_outer;
+///
+/// assert_eq!(vec2_outer([1, 2], [3, 4]), [3, 4,
+///                                         6, 8]);
+/// ```
+pub fn vec2_outer<T>(lhs: Vector2<T>, rhs: Vector2<T>) -> Matrix2<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs[0], lhs[0] * rhs[1],
+        lhs[1] * rhs[0], lhs[1] * rhs[1]
+    ]
+}
+
+/// Computes the outer product of two Vector3<T>, the matrix whose component (i, j) is
+/// lhs[i] * rhs[j]
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_outer, vec3_mul, vec3_dot};
+/// use stones::matrix::mat3_transform_vec;
+///
+/// let (a, b, c) = ([1, 2, 3], [4, -5, 6], [-1, 2, 7]);
+/// assert_eq!(vec3_outer(a, b), [4, -5, 6,
+///                               8, -10, 12,
+///                               12, -15, 18]);
+///
+/// // (a bᵀ) c = a (b · c)
+/// assert_eq!(mat3_transform_vec(vec3_outer(a, b), c), vec3_mul(a, vec3_dot(b, c)));
+/// ```
+pub fn vec3_outer<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> Matrix3<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs[0], lhs[0] * rhs[1], lhs[0] * rhs[2],
+        lhs[1] * rhs[0], lhs[1] * rhs[1], lhs[1] * rhs[2],
+        lhs[2] * rhs[0], lhs[2] * rhs[1], lhs[2] * rhs[2]
+    ]
+}
+
+/// Computes the outer product of two Vector4<T>, the matrix whose component (i, j) is
+/// lhs[i] * rhs[j]
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec4_outer, vec4_mul, vec4_dot};
+/// use stones::matrix::mat4_transform_vec;
+///
+/// let (a, b, c) = ([1, 2, 3, 4], [4, -5, 6, 0], [-1, 2, 7, 3]);
+/// assert_eq!(vec4_outer(a, b)[4..8], [8, -10, 12, 0]);
+/// assert_eq!(mat4_transform_vec(vec4_outer(a, b), c), vec4_mul(a, vec4_dot(b, c)));
+/// ```
+pub fn vec4_outer<T>(lhs: Vector4<T>, rhs: Vector4<T>) -> Matrix4<T>
+    where T: Copy + Mul<Output=T>
+{
+    [
+        lhs[0] * rhs[0], lhs[0] * rhs[1], lhs[0] * rhs[2], lhs[0] * rhs[3],
+        lhs[1] * rhs[0], lhs[1] * rhs[1], lhs[1] * rhs[2], lhs[1] * rhs[3],
+        lhs[2] * rhs[0], lhs[2] * rhs[1], lhs[2] * rhs[2], lhs[2] * rhs[3],
+        lhs[3] * rhs[0], lhs[3] * rhs[1], lhs[3] * rhs[2], lhs[3] * rhs[3]
+    ]
+}
+
+/// Multiplies a row vector on the left of a 3x3 matrix, v * m
+///
+/// This is the row vector convention, the crate otherwise multiplies column vectors on the
+/// right of matrices as in `mat3_transform_vec`.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_transform_mat3;
+/// use stones::matrix::{mat3_transform_vec, mat3_transpose};
+///
+/// let m = [1, 2, 3,
+///          4, 5, 6,
+///          7, 8, 9];
+/// assert_eq!(vec3_transform_mat3([1, 0, -1], m), [-6, -6, -6]);
+/// assert_eq!(vec3_transform_mat3([1, 0, -1], m), mat3_transform_vec(mat3_transpose(m), [1, 0, -1]));
+/// ```
+pub fn vec3_transform_mat3<T>(v: Vector3<T>, m: Matrix3<T>) -> Vector3<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        v[0] * m[0] + v[1] * m[3] + v[2] * m[6],
+        v[0] * m[1] + v[1] * m[4] + v[2] * m[7],
+        v[0] * m[2] + v[1] * m[5] + v[2] * m[8]
+    ]
+}
+
+/// Multiplies a row vector on the left of a 4x4 matrix, v * m
+///
+/// This is the row vector convention used by DirectX style APIs, the crate otherwise
+/// multiplies column vectors on the right of matrices as in `mat4_transform_vec`. With row
+/// vectors, the translation is stored in the last row of the matrix.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec4_transform_mat4;
+/// use stones::matrix::{mat4_transform_vec, mat4_transpose, mat4_translation};
+///
+/// let m = [1, 2, 3, 4,
+///          5, 6, 7, 8,
+///          9, 10, 11, 12,
+///          13, 14, 15, 16];
+/// let v = [1, -1, 2, 3];
+/// assert_eq!(vec4_transform_mat4(v, m), [53, 58, 63, 68]);
+/// assert_eq!(vec4_transform_mat4(v, m), mat4_transform_vec(mat4_transpose(m), v));
+///
+/// // Row vector translation
+/// let translation = [1, 0, 0, 0,
+///                    0, 1, 0, 0,
+///                    0, 0, 1, 0,
+///                    5, -2, 3, 1];
+/// assert_eq!(translation, mat4_transpose(mat4_translation([5, -2, 3])));
+/// assert_eq!(vec4_transform_mat4([1, 1, 1, 1], translation), [6, -1, 4, 1]);
+/// ```
+pub fn vec4_transform_mat4<T>(v: Vector4<T>, m: Matrix4<T>) -> Vector4<T>
+    where T: Copy + Mul<Output=T> + Add<Output=T>
+{
+    [
+        v[0] * m[0] + v[1] * m[4] + v[2] * m[8] + v[3] * m[12],
+        v[0] * m[1] + v[1] * m[5] + v[2] * m[9] + v[3] * m[13],
+        v[0] * m[2] + v[1] * m[6] + v[2] * m[10] + v[3] * m[14],
+        v[0] * m[3] + v[1] * m[7] + v[2] * m[11] + v[3] * m[15]
+    ]
+}
+
+/// Applies a function to each component of a Vector2<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec2_map, vec2_mul};
+///
+/// assert_eq!(vec2_map([3, -4], |x| x * 2), vec2_mul([3, -4], 2));
+/// assert_eq!(vec2_map([0.5f32, -1.0], |x| (x > 0.0) as u8), [1, 0]);
+/// ```
+pub fn vec2_map<T, U, F>(v: Vector2<T>, f: F) -> Vector2<U>
+    where T: Copy, F: Fn(T) -> U
+{
+    [f(v[0]), f(v[1])]
+}
+
+/// Applies a function to each component of a Vector3<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_map, vec3_mul, vec3_abs};
+/// use stones::number_traits::Abs;
+///
+/// assert_eq!(vec3_map([3, -4, 5], |x| x * 2), vec3_mul([3, -4, 5], 2));
+/// assert_eq!(vec3_map([3, -4, 5], |x: i32| x.abs()), [3, 4, 5]);
+/// assert_eq!(vec3_map([3, -4, i32::MIN], Abs::abs), vec3_abs([3, -4, i32::MIN]));
+/// assert_eq!(vec3_map([-0.0, 2.5, -1.0], f64::abs), vec3_abs([-0.0, 2.5, -1.0]));
+///
+/// // Mask of the positive components
+/// assert_eq!(vec3_map([0.5f32, -1.0, 2.0], |x| (x > 0.0) as u8), [1, 0, 1]);
+/// ```
+pub fn vec3_map<T, U, F>(v: Vector3<T>, f: F) -> Vector3<U>
+    where T: Copy, F: Fn(T) -> U
+{
+    [f(v[0]), f(v[1]), f(v[2])]
+}
+
+/// Applies a function to each component of a Vector4<T>
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec4_map, vec4_mul};
+///
+/// assert_eq!(vec4_map([3, -4, 5, 0], |x| x * 2), vec4_mul([3, -4, 5, 0], 2));
+/// assert_eq!(vec4_map([0.5f32, -1.0, 2.0, 0.0], |x| (x > 0.0) as u8), [1, 0, 1, 0]);
+/// ```
+pub fn vec4_map<T, U, F>(v: Vector4<T>, f: F) -> Vector4<U>
+    where T: Copy, F: Fn(T) -> U
+{
+    [f(v[0]), f(v[1]), f(v[2]), f(v[3])]
+}
+
+/// Combines the components of two Vector2<T> pairwise using a function
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec2_zip_with, vec2_add, vec2_sub};
+///
+/// let (v1, v2) = ([5, 3], [12, -8]);
+/// assert_eq!(vec2_zip_with(v1, v2, |a, b| a + b), vec2_add(v1, v2));
+/// assert_eq!(vec2_zip_with(v1, v2, |a, b| a - b), vec2_sub(v1, v2));
+/// assert_eq!(vec2_zip_with(v1, v2, |a, b| a < b), [true, false]);
+/// ```
+pub fn vec2_zip_with<T, U, F>(lhs: Vector2<T>, rhs: Vector2<T>, f: F) -> Vector2<U>
+    where T: Copy, F: Fn(T, T) -> U
+{
+    [f(lhs[0], rhs[0]), f(lhs[1], rhs[1])]
+}
+
+/// Combines the components of two Vector3<T> pairwise using a function
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_zip_with, vec3_add, vec3_sub, vec3_hadamard, vec3_min, vec3_max};
+///
+/// let (v1, v2) = ([5, 3, 7], [12, -8, -2]);
+/// assert_eq!(vec3_zip_with(v1, v2, |a, b| a + b), vec3_add(v1, v2));
+/// assert_eq!(vec3_zip_with(v1, v2, |a, b| a - b), vec3_sub(v1, v2));
+/// assert_eq!(vec3_zip_with(v1, v2, |a, b| a * b), vec3_hadamard(v1, v2));
+/// assert_eq!(vec3_zip_with(v1, v2, std::cmp::max), vec3_max(v1, v2));
+/// assert_eq!(vec3_zip_with(v1, v2, std::cmp::min), vec3_min(v1, v2));
+///
+/// // The float min and max ignore NaN like the dedicated functions
+/// let (v1, v2) = ([f32::NAN, 3.0, -1.0], [2.0, f32::NAN, 0.5]);
+/// assert_eq!(vec3_zip_with(v1, v2, f32::min), vec3_min(v1, v2));
+/// assert_eq!(vec3_zip_with(v1, v2, f32::max), vec3_max(v1, v2));
+/// ```
+pub fn vec3_zip_with<T, U, F>(lhs: Vector3<T>, rhs: Vector3<T>, f: F) -> Vector3<U>
+    where T: Copy, F: Fn(T, T) -> U
+{
+    [f(lhs[0], rhs[0]), f(lhs[1], rhs[1]), f(lhs[2], rhs[2])]
+}
+
+/// Combines the components of two Vector4<T> pairwise using a function
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec4_zip_with, vec4_add, vec4_sub};
+///
+/// let (v1, v2) = ([5, 3, 7, 1], [12, -8, -2, 1]);
+/// assert_eq!(vec4_zip_with(v1, v2, |a, b| a + b), vec4_add(v1, v2));
+/// assert_eq!(vec4_zip_with(v1, v2, |a, b| a - b), vec4_sub(v1, v2));
+/// assert_eq!(vec4_zip_with(v1, v2, std::cmp::min), [5, -8, -2, 1]);
+/// ```
+pub fn vec4_zip_with<T, U, F>(lhs: Vector4<T>, rhs: Vector4<T>, f: F) -> Vector4<U>
+    where T: Copy, F: Fn(T, T) -> U
+{
+    [f(lhs[0], rhs[0]), f(lhs[1], rhs[1]), f(lhs[2], rhs[2]), f(lhs[3], rhs[3])]
+}
+
+/// Folds the components of a Vector2<T> from first to last
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec2_fold, vec2_zip_with, vec2_dot};
+///
+/// assert_eq!(vec2_fold([3, -4], 0, |acc, x| acc + x), -1);
+/// assert_eq!(vec2_fold([3, -4], 1, |acc, x| acc * x), -12);
+///
+/// let (v1, v2) = ([5, 3], [12, -8]);
+/// assert_eq!(vec2_fold(vec2_zip_with(v1, v2, |a, b| a * b), 0, |acc, x| acc + x), vec2_dot(v1, v2));
+/// ```
+pub fn vec2_fold<T, A, F>(v: Vector2<T>, init: A, f: F) -> A
+    where T: Copy, F: Fn(A, T) -> A
+{
+    f(f(init, v[0]), v[1])
+}
+
+/// Folds the components of a Vector3<T> from first to last
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_fold, vec3_zip_with, vec3_dot};
+///
+/// assert_eq!(vec3_fold([3, -4, 5], 0, |acc, x| acc + x), 4);
+/// assert_eq!(vec3_fold([3, -4, 5], 1, |acc, x| acc * x), -60);
+/// assert_eq!(vec3_fold([2.0f32, -1.0, 0.5], f32::MIN, f32::max), 2.0);
+///
+/// let (v1, v2) = ([5, 3, 7], [12, -8, -2]);
+/// assert_eq!(vec3_fold(vec3_zip_with(v1, v2, |a, b| a * b), 0, |acc, x| acc + x), vec3_dot(v1, v2));
+/// ```
+pub fn vec3_fold<T, A, F>(v: Vector3<T>, init: A, f: F) -> A
+    where T: Copy, F: Fn(A, T) -> A
+{
+    f(f(f(init, v[0]), v[1]), v[2])
+}
+
+/// Folds the components of a Vector4<T> from first to last
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec4_fold, vec4_zip_with, vec4_dot};
+///
+/// assert_eq!(vec4_fold([3, -4, 5, 2], 0, |acc, x| acc + x), 6);
+/// assert_eq!(vec4_fold([3, -4, 5, 2], 1, |acc, x| acc * x), -120);
+///
+/// let (v1, v2) = ([5, 3, 7, 1], [12, -8, -2, 1]);
+/// assert_eq!(vec4_fold(vec4_zip_with(v1, v2, |a, b| a * b), 0, |acc, x| acc + x), vec4_dot(v1, v2));
+/// ```
+pub fn vec4_fold<T, A, F>(v: Vector4<T>, init: A, f: F) -> A
+    where T: Copy, F: Fn(A, T) -> A
+{
+    f(f(f(f(init, v[0]), v[1]), v[2]), v[3])
+}
+
+/// Compares two Vector3<T> componentwise, returning whether lhs < rhs for each component
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_less_than;
+///
+/// assert_eq!(vec3_less_than([1, 5, 3], [2, 5, 0]), [true, false, false]);
+/// assert_eq!(vec3_less_than([0.0, f32::NAN, -1.0], [1.0, 1.0, 1.0]), [true, false, true]);
+/// ```
+pub fn vec3_less_than<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> [bool; 3]
+    where T: Copy + PartialOrd
+{
+    vec3_zip_with(lhs, rhs, |a, b| a < b)
+}
+
+/// Compares two Vector3<T> componentwise, returning whether lhs >= rhs for each component
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_greater_equal;
+///
+/// assert_eq!(vec3_greater_equal([1, 5, 3], [2, 5, 0]), [false, true, true]);
+/// assert_eq!(vec3_greater_equal([0.0, f32::NAN, 1.0], [1.0, 1.0, 1.0]), [false, false, true]);
+/// ```
+pub fn vec3_greater_equal<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> [bool; 3]
+    where T: Copy + PartialOrd
+{
+    vec3_zip_with(lhs, rhs, |a, b| a >= b)
+}
+
+/// Compares two Vector3<T> componentwise, returning whether lhs == rhs for each component
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_eq_mask;
+///
+/// assert_eq!(vec3_eq_mask([1, 5, 3], [2, 5, 0]), [false, true, false]);
+/// assert_eq!(vec3_eq_mask([0.0, f32::NAN, -0.0], [0.0, f32::NAN, 0.0]), [true, false, true]);
+/// ```
+pub fn vec3_eq_mask<T>(lhs: Vector3<T>, rhs: Vector3<T>) -> [bool; 3]
+    where T: Copy + PartialEq
+{
+    vec3_zip_with(lhs, rhs, |a, b| a == b)
+}
+
+/// Selects the components of if_true where the mask is true and those of if_false elsewhere
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_select, vec3_less_than};
+///
+/// assert_eq!(vec3_select([true, false, true], [1, 2, 3], [4, 5, 6]), [1, 5, 3]);
+/// assert_eq!(vec3_select([false; 3], [1, 2, 3], [4, 5, 6]), [4, 5, 6]);
+/// assert_eq!(vec3_select([true; 3], [1, 2, 3], [4, 5, 6]), [1, 2, 3]);
+/// ```
+///
+/// Porting GLSL
+/// ```
+/// use stones::vector::{vec3_select, vec3_less_than, vec3_greater_equal, mask_any};
+///
+/// // vec3 step(vec3 edge, vec3 x) {
+/// //     return mix(vec3(1.0), vec3(0.0), lessThan(x, edge));
+/// // }
+/// let step = |edge: [f32; 3], x: [f32; 3]| vec3_select(vec3_less_than(x, edge), [0.0; 3], [1.0; 3]);
+/// assert_eq!(step([0.5; 3], [0.2, 0.5, 0.9]), [0.0, 1.0, 1.0]);
+///
+/// // bool outside = any(lessThan(p, box_min)) || any(greaterThanEqual(p, box_max));
+/// let outside = |p: [f32; 3]| mask_any(vec3_less_than(p, [0.0; 3])) || mask_any(vec3_greater_equal(p, [1.0; 3]));
+/// assert!(!outside([0.5, 0.0, 0.25]));
+/// assert!(outside([0.5, -0.1, 0.25]));
+/// assert!(outside([0.5, 0.5, 1.0]));
+/// ```
+pub fn vec3_select<T>(mask: [bool; 3], if_true: Vector3<T>, if_false: Vector3<T>) -> Vector3<T>
+    where T: Copy
+{
+    [
+        if mask[0] { if_true[0] } else { if_false[0] },
+        if mask[1] { if_true[1] } else { if_false[1] },
+        if mask[2] { if_true[2] } else { if_false[2] }
+    ]
+}
+
+/// Returns whether any component of a mask is true
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::mask_any;
+///
+/// assert!(mask_any([false, true, false]));
+/// assert!(mask_any([true; 3]));
+/// assert!(!mask_any([false; 3]));
+/// ```
+pub fn mask_any(mask: [bool; 3]) -> bool {
+    mask[0] || mask[1] || mask[2]
+}
+
+/// Returns whether all the components of a mask are true
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::mask_all;
+///
+/// assert!(mask_all([true; 3]));
+/// assert!(!mask_all([true, false, true]));
+/// assert!(!mask_all([false; 3]));
+/// ```
+pub fn mask_all(mask: [bool; 3]) -> bool {
+    mask[0] && mask[1] && mask[2]
+}
+
+/// Computes the componentwise mean and variance of a set of vectors
+///
+/// The variance is the population variance, i.e. normalized by the number of vectors. The
+/// values are accumulated in f64 using Welford's algorithm, which keeps f32 inputs accurate
+/// for large counts. Returns None if the slice is empty.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_mean_and_variance;
+///
+/// let points = [[1.0, 0.0, 5.0], [3.0, 0.0, 5.0], [5.0, 6.0, 5.0], [7.0, 2.0, 5.0]];
+/// assert_eq!(vec3_mean_and_variance(&points), Some(([4.0, 2.0, 5.0], [5.0, 6.0, 0.0])));
+///
+/// assert_eq!(vec3_mean_and_variance(&[[1.0, 2.0, 3.0]]), Some(([1.0, 2.0, 3.0], [0.0; 3])));
+/// assert_eq!(vec3_mean_and_variance::<f64>(&[]), None);
+/// ```
+///
+/// Large counts
+/// ```
+/// use stones::vector::vec3_mean_and_variance;
+///
+/// let points: Vec<[f32; 3]> = (0..4000).map(|i| {
+///     let offset = if i % 2 == 0 { 0.5 } else { -0.5 };
+///     [1000.1 + offset, 0.1, -3.3 - offset]
+/// }).collect();
+///
+/// // A naive f32 computation loses the variance to cancellation
+/// let count = points.len() as f32;
+/// let naive_mean = points.iter().map(|p| p[0]).sum::<f32>() / count;
+/// let naive_variance = points.iter().map(|p| p[0] * p[0]).sum::<f32>() / count - naive_mean * naive_mean;
+/// assert!((naive_variance - 0.25).abs() > 1.0);
+///
+/// let (mean, variance) = vec3_mean_and_variance(&points).unwrap();
+/// assert_eq!(mean, [1000.1, 0.1, -3.3]);
+/// assert!((variance[0] - 0.25).abs() < 1e-6);
+/// assert_eq!(variance[1], 0.0);
+/// assert!((variance[2] - 0.25).abs() < 1e-6);
+/// ```
+pub fn vec3_mean_and_variance<T>(points: &[Vector3<T>]) -> Option<(Vector3<T>, Vector3<T>)>
+    where T: Float
+{
+    if points.is_empty() {
+        return None;
+    }
+
+    let mut mean = [0.0f64; 3];
+    let mut squared_deviations = [0.0f64; 3];
+    for (count, point) in points.iter().enumerate() {
+        let count = (count + 1) as f64;
+        for i in 0..3 {
+            let value = point[i].to_f64();
+            let delta = value - mean[i];
+            mean[i] += delta / count;
+            squared_deviations[i] += delta * (value - mean[i]);
+        }
+    }
+
+    let count = points.len() as f64;
+    Some((
+        vec3_map(mean, T::from_f64),
+        vec3_map(squared_deviations, |s| T::from_f64(s / count))
+    ))
+}
+
+/// Computes the componentwise minimum and maximum of a set of vectors
+///
+/// Returns None if the slice is empty.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_min_max;
+///
+/// let points = [[1, 0, 5], [3, -2, 5], [-5, 6, 5], [7, 2, 4]];
+/// assert_eq!(vec3_min_max(&points), Some(([-5, -2, 4], [7, 6, 5])));
+///
+/// assert_eq!(vec3_min_max(&[[1.0, 2.0, 3.0]]), Some(([1.0, 2.0, 3.0], [1.0, 2.0, 3.0])));
+/// assert_eq!(vec3_min_max::<f64>(&[]), None);
+/// ```
+pub fn vec3_min_max<T>(points: &[Vector3<T>]) -> Option<(Vector3<T>, Vector3<T>)>
+    where T: Copy + PartialOrd
+{
+    let first = *points.first()?;
+    Some(points[1..].iter().fold((first, first), |(min, max), point| (
+        vec3_zip_with(min, *point, |a, b| if b < a { b } else { a }),
+        vec3_zip_with(max, *point, |a, b| if b > a { b } else { a })
+    )))
+}
+
+/// Quantizes a position within known bounds to `bits_per_axis` bits per component
+///
+/// Each component is clamped to [min, max] then mapped to an integer in [0, 2^bits - 1]. The
+/// x component is stored in the highest bits and z in the lowest. The bounds are inclusive
+/// and reconstructed exactly.
+///
+/// # Panics
+///
+/// Panics if `bits_per_axis` isn't in [1, 21].
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::{vec3_dequantize_range, vec3_quantize_range};
+///
+/// let (min, max) = ([-10.0, 0.0, 0.0], [10.0, 5.0, 15.0]);
+/// assert_eq!(vec3_quantize_range([-10.0, 5.0, 8.0], min, max, 4), 0x0f8);
+///
+/// // Out of range components are clamped
+/// let quantized = vec3_quantize_range([-20.0, 7.5, 15.0], min, max, 16);
+/// assert_eq!(vec3_dequantize_range(quantized, min, max, 16), [-10.0, 5.0, 15.0]);
+/// ```
+///
+/// Round-trip error
+/// ```
+/// use stones::vector::{vec3_dequantize_range, vec3_quantize_range};
+///
+/// let (min, max) = ([-100.0f32, -20.0, 0.0], [100.0, 20.0, 50.0]);
+/// let bits = 12;
+/// let steps = ((1 << bits) - 1) as f32;
+///
+/// let mut seed = 3u64;
+/// let mut random = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 40) as f32 / (1u64 << 24) as f32
+/// };
+///
+/// for _ in 0..10000 {
+///     let v = [min[0] + random() * (max[0] - min[0]),
+///              min[1] + random() * (max[1] - min[1]),
+///              min[2] + random() * (max[2] - min[2])];
+///     let reconstructed = vec3_dequantize_range(vec3_quantize_range(v, min, max, bits), min, max, bits);
+///     for i in 0..3 {
+///         let step = (max[i] - min[i]) / steps;
+///         assert!((reconstructed[i] - v[i]).abs() <= step);
+///     }
+/// }
+/// ```
+pub fn vec3_quantize_range(v: Vector3f, min: Vector3f, max: Vector3f, bits_per_axis: u32) -> u64 {
+    assert!((1..=21).contains(&bits_per_axis), "bits_per_axis must be in [1, 21]");
+
+    let steps = ((1u64 << bits_per_axis) - 1) as f64;
+    (0..3).fold(0, |quantized, i| {
+        let range = max[i] as f64 - min[i] as f64;
+        let normalized = if range > 0.0 {
+            ((v[i] as f64 - min[i] as f64) / range).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (quantized << bits_per_axis) | (normalized * steps).round() as u64
+    })
+}
+
+/// Reconstructs a position quantized by `vec3_quantize_range`
+///
+/// # Panics
+///
+/// Panics if `bits_per_axis` isn't in [1, 21].
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_dequantize_range;
+///
+/// let (min, max) = ([-10.0, 0.0, 0.0], [10.0, 5.0, 15.0]);
+/// assert_eq!(vec3_dequantize_range(0x0f8, min, max, 4), [-10.0, 5.0, 8.0]);
+/// ```
+pub fn vec3_dequantize_range(quantized: u64, min: Vector3f, max: Vector3f, bits_per_axis: u32) -> Vector3f {
+    assert!((1..=21).contains(&bits_per_axis), "bits_per_axis must be in [1, 21]");
+
+    let mask = (1u64 << bits_per_axis) - 1;
+    let steps = mask as f64;
+    let mut v = [0.0; 3];
+    for (i, component) in v.iter_mut().enumerate() {
+        let shift = bits_per_axis * (2 - i as u32);
+        let normalized = ((quantized >> shift) & mask) as f64 / steps;
+        *component = (min[i] as f64 + normalized * (max[i] as f64 - min[i] as f64)) as f32;
+    }
+
+    v
+}
+
+/// Converts a single precision 3D vector to double precision
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_to_f64;
+///
+/// assert_eq!(vec3_to_f64([1.5, -2.0, 0.1]), [1.5, -2.0, 0.1f32 as f64]);
+/// ```
+pub fn vec3_to_f64(v: Vector3f) -> Vector3d {
+    [v[0] as f64, v[1] as f64, v[2] as f64]
+}
+
+/// Converts a double precision 3D vector to single precision relatively to an origin
+///
+/// The vector is rebased around the origin before being narrowed, which keeps the precision
+/// of large world coordinates close to the origin.
+///
+/// # Examples
+///
+/// ```
+/// use stones::vector::vec3_to_f32_relative_to;
+///
+/// // A point 1e9 units away from the world origin, near a local origin
+/// let origin = [1e9, -1e9, 5e8];
+/// let point = [1e9 + 12.345, -1e9 - 0.006, 5e8 + 0.25];
+/// let local = vec3_to_f32_relative_to(point, origin);
+/// for i in 0..3 {
+///     assert!((local[i] as f64 + origin[i] - point[i]).abs() < 0.01);
+/// }
+///
+/// // Narrowing directly loses several units
+/// assert!(((point[0] as f32) as f64 - point[0]).abs() > 1.0);
+/// ```
+pub fn vec3_to_f32_relative_to(v: Vector3d, origin: Vector3d) -> Vector3f {
+    [(v[0] - origin[0]) as f32, (v[1] - origin[1]) as f32, (v[2] - origin[2]) as f32]
+}
+
+
+fn dot_product<T>(lhs: std::slice::Iter<T>, rhs: std::slice::Iter<T>) -> T 
+    where T: Zero + Copy + Mul<Output=T> + Add<Output=T>
+{
+    lhs.zip(rhs)
+        .fold(T::zero(), |acc, (&a, &b)| acc + a * b)
+}
+
+fn clamp<T>(value: T, min: T, max: T) -> T
+    where T: PartialOrd
+{
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+/// Returns the smallest value, or the other one if a value is unordered like NaN
+fn min<T>(lhs: T, rhs: T) -> T
+    where T: PartialOrd
+{
+    if rhs < lhs || is_unordered(&lhs) { rhs } else { lhs }
+}
+
+/// Returns the largest value, or the other one if a value is unordered like NaN
+fn max<T>(lhs: T, rhs: T) -> T
+    where T: PartialOrd
+{
+    if rhs > lhs || is_unordered(&lhs) { rhs } else { lhs }
+}
+
+fn is_unordered<T>(value: &T) -> bool
+    where T: PartialOrd
+{
+    value.partial_cmp(value).is_none()
 }